@@ -0,0 +1,154 @@
+//! Line-delimited JSON-RPC over stdio.
+//!
+//! Each line on stdin is a request `{ "id", "method", "params" }`; each
+//! response is written as one line `{ "id", "result" }` or `{ "id", "error" }`
+//! on stdout. Methods call the same engine functions as the Tauri commands
+//! of the same name, so editor extensions can scan and clean without the UI.
+
+use cache_cleaner_lib::cache::{self, audit::AuditEntry, CacheType, DeleteMode};
+use cache_cleaner_lib::utils;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufRead, Write};
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ScanCachesParams {
+    #[serde(default)]
+    force: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct CacheTypeParams {
+    cache_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CleanCacheParams {
+    cache_type: String,
+    #[serde(default)]
+    mode: DeleteMode,
+    #[serde(default)]
+    protect_recent_minutes: Option<u64>,
+}
+
+fn main() {
+    cache::guard::init_allow_root(std::env::args());
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("failed to start tokio runtime");
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match serde_json::from_str::<RpcRequest>(&line) {
+            Ok(request) => {
+                let id = request.id.clone();
+                respond(id, runtime.block_on(dispatch(request)))
+            }
+            Err(e) => respond(Value::Null, Err(format!("Invalid request: {}", e))),
+        };
+        let written = serde_json::to_string(&response)
+            .map_err(std::io::Error::other)
+            .and_then(|json| writeln!(stdout, "{}", json))
+            .and_then(|_| stdout.flush());
+        if written.is_err() {
+            break;
+        }
+    }
+    cache::audit::flush();
+}
+
+fn respond(id: Value, outcome: Result<Value, String>) -> RpcResponse {
+    match outcome {
+        Ok(result) => RpcResponse {
+            id,
+            result: Some(result),
+            error: None,
+        },
+        Err(error) => RpcResponse {
+            id,
+            result: None,
+            error: Some(error),
+        },
+    }
+}
+
+async fn dispatch(request: RpcRequest) -> Result<Value, String> {
+    match request.method.as_str() {
+        "scan_caches" => {
+            let params: ScanCachesParams = params_or_default(request.params)?;
+            to_value(cache::scan_results::cached_scan_all(params.force).await)
+        }
+        "get_cache_size" => {
+            let params: CacheTypeParams = params(request.params)?;
+            let ct = CacheType::from_str(&params.cache_type)?;
+            to_value(cache::scanner::get_size(&ct).await.map_err(|e| e.to_string())?)
+        }
+        "clean_cache" => {
+            let params: CleanCacheParams = params(request.params)?;
+            let _lock = cache::operation_lock::exclusive().await;
+            let ct = CacheType::from_str(&params.cache_type)?;
+            let dry_run = params.mode.is_preview();
+            let result = cache::cleaner::clean_protecting_recent(&ct, dry_run, params.protect_recent_minutes)
+                .await
+                .map_err(|e| e.to_string())?;
+            let paths = cache::scanner::get_cache_path(&ct)
+                .map(|path| vec![path.display().to_string()])
+                .unwrap_or_default();
+            cache::audit::record(AuditEntry::from_clean(&result, paths));
+            if !dry_run {
+                cache::scan_results::invalidate_scan_cache().await;
+            }
+            to_value(result)
+        }
+        "scan_docker_resources" => {
+            to_value(cache::docker::scan_docker_resources().await.map_err(|e| e.to_string())?)
+        }
+        "get_disk_usage" => to_value(utils::disk::get_disk_usage().map_err(|e| e.to_string())?),
+        "get_api_version" => to_value(cache_cleaner_lib::API_VERSION),
+        other => Err(format!("Unknown method: {}", other)),
+    }
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, String> {
+    serde_json::from_value(params).map_err(|e| format!("Invalid params: {}", e))
+}
+
+/// Like `params`, treating missing params as all defaults
+fn params_or_default<T: DeserializeOwned + Default>(value: Value) -> Result<T, String> {
+    if value.is_null() {
+        Ok(T::default())
+    } else {
+        params(value)
+    }
+}
+
+fn to_value(value: impl Serialize) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|e| e.to_string())
+}
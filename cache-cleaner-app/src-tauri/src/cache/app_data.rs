@@ -0,0 +1,139 @@
+//! The app's own data in `~/.cache-cleaner/`, or `CACHE_CLEANER_DATA_DIR`.
+//!
+//! Config, the size index, trash and anything else the app writes live
+//! there. Users can see what it costs and reset it.
+
+use super::guard;
+use crate::utils::filesystem;
+use crate::utils::home::app_data_dir;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppDataEntry {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppFootprint {
+    pub data_dir: String,
+    pub total_bytes: u64,
+    /// Largest first
+    pub entries: Vec<AppDataEntry>,
+}
+
+fn data_dir() -> Result<PathBuf> {
+    Ok(app_data_dir()?)
+}
+
+/// Size of every file and folder the app keeps in its data directory
+pub fn get_app_footprint() -> Result<AppFootprint> {
+    app_footprint_in(&data_dir()?)
+}
+
+/// Delete everything in the app's data directory
+pub fn reset_app_data() -> Result<()> {
+    guard::ensure_deletion_enabled()?;
+    reset_app_data_in(&data_dir()?)
+}
+
+fn app_footprint_in(dir: &Path) -> Result<AppFootprint> {
+    let mut entries = Vec::new();
+    if dir.exists() {
+        for entry in std::fs::read_dir(dir)?.flatten() {
+            let path = entry.path();
+            let is_dir = path.is_dir();
+            let size_bytes = if is_dir {
+                filesystem::calculate_dir_size_sync(&path).unwrap_or(0)
+            } else {
+                filesystem::calculate_file_size_sync(&path).unwrap_or(0)
+            };
+            entries.push(AppDataEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: path.display().to_string(),
+                size_bytes,
+                is_dir,
+            });
+        }
+    }
+
+    entries.sort_by_key(|entry| Reverse(entry.size_bytes));
+    Ok(AppFootprint {
+        data_dir: dir.display().to_string(),
+        total_bytes: entries.iter().map(|e| e.size_bytes).sum(),
+        entries,
+    })
+}
+
+fn reset_app_data_in(dir: &Path) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    let mut errors = Vec::new();
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        let removed = if path.is_dir() {
+            filesystem::delete_dir_all(&path)
+        } else {
+            filesystem::delete_file(&path)
+        };
+        if let Err(e) = removed {
+            errors.push(format!("{}: {}", path.display(), e));
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Failed to reset app data: {}", errors.join("; ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_app_data() -> TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("config.json"), [b' '; 100]).unwrap();
+        fs::write(dir.path().join("stats.json"), [b' '; 20]).unwrap();
+        fs::create_dir_all(dir.path().join("trash/op-1/contents")).unwrap();
+        fs::write(dir.path().join("trash/op-1/contents/file"), [0u8; 300]).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_footprint_reports_sizes() {
+        let dir = create_app_data();
+        let footprint = app_footprint_in(dir.path()).unwrap();
+
+        assert_eq!(footprint.total_bytes, 420);
+        assert_eq!(footprint.entries.len(), 3);
+        assert_eq!(footprint.entries[0].name, "trash");
+        assert!(footprint.entries[0].is_dir);
+        assert_eq!(footprint.entries[0].size_bytes, 300);
+    }
+
+    #[test]
+    fn test_footprint_missing_dir() {
+        let footprint = app_footprint_in(Path::new("/nonexistent/.cache-cleaner")).unwrap();
+        assert_eq!(footprint.total_bytes, 0);
+        assert!(footprint.entries.is_empty());
+    }
+
+    #[test]
+    fn test_reset_everything() {
+        let dir = create_app_data();
+        reset_app_data_in(dir.path()).unwrap();
+
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,263 @@
+//! Append-only log of destructive operations in `~/.cache-cleaner/audit.jsonl`.
+//!
+//! Every clean or removal the user starts is recorded with `record`, one JSON
+//! object per line. Writes happen on a background thread so they never slow
+//! the operation down; `flush` waits for them and runs before the app exits.
+//! The log is rotated to `audit.jsonl.1` once it reaches `MAX_LOG_BYTES`.
+
+use super::CleanResult;
+use crate::utils::home::app_data_dir;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+const LOG_FILE: &str = "audit.jsonl";
+const ROTATED_LOG_FILE: &str = "audit.jsonl.1";
+
+/// Size at which the log is rotated; one previous file is kept
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Who started an operation; everything is user-initiated until cleans can be scheduled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    UserInitiated,
+    Scheduled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Seconds since epoch
+    pub ts: u64,
+    pub operation: String,
+    pub paths: Vec<String>,
+    pub freed_bytes: u64,
+    pub dry_run: bool,
+    /// Moved to the app trash rather than deleted
+    pub trashed: bool,
+    pub trigger: Trigger,
+}
+
+impl AuditEntry {
+    /// A user-initiated permanent deletion, timestamped now
+    pub fn new(operation: impl Into<String>, paths: Vec<String>, freed_bytes: u64, dry_run: bool) -> Self {
+        Self {
+            ts: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            operation: operation.into(),
+            paths,
+            freed_bytes,
+            dry_run,
+            trashed: false,
+            trigger: Trigger::UserInitiated,
+        }
+    }
+
+    pub fn from_clean(result: &CleanResult, paths: Vec<String>) -> Self {
+        Self::new(
+            format!("clean:{:?}", result.cache_type),
+            paths,
+            result.freed_bytes,
+            result.dry_run,
+        )
+    }
+
+    pub fn trashed(mut self, trashed: bool) -> Self {
+        self.trashed = trashed;
+        self
+    }
+}
+
+#[cfg_attr(test, allow(dead_code))]
+enum Message {
+    Entry(AuditEntry),
+    Flush(mpsc::Sender<()>),
+}
+
+static WRITER: OnceLock<Mutex<Sender<Message>>> = OnceLock::new();
+
+#[cfg(test)]
+thread_local! {
+    static LOG_FOR_THREAD: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+fn log_dir() -> Result<PathBuf> {
+    Ok(app_data_dir()?)
+}
+
+/// Queue an entry for the audit log without waiting for the write
+pub fn record(entry: AuditEntry) {
+    #[cfg(test)]
+    record_for_test(&entry);
+
+    #[cfg(not(test))]
+    if let Ok(sender) = writer().lock() {
+        let _ = sender.send(Message::Entry(entry));
+    }
+}
+
+/// Tests write synchronously to their own directory, never the user's log
+#[cfg(test)]
+fn record_for_test(entry: &AuditEntry) {
+    if let Some(dir) = LOG_FOR_THREAD.with(|dir| dir.borrow().clone()) {
+        if let Err(e) = append_entry(&dir, entry, MAX_LOG_BYTES) {
+            eprintln!("[Rust] Failed to write audit entry: {}", e);
+        }
+    }
+}
+
+/// Wait until every queued entry has been written
+pub fn flush() {
+    let Some(writer) = WRITER.get() else {
+        return;
+    };
+    let (done, wait) = mpsc::channel();
+    let sent = writer.lock().map(|sender| sender.send(Message::Flush(done)).is_ok());
+    if sent.unwrap_or(false) {
+        let _ = wait.recv();
+    }
+}
+
+#[cfg_attr(test, allow(dead_code))]
+fn writer() -> &'static Mutex<Sender<Message>> {
+    WRITER.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Message>();
+        std::thread::spawn(move || {
+            for message in receiver {
+                match message {
+                    Message::Entry(entry) => {
+                        let written = log_dir().and_then(|dir| append_entry(&dir, &entry, MAX_LOG_BYTES));
+                        if let Err(e) = written {
+                            eprintln!("[Rust] Failed to write audit entry: {}", e);
+                        }
+                    }
+                    Message::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        });
+        Mutex::new(sender)
+    })
+}
+
+/// The most recent `limit` entries, newest first
+pub fn get_audit_log(limit: usize) -> Result<Vec<AuditEntry>> {
+    flush();
+    read_entries(&log_dir()?, limit)
+}
+
+fn append_entry(dir: &Path, entry: &AuditEntry, max_bytes: u64) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let log = dir.join(LOG_FILE);
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    let current = std::fs::metadata(&log).map(|m| m.len()).unwrap_or(0);
+    if current > 0 && current + line.len() as u64 > max_bytes {
+        std::fs::rename(&log, dir.join(ROTATED_LOG_FILE))?;
+    }
+
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&log)?;
+    file.write_all(line.as_bytes())?;
+    file.flush()?;
+    Ok(())
+}
+
+fn read_entries(dir: &Path, limit: usize) -> Result<Vec<AuditEntry>> {
+    let mut entries = Vec::new();
+    // Newest file first; each file is oldest-to-newest
+    for name in [LOG_FILE, ROTATED_LOG_FILE] {
+        let Ok(file) = std::fs::File::open(dir.join(name)) else {
+            continue;
+        };
+        let mut lines: Vec<AuditEntry> = BufReader::new(file)
+            .lines()
+            .map_while(|line| line.ok())
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect();
+        lines.reverse();
+        entries.extend(lines);
+        if entries.len() >= limit {
+            break;
+        }
+    }
+    entries.truncate(limit);
+    Ok(entries)
+}
+
+/// Send this thread's audit entries to `dir` instead of dropping them
+#[cfg(test)]
+pub(crate) fn set_log_dir_for_thread(dir: Option<PathBuf>) {
+    LOG_FOR_THREAD.with(|log| *log.borrow_mut() = dir);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheType;
+    use crate::utils::filesystem;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    #[test]
+    fn test_clean_is_appended() {
+        let log_dir = create_test_dir();
+        let cache = create_test_dir();
+        fs::write(cache.path().join("a.bin"), [0u8; 100]).unwrap();
+        set_log_dir_for_thread(Some(log_dir.path().to_path_buf()));
+
+        let outcome = filesystem::empty_dir_measured(cache.path(), 100, 1, None);
+        let result = CleanResult::from_removal(CacheType::Npm, &outcome, "Freed 100 bytes".to_string());
+        record(AuditEntry::from_clean(&result, vec![cache.path().display().to_string()]));
+        set_log_dir_for_thread(None);
+
+        let entries = read_entries(log_dir.path(), 10).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].operation, "clean:Npm");
+        assert_eq!(entries[0].freed_bytes, 100);
+        assert!(!entries[0].dry_run);
+        assert_eq!(entries[0].trigger, Trigger::UserInitiated);
+    }
+
+    #[test]
+    fn test_dry_run_recorded_distinctly() {
+        let log_dir = create_test_dir();
+        set_log_dir_for_thread(Some(log_dir.path().to_path_buf()));
+        record(AuditEntry::new("remove_npm_caches", vec!["/a".to_string()], 10, true));
+        record(AuditEntry::new("remove_npm_caches", vec!["/a".to_string()], 10, false).trashed(true));
+        set_log_dir_for_thread(None);
+
+        let entries = read_entries(log_dir.path(), 10).unwrap();
+        assert_eq!(entries.len(), 2);
+        // Newest first
+        assert!(!entries[0].dry_run);
+        assert!(entries[0].trashed);
+        assert!(entries[1].dry_run);
+    }
+
+    #[test]
+    fn test_rotation_keeps_previous_file() {
+        let log_dir = create_test_dir();
+        let entry = AuditEntry::new("clean:Npm", vec!["/cache".to_string()], 1, false);
+        let line_len = serde_json::to_string(&entry).unwrap().len() as u64 + 1;
+        for _ in 0..5 {
+            append_entry(log_dir.path(), &entry, line_len * 2).unwrap();
+        }
+
+        assert!(log_dir.path().join(ROTATED_LOG_FILE).exists());
+        assert!(fs::metadata(log_dir.path().join(LOG_FILE)).unwrap().len() <= line_len * 2);
+        assert_eq!(read_entries(log_dir.path(), 10).unwrap().len(), 3);
+        assert_eq!(read_entries(log_dir.path(), 2).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,220 @@
+//! Named scan snapshots in `baselines/` of the app data directory, for growth trends.
+//!
+//! `save_scan_baseline` stores the current scan as `<name>.json`;
+//! `compare_to_baseline` scans again and reports how every cache type grew
+//! or shrank since, along with cache paths that appeared or disappeared.
+
+use super::{scanner, CacheInfo, CacheType};
+use crate::utils::home::app_data_dir;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanBaseline {
+    pub name: String,
+    /// Seconds since epoch
+    pub created_at: u64,
+    pub caches: Vec<CacheInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheTypeDelta {
+    pub cache_type: CacheType,
+    pub previous_bytes: u64,
+    pub current_bytes: u64,
+    /// Negative when the cache shrank
+    pub delta_bytes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanDelta {
+    pub baseline_name: String,
+    pub baseline_created_at: u64,
+    pub caches: Vec<CacheTypeDelta>,
+    /// Cache paths present now but not in the baseline
+    pub new_paths: Vec<String>,
+    /// Cache paths in the baseline that no longer exist
+    pub gone_paths: Vec<String>,
+}
+
+fn baselines_dir() -> Result<PathBuf> {
+    Ok(app_data_dir()?.join("baselines"))
+}
+
+/// Scan now and store the result under `name`, replacing any baseline of that name
+pub async fn save_scan_baseline(name: String) -> Result<ScanBaseline> {
+    let report = scanner::scan_all().await;
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+    save_baseline_in(&baselines_dir()?, &name, report.caches, now)
+}
+
+/// Scan now and compare against the baseline saved as `name`
+pub async fn compare_to_baseline(name: String) -> Result<ScanDelta> {
+    let baseline = load_baseline_in(&baselines_dir()?, &name)?;
+    let report = scanner::scan_all().await;
+    Ok(compute_delta(&baseline, &report.caches))
+}
+
+fn baseline_file(dir: &Path, name: &str) -> Result<PathBuf> {
+    // Names become file names, never paths
+    if name.is_empty() || name.contains('/') || name.contains('\\') || name.starts_with('.') {
+        return Err(anyhow!("Invalid baseline name: {}", name));
+    }
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+fn save_baseline_in(dir: &Path, name: &str, caches: Vec<CacheInfo>, now_secs: u64) -> Result<ScanBaseline> {
+    let file = baseline_file(dir, name)?;
+    let baseline = ScanBaseline {
+        name: name.to_string(),
+        created_at: now_secs,
+        caches,
+    };
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(file, serde_json::to_string_pretty(&baseline)?)?;
+    Ok(baseline)
+}
+
+fn load_baseline_in(dir: &Path, name: &str) -> Result<ScanBaseline> {
+    let file = baseline_file(dir, name)?;
+    let content = std::fs::read_to_string(&file).map_err(|_| anyhow!("No baseline named {}", name))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn compute_delta(baseline: &ScanBaseline, current: &[CacheInfo]) -> ScanDelta {
+    // Current types first in scan order, then types only the baseline had
+    let mut cache_types: Vec<&CacheType> = Vec::new();
+    for info in current.iter().chain(&baseline.caches) {
+        if !cache_types.contains(&&info.cache_type) {
+            cache_types.push(&info.cache_type);
+        }
+    }
+
+    let total = |caches: &[CacheInfo], cache_type: &CacheType| -> u64 {
+        caches
+            .iter()
+            .filter(|info| &info.cache_type == cache_type && info.exists)
+            .map(|info| info.size)
+            .sum()
+    };
+    let caches = cache_types
+        .into_iter()
+        .map(|cache_type| {
+            let previous_bytes = total(&baseline.caches, cache_type);
+            let current_bytes = total(current, cache_type);
+            CacheTypeDelta {
+                cache_type: cache_type.clone(),
+                previous_bytes,
+                current_bytes,
+                delta_bytes: current_bytes as i64 - previous_bytes as i64,
+            }
+        })
+        .collect();
+
+    let existing = |caches: &[CacheInfo]| -> BTreeSet<String> {
+        caches
+            .iter()
+            .filter(|info| info.exists)
+            .map(|info| info.path.clone())
+            .collect()
+    };
+    let before = existing(&baseline.caches);
+    let after = existing(current);
+
+    ScanDelta {
+        baseline_name: baseline.name.clone(),
+        baseline_created_at: baseline.created_at,
+        caches,
+        new_paths: after.difference(&before).cloned().collect(),
+        gone_paths: before.difference(&after).cloned().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn info(cache_type: CacheType, path: &str, size: u64) -> CacheInfo {
+        CacheInfo {
+            cache_type,
+            path: path.to_string(),
+            size,
+            exists: true,
+            item_count: 1,
+            size_margin: None,
+            note: None,
+        }
+    }
+
+    fn find(delta: &ScanDelta, cache_type: CacheType) -> &CacheTypeDelta {
+        delta.caches.iter().find(|d| d.cache_type == cache_type).unwrap()
+    }
+
+    #[test]
+    fn test_delta_against_saved_baseline() {
+        let dir = create_test_dir();
+        let fixture = vec![
+            info(CacheType::Npm, "/Users/test/.npm/_cacache", 1_000),
+            info(CacheType::Yarn, "/Users/test/Library/Caches/Yarn", 500),
+            info(CacheType::Pip, "/Users/test/Library/Caches/pip", 300),
+        ];
+        save_baseline_in(dir.path(), "before-upgrade", fixture, 1_700_000_000).unwrap();
+
+        let current = vec![
+            info(CacheType::Npm, "/Users/test/.npm/_cacache", 4_000),
+            info(CacheType::Yarn, "/Users/test/Library/Caches/Yarn", 200),
+            info(CacheType::Cargo, "/Users/test/.cargo/registry", 700),
+        ];
+        let baseline = load_baseline_in(dir.path(), "before-upgrade").unwrap();
+        let delta = compute_delta(&baseline, &current);
+
+        assert_eq!(delta.baseline_created_at, 1_700_000_000);
+        assert_eq!(find(&delta, CacheType::Npm).delta_bytes, 3_000);
+        assert_eq!(find(&delta, CacheType::Yarn).delta_bytes, -300);
+        assert_eq!(
+            find(&delta, CacheType::Cargo),
+            &CacheTypeDelta {
+                cache_type: CacheType::Cargo,
+                previous_bytes: 0,
+                current_bytes: 700,
+                delta_bytes: 700,
+            }
+        );
+        assert_eq!(find(&delta, CacheType::Pip).current_bytes, 0);
+        assert_eq!(delta.new_paths, vec!["/Users/test/.cargo/registry"]);
+        assert_eq!(delta.gone_paths, vec!["/Users/test/Library/Caches/pip"]);
+    }
+
+    #[test]
+    fn test_missing_caches_count_as_gone() {
+        let baseline = ScanBaseline {
+            name: "b".to_string(),
+            created_at: 0,
+            caches: vec![info(CacheType::Npm, "/npm", 100)],
+        };
+        let mut missing = info(CacheType::Npm, "/npm", 0);
+        missing.exists = false;
+
+        let delta = compute_delta(&baseline, &[missing]);
+        assert_eq!(find(&delta, CacheType::Npm).delta_bytes, -100);
+        assert_eq!(delta.gone_paths, vec!["/npm"]);
+        assert!(delta.new_paths.is_empty());
+    }
+
+    #[test]
+    fn test_baseline_names_are_not_paths() {
+        let dir = create_test_dir();
+        for name in ["", "../escape", ".hidden", "a/b"] {
+            assert!(save_baseline_in(dir.path(), name, Vec::new(), 0).is_err(), "{:?}", name);
+        }
+        assert!(load_baseline_in(dir.path(), "never-saved").is_err());
+    }
+}
@@ -1,9 +1,11 @@
 use crate::cache::{CacheInfo, CacheType, CleanResult};
 use crate::cache::paths::MacPaths;
+use crate::utils::filesystem;
 use std::fs;
+use std::time::Duration;
 
 pub fn get_safari_cache_info() -> CacheInfo {
-    let path = MacPaths::safari_cache();
+    let path = MacPaths::safari_cache().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = if path.exists() {
         match calculate_dir_size(&path) {
@@ -20,11 +22,13 @@ pub fn get_safari_cache_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
 pub fn get_firefox_cache_info() -> CacheInfo {
-    let path = MacPaths::firefox_profiles();
+    let path = MacPaths::firefox_profiles().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = if path.exists() {
         match calculate_dir_size(&path) {
@@ -41,11 +45,13 @@ pub fn get_firefox_cache_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
 pub fn get_arc_cache_info() -> CacheInfo {
-    let path = MacPaths::arc_cache();
+    let path = MacPaths::arc_cache().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = if path.exists() {
         match calculate_dir_size(&path) {
@@ -62,10 +68,12 @@ pub fn get_arc_cache_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
-pub fn clean_browser_cache(cache_type: CacheType, dry_run: bool) -> CleanResult {
+pub fn clean_browser_cache(cache_type: CacheType, dry_run: bool, protect_recent: Option<Duration>) -> CleanResult {
     let path = match cache_type {
         CacheType::Safari => MacPaths::safari_cache(),
         CacheType::Firefox => MacPaths::firefox_profiles(),
@@ -77,6 +85,19 @@ pub fn clean_browser_cache(cache_type: CacheType, dry_run: bool) -> CleanResult
             success: false,
             message: "Invalid browser cache type".to_string(),
             dry_run,
+            ..Default::default()
+        },
+    };
+    let path = match path {
+        Ok(p) => p,
+        Err(e) => return CleanResult {
+            cache_type,
+            freed_bytes: 0,
+            items_removed: 0,
+            success: false,
+            message: e.to_string(),
+            dry_run,
+            ..Default::default()
         },
     };
 
@@ -88,6 +109,7 @@ pub fn clean_browser_cache(cache_type: CacheType, dry_run: bool) -> CleanResult
             success: true,
             message: "Cache directory does not exist".to_string(),
             dry_run,
+            ..Default::default()
         };
     }
 
@@ -100,6 +122,7 @@ pub fn clean_browser_cache(cache_type: CacheType, dry_run: bool) -> CleanResult
             success: false,
             message: format!("Failed to calculate size: {}", e),
             dry_run,
+            ..Default::default()
         },
     };
 
@@ -111,27 +134,12 @@ pub fn clean_browser_cache(cache_type: CacheType, dry_run: bool) -> CleanResult
             success: true,
             message: format!("Would clean {} items ({} bytes)", items_before, size_before),
             dry_run,
+            ..Default::default()
         };
     }
 
-    match fs::remove_dir_all(&path) {
-        Ok(_) => CleanResult {
-            cache_type,
-            freed_bytes: size_before,
-            items_removed: items_before,
-            success: true,
-            message: format!("Successfully cleaned {} items", items_before),
-            dry_run,
-        },
-        Err(e) => CleanResult {
-            cache_type,
-            freed_bytes: 0,
-            items_removed: 0,
-            success: false,
-            message: format!("Failed to clean cache: {}", e),
-            dry_run,
-        },
-    }
+    let outcome = filesystem::empty_dir_measured(&path, size_before, items_before, protect_recent);
+    CleanResult::from_removal(cache_type, &outcome, format!("Successfully cleaned {} items", items_before))
 }
 
 fn calculate_dir_size(path: &std::path::Path) -> Result<(u64, usize), std::io::Error> {
@@ -0,0 +1,241 @@
+//! Chrome caches per profile.
+//!
+//! Chrome keeps one cache folder per profile under
+//! `~/Library/Caches/Google/Chrome/<profile>`, where the profile directory is
+//! `Default` or `Profile N`. The name shown in Chrome's profile picker is read
+//! from that profile's `Preferences` in Application Support, so the user can
+//! clear a work profile's cache without touching a personal one.
+
+use super::guard;
+use super::indexeddb::{last_used_profile, DEFAULT_PROFILE};
+use super::paths::MacPaths;
+use crate::utils::filesystem;
+use crate::utils::permissions;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChromeProfileCache {
+    /// Profile directory name, e.g. `Default` or `Profile 2`
+    pub profile: String,
+    /// Name shown in Chrome's profile picker, if `Preferences` has one
+    pub display_name: Option<String>,
+    pub path: String,
+    pub size_bytes: u64,
+    /// The profile Chrome opens on launch
+    pub is_last_used: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChromeProfileCleanResult {
+    pub profile: String,
+    pub total_freed_bytes: u64,
+    pub items_removed: usize,
+    pub success: bool,
+    pub message: String,
+    pub dry_run: bool,
+}
+
+fn chrome_support_dir() -> Result<PathBuf> {
+    Ok(MacPaths::home()?.join("Library/Application Support/Google/Chrome"))
+}
+
+/// Cache folder of every Chrome profile, largest first
+pub async fn scan_chrome_profiles() -> Result<Vec<ChromeProfileCache>> {
+    let cache_base = MacPaths::chrome_cache()?;
+    let support_base = chrome_support_dir()?;
+    tokio::task::spawn_blocking(move || Ok(scan_chrome_profiles_in(&cache_base, &support_base))).await?
+}
+
+/// Empty the cache folder of one profile. Refused while Chrome is running,
+/// since it would rebuild entries mid-removal; a dry run only measures.
+pub async fn clean_chrome_profile_cache(profile: String, dry_run: bool) -> Result<ChromeProfileCleanResult> {
+    if !dry_run {
+        guard::ensure_deletion_enabled()?;
+    }
+    let cache_base = MacPaths::chrome_cache()?;
+    let chrome_running = permissions::is_chrome_running().unwrap_or(false);
+    tokio::task::spawn_blocking(move || {
+        clean_chrome_profile_cache_in(&cache_base, &profile, dry_run, chrome_running)
+    })
+    .await?
+}
+
+fn is_profile_dir(name: &str) -> bool {
+    name == DEFAULT_PROFILE
+        || name
+            .strip_prefix("Profile ")
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn scan_chrome_profiles_in(cache_base: &Path, support_base: &Path) -> Vec<ChromeProfileCache> {
+    let Ok(entries) = std::fs::read_dir(cache_base) else {
+        return Vec::new();
+    };
+    let last_used = last_used_profile(support_base);
+
+    let mut profiles: Vec<ChromeProfileCache> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let profile = entry.file_name().to_string_lossy().to_string();
+            if !is_profile_dir(&profile) {
+                return None;
+            }
+            let path = entry.path();
+            Some(ChromeProfileCache {
+                display_name: profile_display_name(&support_base.join(&profile)),
+                size_bytes: filesystem::calculate_dir_size_sync(&path).unwrap_or(0),
+                path: path.display().to_string(),
+                is_last_used: profile == last_used,
+                profile,
+            })
+        })
+        .collect();
+
+    profiles.sort_by_key(|profile| Reverse(profile.size_bytes));
+    profiles
+}
+
+/// `profile.name` from the profile's `Preferences`
+fn profile_display_name(profile_dir: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(profile_dir.join("Preferences")).ok()?;
+    let prefs: serde_json::Value = serde_json::from_str(&content).ok()?;
+    prefs
+        .get("profile")?
+        .get("name")?
+        .as_str()
+        .filter(|name| !name.is_empty())
+        .map(|name| name.to_string())
+}
+
+fn clean_chrome_profile_cache_in(
+    cache_base: &Path,
+    profile: &str,
+    dry_run: bool,
+    chrome_running: bool,
+) -> Result<ChromeProfileCleanResult> {
+    // Profile names become a single path component, never a path
+    if !is_profile_dir(profile) {
+        return Err(anyhow!("Not a Chrome profile: {}", profile));
+    }
+    if chrome_running && !dry_run {
+        return Err(anyhow!("Quit Chrome before cleaning the cache of {}", profile));
+    }
+    let path = cache_base.join(profile);
+    if !path.is_dir() {
+        return Err(anyhow!("No cache found for Chrome profile {}", profile));
+    }
+
+    let size = filesystem::calculate_dir_size_sync(&path).unwrap_or(0);
+    let items = filesystem::count_items(&path).unwrap_or(0);
+    if dry_run || filesystem::skip_deletion() {
+        return Ok(ChromeProfileCleanResult {
+            profile: profile.to_string(),
+            total_freed_bytes: size,
+            items_removed: items,
+            success: true,
+            message: format!("Would remove {} item(s) from Chrome profile {}", items, profile),
+            dry_run: true,
+        });
+    }
+
+    guard::assert_safe_to_delete(&path)?;
+    let outcome = filesystem::empty_dir_measured(&path, size, items, None);
+    let message = if outcome.errors.is_empty() {
+        format!("Removed {} item(s) from Chrome profile {}", outcome.items_removed, profile)
+    } else {
+        format!(
+            "Removed {} item(s) from Chrome profile {}, {} errors: {}",
+            outcome.items_removed,
+            profile,
+            outcome.errors.len(),
+            outcome.errors.join("; ")
+        )
+    };
+
+    Ok(ChromeProfileCleanResult {
+        profile: profile.to_string(),
+        total_freed_bytes: outcome.freed_bytes,
+        items_removed: outcome.items_removed,
+        success: outcome.errors.is_empty(),
+        message,
+        dry_run: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    /// `Default` ("Personal", 100 bytes) and `Profile 1` ("Work", 400 bytes)
+    fn create_fixture(root: &Path) -> (PathBuf, PathBuf) {
+        let cache_base = root.join("Caches/Google/Chrome");
+        let support_base = root.join("Application Support/Google/Chrome");
+        for (profile, name, bytes) in [("Default", "Personal", 100), ("Profile 1", "Work", 400)] {
+            let cache = cache_base.join(profile).join("Cache/Cache_Data");
+            fs::create_dir_all(&cache).unwrap();
+            fs::write(cache.join("data_0"), vec![0u8; bytes]).unwrap();
+
+            let data = support_base.join(profile);
+            fs::create_dir_all(&data).unwrap();
+            fs::write(data.join("Preferences"), format!(r#"{{"profile": {{"name": "{}"}}}}"#, name)).unwrap();
+        }
+        fs::create_dir_all(cache_base.join("ShaderCache")).unwrap();
+        fs::write(support_base.join("Local State"), r#"{"profile": {"last_used": "Profile 1"}}"#).unwrap();
+        (cache_base, support_base)
+    }
+
+    #[test]
+    fn test_scan_lists_profiles_with_names() {
+        let root = create_test_dir();
+        let (cache_base, support_base) = create_fixture(root.path());
+
+        let profiles = scan_chrome_profiles_in(&cache_base, &support_base);
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].profile, "Profile 1");
+        assert_eq!(profiles[0].display_name.as_deref(), Some("Work"));
+        assert_eq!(profiles[0].size_bytes, 400);
+        assert!(profiles[0].is_last_used);
+        assert_eq!(profiles[1].profile, "Default");
+        assert_eq!(profiles[1].display_name.as_deref(), Some("Personal"));
+        assert!(!profiles[1].is_last_used);
+    }
+
+    #[test]
+    fn test_clean_targets_only_selected_profile() {
+        let root = create_test_dir();
+        let (cache_base, _) = create_fixture(root.path());
+
+        let dry = clean_chrome_profile_cache_in(&cache_base, "Profile 1", true, true).unwrap();
+        assert!(dry.dry_run);
+        assert_eq!(dry.total_freed_bytes, 400);
+        assert!(cache_base.join("Profile 1/Cache/Cache_Data/data_0").exists());
+
+        let result = clean_chrome_profile_cache_in(&cache_base, "Profile 1", false, false).unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.total_freed_bytes, 400);
+        assert!(!cache_base.join("Profile 1/Cache").exists());
+        assert!(cache_base.join("Default/Cache/Cache_Data/data_0").exists());
+    }
+
+    #[test]
+    fn test_clean_refuses_running_chrome_and_bad_names() {
+        let root = create_test_dir();
+        let (cache_base, _) = create_fixture(root.path());
+
+        assert!(clean_chrome_profile_cache_in(&cache_base, "Default", false, true).is_err());
+        for name in ["", "../Default", "ShaderCache", "Profile x", "Profile 1/Cache"] {
+            assert!(clean_chrome_profile_cache_in(&cache_base, name, false, false).is_err(), "{:?}", name);
+        }
+        assert!(cache_base.join("Default/Cache/Cache_Data/data_0").exists());
+    }
+}
@@ -1,43 +1,273 @@
-use super::{CacheType, CleanResult};
-use crate::cache::{browser_caches, dev_tools, package_managers};
-use crate::utils::filesystem;
+use super::config::AppConfig;
+use super::{CacheCategory, CacheType, CleanResult, CleanStrategy, Verbosity};
+use crate::cache::{browser_caches, cleanup_eta, conda, dev_tools, go_caches, guard, manifest, package_managers, python_caches, quicklook, scanner};
+use crate::cache::paths::MacPaths;
+use crate::events::{self, AppEvent, Throttle};
+use crate::utils::disk::{DiskStats, SystemDiskStats};
+use crate::utils::filesystem::{self, RemovalOutcome};
+use crate::utils::format::format_size;
+use crate::utils::home::require_home;
 use anyhow::Result;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// At most ~10 progress events per second reach the frontend
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
 
 pub async fn clean(cache_type: &CacheType, dry_run: bool) -> Result<CleanResult> {
+    clean_protecting_recent(cache_type, dry_run, None).await
+}
+
+/// Clean `cache_type`, keeping files modified within the last
+/// `protect_recent_minutes` (e.g. output of a build that is still running)
+pub async fn clean_protecting_recent(
+    cache_type: &CacheType,
+    dry_run: bool,
+    protect_recent_minutes: Option<u64>,
+) -> Result<CleanResult> {
+    if !dry_run {
+        guard::ensure_deletion_enabled()?;
+    }
+    let protect_recent = protect_recent_minutes.map(|minutes| Duration::from_secs(minutes * 60));
+    let files_in_use = files_in_use(cache_type).await;
+    let free_space = FreeSpaceCheck::start(dry_run);
+    let started = Instant::now();
+    let result = clean_type(cache_type, dry_run, protect_recent).await?;
+    record_throughput(&result, started.elapsed());
+    let result = free_space
+        .finish(result)
+        .with_files_in_use(files_in_use)
+        .with_verbosity(configured_verbosity())
+        .with_repopulation_hint();
+    if !result.dry_run {
+        events::bus().publish(AppEvent::CleanCompleted {
+            cache_type: format!("{:?}", result.cache_type),
+            freed_bytes: result.freed_bytes,
+            success: result.success,
+        });
+    }
+    Ok(result)
+}
+
+async fn clean_type(cache_type: &CacheType, dry_run: bool, protect_recent: Option<Duration>) -> Result<CleanResult> {
     match cache_type {
         // Browser caches
         CacheType::Safari | CacheType::Firefox | CacheType::Arc => {
-            Ok(browser_caches::clean_browser_cache(cache_type.clone(), dry_run))
+            Ok(browser_caches::clean_browser_cache(cache_type.clone(), dry_run, protect_recent))
         }
         
         // Package managers
         CacheType::Yarn | CacheType::Pnpm | CacheType::Pip | 
-        CacheType::CocoaPods | CacheType::Gradle | CacheType::Cargo => {
-            Ok(package_managers::clean_package_cache(cache_type.clone(), dry_run))
+        CacheType::CocoaPods | CacheType::Gradle => {
+            Ok(package_managers::clean_package_cache(cache_type.clone(), dry_run, protect_recent))
         }
         
+        // go clean when the toolchain exists, plain removal otherwise
+        CacheType::GoBuild | CacheType::GoModCache => {
+            go_caches::clean_go_cache(cache_type.clone(), dry_run, protect_recent)
+        }
+
+        // uv / poetry's own clean command when installed, plain removal otherwise
+        CacheType::Poetry | CacheType::Uv => {
+            python_caches::clean_python_cache(cache_type.clone(), dry_run, protect_recent)
+        }
+
+        // conda / mamba clean from the detected install, pruning pkgs otherwise
+        CacheType::Conda => conda::clean_conda_cache(dry_run, protect_recent),
+
         // Development tools
         CacheType::XcodeDerivedData | CacheType::XcodeArchives | CacheType::XcodeSimulators => {
-            Ok(dev_tools::clean_xcode_cache(cache_type.clone(), dry_run))
+            Ok(dev_tools::clean_xcode_cache(cache_type.clone(), dry_run, protect_recent))
+        }
+        
+        // Manifest-defined caches
+        CacheType::Manifest(id) => {
+            let entry = manifest::find_entry(id)
+                .ok_or_else(|| anyhow::anyhow!("Unknown manifest cache: {}", id))?;
+            manifest::clean_entry(entry, dry_run).await
         }
         
+        // Reset through qlmanage instead of deleting files
+        CacheType::QuickLook => quicklook::clean_quicklook_cache(dry_run).await,
+
         // Existing cache types
         CacheType::Cursor => {
-            clean_cursor_cache(dry_run).await
+            clean_cursor_cache(dry_run, protect_recent).await
         }
         CacheType::VSCode => {
-            clean_vscode_cache(dry_run).await
+            clean_vscode_cache(dry_run, protect_recent).await
         }
         
         _ => {
-            clean_directory_cache(cache_type, dry_run).await
+            clean_directory_cache(cache_type, dry_run, protect_recent).await
+        }
+    }
+}
+
+/// Clean `cache_type`, publishing `clean-progress` events as entries are removed.
+///
+/// Types that are not cleaned by emptying a directory, and dry runs, behave
+/// exactly like `clean`.
+pub async fn clean_with_progress(cache_type: &CacheType, dry_run: bool) -> Result<CleanResult> {
+    if !dry_run {
+        guard::ensure_deletion_enabled()?;
+    }
+    let path = match progress_target(cache_type)? {
+        Some(path) if !dry_run && path.exists() => path,
+        _ => return clean(cache_type, dry_run).await,
+    };
+
+    let files_in_use = files_in_use(cache_type).await;
+    let free_space = FreeSpaceCheck::start(dry_run);
+    let size_before = filesystem::calculate_dir_size(&path).await?;
+    let items_total = filesystem::count_items(&path)?;
+    let name = format!("{:?}", cache_type);
+
+    let progress_name = name.clone();
+    let started = Instant::now();
+    let outcome = tokio::task::spawn_blocking(move || {
+        let mut throttle = Throttle::new(PROGRESS_INTERVAL);
+        filesystem::empty_dir_with_progress(&path, size_before, items_total, None, |items_done, bytes_freed| {
+            // The last update always goes out so the bar reaches 100%
+            if throttle.ready() || items_done == items_total {
+                events::bus().publish(AppEvent::CleanProgress {
+                    cache_type: progress_name.clone(),
+                    items_done,
+                    items_total,
+                    bytes_freed,
+                });
+            }
+        })
+    })
+    .await?;
+
+    let message = format!("Freed {} ({} items)", format_size(outcome.freed_bytes), outcome.items_removed);
+    let result = CleanResult::from_removal(cache_type.clone(), &outcome, message);
+    record_throughput(&result, started.elapsed());
+    let result = free_space
+        .finish(result)
+        .with_files_in_use(files_in_use)
+        .with_verbosity(configured_verbosity())
+        .with_repopulation_hint();
+    events::bus().publish(AppEvent::CleanCompleted {
+        cache_type: name,
+        freed_bytes: result.freed_bytes,
+        success: result.success,
+    });
+    Ok(result)
+}
+
+/// Feed a real clean's deletion speed into the cleanup ETA model
+fn record_throughput(result: &CleanResult, elapsed: Duration) {
+    // Read-only runs only measure, which says nothing about deletion speed
+    if result.dry_run || !result.success || filesystem::skip_deletion() {
+        return;
+    }
+    if let Err(e) = cleanup_eta::record_clean(result.items_removed, elapsed) {
+        eprintln!("[Rust] failed to record clean throughput: {}", e);
+    }
+}
+
+fn configured_verbosity() -> Verbosity {
+    AppConfig::load().map(|config| config.verbosity).unwrap_or_default()
+}
+
+/// Processes holding files in the cache open, checked only when
+/// `check_open_files` is enabled since `lsof` walks the whole cache
+async fn files_in_use(cache_type: &CacheType) -> Vec<String> {
+    if !AppConfig::load().map(|config| config.check_open_files).unwrap_or(false) {
+        return Vec::new();
+    }
+    let Ok(path) = scanner::get_cache_path(cache_type) else {
+        return Vec::new();
+    };
+    tokio::task::spawn_blocking(move || filesystem::open_by_process(&path))
+        .await
+        .ok()
+        .and_then(|result| result.ok())
+        .unwrap_or_default()
+}
+
+/// Free space before a clean, to compare with afterwards. Off unless
+/// `verify_disk_space` is set, since each reading runs `diskutil`.
+struct FreeSpaceCheck {
+    stats: Option<Box<dyn DiskStats>>,
+    free_before: Option<u64>,
+}
+
+impl FreeSpaceCheck {
+    fn start(dry_run: bool) -> Self {
+        let enabled = !dry_run && AppConfig::load().map(|config| config.verify_disk_space).unwrap_or(false);
+        Self::with_stats(enabled.then(|| Box::new(SystemDiskStats) as Box<dyn DiskStats>))
+    }
+
+    fn with_stats(stats: Option<Box<dyn DiskStats>>) -> Self {
+        let free_before = stats.as_ref().and_then(|stats| stats.free_bytes().ok());
+        Self { stats, free_before }
+    }
+
+    /// `result` with the measured free-space delta, if both readings worked
+    fn finish(self, result: CleanResult) -> CleanResult {
+        let free_after = self.stats.as_ref().and_then(|stats| stats.free_bytes().ok());
+        match (self.free_before, free_after) {
+            (Some(before), Some(after)) => result.with_disk_delta(before, after),
+            _ => result,
         }
     }
 }
 
-async fn clean_cursor_cache(dry_run: bool) -> Result<CleanResult> {
-    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
-    let cursor_cache_paths = get_cursor_cache_paths(&home);
+/// Directory a cache type is cleaned by emptying, if it is cleaned that way
+fn progress_target(cache_type: &CacheType) -> Result<Option<PathBuf>> {
+    let home = require_home()?;
+    let root = match cache_type {
+        CacheType::Npm => home.join(".npm"),
+        CacheType::Chrome => home.join("Library/Caches/Google/Chrome"),
+        CacheType::CacheDir => home.join(".cache"),
+        CacheType::Cargo => home.join(".cargo"),
+        CacheType::Safari => MacPaths::safari_cache()?,
+        CacheType::Firefox => MacPaths::firefox_profiles()?,
+        CacheType::Arc => MacPaths::arc_cache()?,
+        CacheType::Yarn => MacPaths::yarn_cache()?,
+        CacheType::Pnpm => MacPaths::pnpm_cache()?,
+        CacheType::Pip => MacPaths::pip_cache()?,
+        CacheType::CocoaPods => MacPaths::cocoapods_cache()?,
+        CacheType::Gradle => MacPaths::gradle_cache()?,
+        CacheType::XcodeDerivedData => MacPaths::xcode_derived_data()?,
+        CacheType::XcodeArchives => MacPaths::xcode_archives()?,
+        _ => return Ok(None),
+    };
+
+    Ok(match cache_type.clean_strategy() {
+        CleanStrategy::RemoveContents => Some(root),
+        CleanStrategy::RemoveSubpath(subpath) => Some(root.join(subpath)),
+        CleanStrategy::RunCommand(_) => None,
+    })
+}
+
+/// Clean every candidate cache type in `category`, one result per type
+pub async fn clean_by_category(category: CacheCategory, dry_run: bool) -> Vec<CleanResult> {
+    let mut results = Vec::new();
+    for cache_type in scanner::candidate_types_in(category) {
+        let result = match clean(&cache_type, dry_run).await {
+            Ok(result) => result,
+            Err(e) => CleanResult {
+                cache_type,
+                freed_bytes: 0,
+                items_removed: 0,
+                success: false,
+                message: e.to_string(),
+                dry_run,
+                ..Default::default()
+            },
+        };
+        results.push(result);
+    }
+    results
+}
+
+async fn clean_cursor_cache(dry_run: bool, protect_recent: Option<Duration>) -> Result<CleanResult> {
+    let home = require_home()?;
+    let cursor_cache_paths = scanner::get_cursor_cache_paths(&home);
     
     let mut total_size = 0u64;
     let mut item_count = 0usize;
@@ -59,6 +289,7 @@ async fn clean_cursor_cache(dry_run: bool) -> Result<CleanResult> {
             success: true,
             message: "Cursor cache directories do not exist".to_string(),
             dry_run,
+            ..Default::default()
         });
     }
     
@@ -70,27 +301,25 @@ async fn clean_cursor_cache(dry_run: bool) -> Result<CleanResult> {
             success: true,
             message: format!("Would free {} bytes ({} items)", total_size, item_count),
             dry_run: true,
+            ..Default::default()
         });
     }
     
-    // Clean contents of each cache directory
+    // Clean contents of each cache directory, measuring what really went away
+    let mut outcome = RemovalOutcome::default();
     for path in &existing_paths {
-        filesystem::remove_dir_contents(path)?;
+        let size = filesystem::calculate_dir_size(path).await?;
+        let items = filesystem::count_items(path)?;
+        outcome.absorb(filesystem::empty_dir_measured(path, size, items, protect_recent));
     }
     
-    Ok(CleanResult {
-        cache_type: CacheType::Cursor,
-        freed_bytes: total_size,
-        items_removed: item_count,
-        success: true,
-        message: format!("Freed {} bytes ({} items)", total_size, item_count),
-        dry_run: false,
-    })
+    let message = format!("Freed {} ({} items)", format_size(outcome.freed_bytes), outcome.items_removed);
+    Ok(CleanResult::from_removal(CacheType::Cursor, &outcome, message))
 }
 
-async fn clean_vscode_cache(dry_run: bool) -> Result<CleanResult> {
-    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
-    let vscode_cache_paths = get_vscode_cache_paths(&home);
+async fn clean_vscode_cache(dry_run: bool, protect_recent: Option<Duration>) -> Result<CleanResult> {
+    let home = require_home()?;
+    let vscode_cache_paths = scanner::get_vscode_cache_paths(&home);
     
     let mut total_size = 0u64;
     let mut item_count = 0usize;
@@ -112,6 +341,7 @@ async fn clean_vscode_cache(dry_run: bool) -> Result<CleanResult> {
             success: true,
             message: "VSCode cache directories do not exist".to_string(),
             dry_run,
+            ..Default::default()
         });
     }
     
@@ -123,84 +353,35 @@ async fn clean_vscode_cache(dry_run: bool) -> Result<CleanResult> {
             success: true,
             message: format!("Would free {} bytes ({} items)", total_size, item_count),
             dry_run: true,
+            ..Default::default()
         });
     }
     
-    // Clean contents of each cache directory
+    // Clean contents of each cache directory, measuring what really went away
+    let mut outcome = RemovalOutcome::default();
     for path in &existing_paths {
-        filesystem::remove_dir_contents(path)?;
-    }
-    
-    Ok(CleanResult {
-        cache_type: CacheType::VSCode,
-        freed_bytes: total_size,
-        items_removed: item_count,
-        success: true,
-        message: format!("Freed {} bytes ({} items)", total_size, item_count),
-        dry_run: false,
-    })
-}
-
-/// Get all safe Cursor cache directories
-fn get_cursor_cache_paths(home: &std::path::Path) -> Vec<std::path::PathBuf> {
-    let mut paths = Vec::new();
-    
-    // Main cache in Library/Caches (todesktop bundle ID)
-    let caches_dir = home.join("Library/Caches");
-    if let Ok(entries) = std::fs::read_dir(&caches_dir) {
-        for entry in entries.filter_map(|e| e.ok()) {
-            let name = entry.file_name().to_string_lossy().to_string();
-            if name.starts_with("com.todesktop.") && entry.path().is_dir() {
-                paths.push(entry.path());
-            }
-        }
-    }
-    
-    // Secondary cache directory
-    let cursor_cache = caches_dir.join("Cursor");
-    if cursor_cache.exists() {
-        paths.push(cursor_cache);
-    }
-    
-    // Safe directories in Application Support
-    let app_support = home.join("Library/Application Support/Cursor");
-    for subdir in ["CachedExtensions", "CachedExtensionVSIXs", "logs"] {
-        let path = app_support.join(subdir);
-        if path.exists() {
-            paths.push(path);
-        }
-    }
-    
-    paths
-}
-
-/// Get all VSCode cache directories
-fn get_vscode_cache_paths(home: &std::path::Path) -> Vec<std::path::PathBuf> {
-    let mut paths = Vec::new();
-    
-    // Cache/Cache_Data directory
-    let cache_data = home.join("Library/Application Support/Code/Cache/Cache_Data");
-    if cache_data.exists() {
-        paths.push(cache_data);
-    }
-    
-    // User/workspaceStorage directory
-    let workspace_storage = home.join("Library/Application Support/Code/User/workspaceStorage");
-    if workspace_storage.exists() {
-        paths.push(workspace_storage);
+        let size = filesystem::calculate_dir_size(path).await?;
+        let items = filesystem::count_items(path)?;
+        outcome.absorb(filesystem::empty_dir_measured(path, size, items, protect_recent));
     }
     
-    paths
+    let message = format!("Freed {} ({} items)", format_size(outcome.freed_bytes), outcome.items_removed);
+    Ok(CleanResult::from_removal(CacheType::VSCode, &outcome, message))
 }
 
-async fn clean_directory_cache(cache_type: &CacheType, dry_run: bool) -> Result<CleanResult> {
-    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+async fn clean_directory_cache(
+    cache_type: &CacheType,
+    dry_run: bool,
+    protect_recent: Option<Duration>,
+) -> Result<CleanResult> {
+    let home = require_home()?;
     
-    let path = match cache_type {
+    let root = match cache_type {
         CacheType::Npm => home.join(".npm"),
         CacheType::Chrome => home.join("Library/Caches/Google/Chrome"),
         CacheType::CacheDir => home.join(".cache"),
         CacheType::VSCode => home.join("Library/Application Support/Code/Cache/Cache_Data"),
+        CacheType::Cargo => home.join(".cargo"),
 
         _ => {
             return Ok(CleanResult {
@@ -210,9 +391,16 @@ async fn clean_directory_cache(cache_type: &CacheType, dry_run: bool) -> Result<
                 success: false,
                 message: "Unsupported cache type".to_string(),
                 dry_run,
+                ..Default::default()
             });
         }
     };
+
+    let strategy = cache_type.clean_strategy();
+    let path = match strategy {
+        CleanStrategy::RemoveSubpath(subpath) => root.join(subpath),
+        CleanStrategy::RemoveContents | CleanStrategy::RunCommand(_) => root,
+    };
     
     if !path.exists() {
         return Ok(CleanResult {
@@ -222,6 +410,7 @@ async fn clean_directory_cache(cache_type: &CacheType, dry_run: bool) -> Result<
             success: true,
             message: "Cache directory does not exist".to_string(),
             dry_run,
+            ..Default::default()
         });
     }
     
@@ -236,24 +425,72 @@ async fn clean_directory_cache(cache_type: &CacheType, dry_run: bool) -> Result<
             success: true,
             message: format!("Would free {} bytes ({} items)", size_before, item_count),
             dry_run: true,
+            ..Default::default()
         });
     }
     
-    filesystem::remove_dir_contents(&path)?;
+    if let CleanStrategy::RunCommand(command) = strategy {
+        manifest::run_cleaner_command(command)?;
+        // The tool decides what to keep, so leftovers are not a failure
+        let size_after = filesystem::calculate_dir_size(&path).await.unwrap_or(0);
+        let freed_bytes = size_before.saturating_sub(size_after);
+        return Ok(CleanResult {
+            cache_type: cache_type.clone(),
+            freed_bytes,
+            items_removed: item_count,
+            success: true,
+            message: format!("Freed {}", format_size(freed_bytes)),
+            dry_run: false,
+            ..Default::default()
+        });
+    }
     
-    Ok(CleanResult {
-        cache_type: cache_type.clone(),
-        freed_bytes: size_before,
-        items_removed: item_count,
-        success: true,
-        message: format!("Freed {} bytes", size_before),
-        dry_run: false,
-    })
+    let outcome = filesystem::empty_dir_measured(&path, size_before, item_count, protect_recent);
+    let message = format!("Freed {}", format_size(outcome.freed_bytes));
+    Ok(CleanResult::from_removal(cache_type.clone(), &outcome, message))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::home::{set_data_dir_for_thread, set_home_for_thread};
+    use std::sync::Mutex;
+
+    /// Hands out the given free-space readings in order
+    struct FakeDiskStats(Mutex<Vec<u64>>);
+
+    impl DiskStats for FakeDiskStats {
+        fn free_bytes(&self) -> Result<u64> {
+            Ok(self.0.lock().unwrap().remove(0))
+        }
+    }
+
+    fn checked_clean(freed_bytes: u64, free_before: u64, free_after: u64) -> CleanResult {
+        let stats = FakeDiskStats(Mutex::new(vec![free_before, free_after]));
+        let check = FreeSpaceCheck::with_stats(Some(Box::new(stats)));
+        let outcome = RemovalOutcome { freed_bytes, ..Default::default() };
+        check.finish(CleanResult::from_removal(CacheType::Npm, &outcome, "Freed".to_string()))
+    }
+
+    #[test]
+    fn test_free_space_check_records_delta() {
+        const GB: u64 = 1024 * 1024 * 1024;
+        let result = checked_clean(2 * GB, 100 * GB, 102 * GB);
+        assert_eq!(result.actual_disk_delta, Some(2 * GB));
+        assert!(result.disk_delta_warning.is_none());
+
+        // An APFS clone shared most of its blocks, so little space came back
+        let result = checked_clean(2 * GB, 100 * GB, 100 * GB + GB / 10);
+        assert_eq!(result.actual_disk_delta, Some(GB / 10));
+        assert!(result.disk_delta_warning.unwrap().contains("APFS"));
+
+        let unchecked = FreeSpaceCheck::with_stats(None).finish(CleanResult::from_removal(
+            CacheType::Npm,
+            &RemovalOutcome::default(),
+            "Freed".to_string(),
+        ));
+        assert!(unchecked.actual_disk_delta.is_none());
+    }
 
     #[tokio::test]
     async fn test_clean_unsupported_type() {
@@ -262,6 +499,41 @@ mod tests {
         assert_eq!(result.message, "Unsupported cache type");
     }
 
+    #[cfg(feature = "readonly")]
+    #[tokio::test]
+    async fn test_readonly_build_refuses_to_clean() {
+        let err = clean(&CacheType::Npm, false).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<crate::cache::CacheError>(),
+            Some(crate::cache::CacheError::DeletionDisabled)
+        ));
+        // Reporting still works
+        assert!(clean(&CacheType::Npm, true).await.unwrap().dry_run);
+    }
+
+    #[cfg(not(feature = "readonly"))]
+    #[tokio::test]
+    async fn test_read_only_clean_estimates_and_keeps_files() {
+        guard::allow_root_for_tests();
+        let home = tempfile::tempdir().unwrap();
+        let blob = home.path().join(".npm/_cacache/content-v2/blob");
+        std::fs::create_dir_all(blob.parent().unwrap()).unwrap();
+        std::fs::write(&blob, [0u8; 4096]).unwrap();
+
+        set_home_for_thread(Some(home.path().to_path_buf()));
+        set_data_dir_for_thread(Some(home.path().join(".cache-cleaner")));
+        guard::set_read_only_for_thread(true);
+        let result = clean(&CacheType::Npm, false).await;
+        guard::set_read_only_for_thread(false);
+        set_data_dir_for_thread(None);
+        set_home_for_thread(None);
+
+        let result = result.unwrap();
+        assert!(result.success, "{}", result.message);
+        assert!(result.freed_bytes > 0);
+        assert!(blob.exists());
+    }
+
     #[tokio::test]
     async fn test_clean_dry_run_returns_dry_run_flag() {
         let result = clean(&CacheType::Npm, true).await.unwrap();
@@ -271,7 +543,7 @@ mod tests {
     #[tokio::test]
     async fn test_clean_cursor_nonexistent() {
         // This test verifies behavior when Cursor cache doesn't exist
-        let result = clean_cursor_cache(true).await.unwrap();
+        let result = clean_cursor_cache(true, None).await.unwrap();
         // Either files exist or they don't - both are valid
         assert!(result.success);
     }
@@ -301,4 +573,31 @@ mod tests {
         let result = clean(&CacheType::XcodeDerivedData, true).await.unwrap();
         assert!(matches!(result.cache_type, CacheType::XcodeDerivedData));
     }
+
+    #[tokio::test]
+    async fn test_clean_with_progress_dry_run_matches_clean() {
+        let result = clean_with_progress(&CacheType::Npm, true).await.unwrap();
+        assert!(result.dry_run);
+        assert!(matches!(result.cache_type, CacheType::Npm));
+    }
+
+    #[test]
+    fn test_progress_target_skips_command_cleaned_types() {
+        assert!(progress_target(&CacheType::QuickLook).unwrap().is_none());
+        let npm = progress_target(&CacheType::Npm).unwrap().unwrap();
+        assert!(npm.ends_with(".npm/_cacache"));
+    }
+
+    #[tokio::test]
+    async fn test_clean_by_category_browser_dry_run() {
+        let results = clean_by_category(CacheCategory::Browser, true).await;
+        assert_eq!(results.len(), 4);
+        for result in &results {
+            assert!(result.dry_run);
+            assert!(matches!(
+                result.cache_type,
+                CacheType::Safari | CacheType::Firefox | CacheType::Arc | CacheType::Chrome
+            ));
+        }
+    }
 }
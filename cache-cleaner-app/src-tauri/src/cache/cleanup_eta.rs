@@ -0,0 +1,223 @@
+//! How long a clean will take, from how fast past cleans deleted.
+//!
+//! Every real clean of a cache type records its items per second into a
+//! rolling average kept in `stats.json` in the app data directory. An
+//! estimate counts the items below the given paths and divides by that rate,
+//! widened into a range since deletion speed varies with file sizes and disk
+//! load.
+
+use crate::utils::filesystem;
+use crate::utils::home::app_data_dir;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Used until a clean has been measured; a slow disk's rate, so early
+/// estimates err long
+const DEFAULT_ITEMS_PER_SEC: f64 = 2_000.0;
+
+/// Weight of the newest clean in the rolling average
+const SMOOTHING: f64 = 0.3;
+
+/// Smaller cleans mostly measure startup cost, not deletion speed
+const MIN_SAMPLE_ITEMS: usize = 100;
+const MIN_SAMPLE_DURATION: Duration = Duration::from_millis(50);
+
+/// How far the range reaches either side of the expected time, as a factor
+/// of the rate: wide while the rate is a guess, narrower once measured
+const UNMEASURED_SPREAD: f64 = 3.0;
+const MEASURED_SPREAD: f64 = 1.5;
+
+/// Rolling average deletion throughput
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ThroughputModel {
+    pub items_per_sec: f64,
+    /// Cleans the average is based on; 0 means the built-in default
+    pub samples: u32,
+}
+
+impl Default for ThroughputModel {
+    fn default() -> Self {
+        Self {
+            items_per_sec: DEFAULT_ITEMS_PER_SEC,
+            samples: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DurationEstimate {
+    pub item_count: u64,
+    pub min_seconds: f64,
+    pub max_seconds: f64,
+    /// Rate the estimate is based on
+    pub items_per_sec: f64,
+    /// Past cleans behind that rate; 0 when it is the built-in default
+    pub samples: u32,
+}
+
+/// Persisted statistics about past operations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Stats {
+    #[serde(default)]
+    deletion_throughput: ThroughputModel,
+}
+
+impl ThroughputModel {
+    /// Fold one clean into the average; returns false when it was too small to count
+    pub fn record(&mut self, items_removed: usize, elapsed: Duration) -> bool {
+        if items_removed < MIN_SAMPLE_ITEMS || elapsed < MIN_SAMPLE_DURATION {
+            return false;
+        }
+        let rate = items_removed as f64 / elapsed.as_secs_f64();
+        self.items_per_sec = if self.samples == 0 {
+            rate
+        } else {
+            SMOOTHING * rate + (1.0 - SMOOTHING) * self.items_per_sec
+        };
+        self.samples = self.samples.saturating_add(1);
+        true
+    }
+
+    pub fn estimate(&self, item_count: u64) -> DurationEstimate {
+        let spread = if self.samples == 0 { UNMEASURED_SPREAD } else { MEASURED_SPREAD };
+        let expected = item_count as f64 / self.items_per_sec;
+        DurationEstimate {
+            item_count,
+            min_seconds: expected / spread,
+            max_seconds: expected * spread,
+            items_per_sec: self.items_per_sec,
+            samples: self.samples,
+        }
+    }
+}
+
+fn stats_path() -> Result<PathBuf> {
+    Ok(app_data_dir()?.join("stats.json"))
+}
+
+fn load_stats(path: &Path) -> Stats {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_stats(path: &Path, stats: &Stats) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(stats)?)?;
+    Ok(())
+}
+
+/// Estimated time to delete everything below `paths`
+pub fn estimate_cleanup_duration(paths: Vec<String>) -> Result<DurationEstimate> {
+    let model = load_stats(&stats_path()?).deletion_throughput;
+    Ok(model.estimate(count_paths(&paths)))
+}
+
+/// Update the throughput average after a real clean
+pub fn record_clean(items_removed: usize, elapsed: Duration) -> Result<()> {
+    record_clean_in(&stats_path()?, items_removed, elapsed)
+}
+
+fn record_clean_in(path: &Path, items_removed: usize, elapsed: Duration) -> Result<()> {
+    let mut stats = load_stats(path);
+    if stats.deletion_throughput.record(items_removed, elapsed) {
+        save_stats(path, &stats)?;
+    }
+    Ok(())
+}
+
+/// Items a clean would delete: everything below a directory, one per file
+fn count_paths(paths: &[String]) -> u64 {
+    paths
+        .iter()
+        .map(|path| {
+            let path = Path::new(path);
+            if path.is_dir() {
+                filesystem::count_items(path).unwrap_or(0) as u64
+            } else if path.exists() {
+                1
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn seeded(items_per_sec: f64, samples: u32) -> ThroughputModel {
+        ThroughputModel { items_per_sec, samples }
+    }
+
+    #[test]
+    fn test_estimate_with_seeded_model() {
+        // 10,000 items at 1,000 items/s: 10s expected
+        let estimate = seeded(1_000.0, 5).estimate(10_000);
+        assert_eq!(estimate.item_count, 10_000);
+        assert!(estimate.min_seconds <= 10.0 && 10.0 <= estimate.max_seconds);
+        assert!((estimate.min_seconds - 10.0 / MEASURED_SPREAD).abs() < 1e-9);
+        assert!((estimate.max_seconds - 10.0 * MEASURED_SPREAD).abs() < 1e-9);
+
+        // An unmeasured model gives a wider range around the default rate
+        let guess = ThroughputModel::default().estimate(10_000);
+        assert_eq!(guess.samples, 0);
+        assert!(guess.max_seconds - guess.min_seconds > estimate.max_seconds - estimate.min_seconds);
+    }
+
+    #[test]
+    fn test_record_updates_rolling_average() {
+        let mut model = ThroughputModel::default();
+        assert!(model.record(5_000, Duration::from_secs(1)));
+        assert_eq!(model.items_per_sec, 5_000.0);
+        assert!(model.record(1_000, Duration::from_secs(1)));
+        assert!((model.items_per_sec - (0.3 * 1_000.0 + 0.7 * 5_000.0)).abs() < 1e-9);
+        assert_eq!(model.samples, 2);
+
+        // Too small to say anything about throughput
+        assert!(!model.record(10, Duration::from_secs(1)));
+        assert!(!model.record(10_000, Duration::from_millis(1)));
+        assert_eq!(model.samples, 2);
+    }
+
+    #[test]
+    fn test_recorded_cleans_persist() {
+        let dir = create_test_dir();
+        let path = dir.path().join("stats.json");
+        record_clean_in(&path, 3_000, Duration::from_secs(2)).unwrap();
+
+        let model = load_stats(&path).deletion_throughput;
+        assert_eq!(model, seeded(1_500.0, 1));
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(load_stats(&path).deletion_throughput, ThroughputModel::default());
+    }
+
+    #[test]
+    fn test_count_paths() {
+        let dir = create_test_dir();
+        fs::create_dir_all(dir.path().join("cache/sub")).unwrap();
+        fs::write(dir.path().join("cache/a"), "a").unwrap();
+        fs::write(dir.path().join("cache/sub/b"), "b").unwrap();
+        fs::write(dir.path().join("single"), "c").unwrap();
+
+        let paths = [
+            dir.path().join("cache").display().to_string(),
+            dir.path().join("single").display().to_string(),
+            dir.path().join("missing").display().to_string(),
+        ];
+        assert_eq!(count_paths(&paths), 4);
+    }
+}
@@ -0,0 +1,192 @@
+//! "Clean these to reach N GB free" planning.
+//!
+//! Every kind of suggestion (scanned caches, smart folder suggestions, Docker
+//! resources) is flattened into `PlanItem`s, ordered safest first and then
+//! largest first, and picked greedily until the projected free space meets
+//! the target.
+
+use super::smart_suggestions::{self, CleanupConfidence};
+use super::{docker, scan_results};
+use crate::utils::disk;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Folder suggestions below this size are not worth planning around
+const PLAN_MIN_SUGGESTION_MB: u64 = 100;
+const PLAN_MAX_AGE_DAYS: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanSource {
+    Cache,
+    SmartSuggestion,
+    Docker,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanItem {
+    pub source: PlanSource,
+    /// Cache type, folder path or Docker id, depending on `source`
+    pub id: String,
+    pub name: String,
+    pub size_bytes: u64,
+    pub confidence: CleanupConfidence,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupPlan {
+    pub target_free_bytes: u64,
+    pub current_free_bytes: u64,
+    pub projected_freed_bytes: u64,
+    pub projected_free_bytes: u64,
+    /// False when cleaning every candidate still falls short of the target
+    pub reachable: bool,
+    pub items: Vec<PlanItem>,
+}
+
+/// Pick suggestions until free space would reach `target_free_bytes`
+pub async fn suggest_to_reach_free_space(target_free_bytes: u64) -> Result<CleanupPlan> {
+    let current_free_bytes = disk::get_disk_usage()?.free_bytes;
+    let candidates = gather_candidates().await;
+    Ok(build_plan(candidates, current_free_bytes, target_free_bytes))
+}
+
+/// Every suggestion source, skipping the ones that are unavailable
+async fn gather_candidates() -> Vec<PlanItem> {
+    let mut items = Vec::new();
+
+    let report = scan_results::cached_scan_all(false).await;
+    for cache in report.caches.into_iter().filter(|c| c.exists && c.size > 0) {
+        items.push(PlanItem {
+            source: PlanSource::Cache,
+            id: format!("{:?}", cache.cache_type),
+            name: format!("{:?}", cache.cache_type),
+            size_bytes: cache.size,
+            confidence: CleanupConfidence::Safe,
+        });
+    }
+
+    if let Ok(suggestions) =
+        smart_suggestions::scan_suggestions(PLAN_MIN_SUGGESTION_MB, PLAN_MAX_AGE_DAYS).await
+    {
+        for suggestion in suggestions {
+            items.push(PlanItem {
+                source: PlanSource::SmartSuggestion,
+                id: suggestion.path,
+                name: suggestion.name,
+                size_bytes: suggestion.size_bytes,
+                confidence: suggestion.confidence,
+            });
+        }
+    }
+
+    // Docker being absent or stopped is not an error here
+    if let Ok(suggestions) = docker::get_docker_suggestions().await {
+        for suggestion in suggestions.into_iter().filter(|s| s.size > 0) {
+            items.push(PlanItem {
+                source: PlanSource::Docker,
+                id: suggestion.id,
+                name: suggestion.name,
+                size_bytes: suggestion.size,
+                confidence: if suggestion.auto_select {
+                    CleanupConfidence::Safe
+                } else {
+                    CleanupConfidence::Caution
+                },
+            });
+        }
+    }
+
+    items
+}
+
+/// Greedy selection: safest first, then largest first
+fn build_plan(mut candidates: Vec<PlanItem>, current_free_bytes: u64, target_free_bytes: u64) -> CleanupPlan {
+    candidates.sort_by(|a, b| {
+        a.confidence
+            .cmp(&b.confidence)
+            .then_with(|| b.size_bytes.cmp(&a.size_bytes))
+    });
+
+    let mut projected_freed_bytes = 0u64;
+    let mut items = Vec::new();
+    for candidate in candidates {
+        if current_free_bytes.saturating_add(projected_freed_bytes) >= target_free_bytes {
+            break;
+        }
+        projected_freed_bytes += candidate.size_bytes;
+        items.push(candidate);
+    }
+
+    let projected_free_bytes = current_free_bytes.saturating_add(projected_freed_bytes);
+    CleanupPlan {
+        target_free_bytes,
+        current_free_bytes,
+        projected_freed_bytes,
+        projected_free_bytes,
+        reachable: projected_free_bytes >= target_free_bytes,
+        items,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1_000_000_000;
+
+    fn item(id: &str, size_bytes: u64, confidence: CleanupConfidence) -> PlanItem {
+        PlanItem {
+            source: PlanSource::SmartSuggestion,
+            id: id.to_string(),
+            name: id.to_string(),
+            size_bytes,
+            confidence,
+        }
+    }
+
+    fn fixture() -> Vec<PlanItem> {
+        vec![
+            item("risky-huge", 50 * GB, CleanupConfidence::Risky),
+            item("safe-small", GB, CleanupConfidence::Safe),
+            item("caution-big", 8 * GB, CleanupConfidence::Caution),
+            item("safe-big", 5 * GB, CleanupConfidence::Safe),
+        ]
+    }
+
+    fn ids(plan: &CleanupPlan) -> Vec<&str> {
+        plan.items.iter().map(|i| i.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_greedy_selection_reaches_target() {
+        let plan = build_plan(fixture(), 10 * GB, 20 * GB);
+        assert_eq!(ids(&plan), vec!["safe-big", "safe-small", "caution-big"]);
+        assert_eq!(plan.projected_freed_bytes, 14 * GB);
+        assert_eq!(plan.projected_free_bytes, 24 * GB);
+        assert!(plan.reachable);
+    }
+
+    #[test]
+    fn test_stops_once_target_is_met() {
+        let plan = build_plan(fixture(), 10 * GB, 15 * GB);
+        assert_eq!(ids(&plan), vec!["safe-big"]);
+        assert!(plan.reachable);
+    }
+
+    #[test]
+    fn test_unreachable_target_selects_everything() {
+        let plan = build_plan(fixture(), 10 * GB, 100 * GB);
+        assert_eq!(plan.items.len(), 4);
+        assert_eq!(plan.projected_free_bytes, 74 * GB);
+        assert!(!plan.reachable);
+    }
+
+    #[test]
+    fn test_already_enough_free_space() {
+        let plan = build_plan(fixture(), 30 * GB, 20 * GB);
+        assert!(plan.items.is_empty());
+        assert_eq!(plan.projected_freed_bytes, 0);
+        assert!(plan.reachable);
+    }
+}
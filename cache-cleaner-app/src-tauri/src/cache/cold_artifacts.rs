@@ -0,0 +1,273 @@
+//! Cold artifacts inside package manager caches.
+//!
+//! Gradle, Cargo and CocoaPods keep one folder or archive per dependency
+//! version. Entries nobody has read or written for a while are listed so
+//! they can be pruned while the hot part of the cache stays in place.
+
+use super::paths::MacPaths;
+use super::smart_suggestions::CleanupConfidence;
+use super::{guard, CacheError, CacheType, PathResult};
+use crate::utils::{access_tracker, filesystem};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LargeCacheEntry {
+    pub name: String,
+    pub path: String,
+    pub size_bytes: u64,
+    /// Days since anything inside was last read or written
+    pub last_used_days: Option<u64>,
+    pub confidence: CleanupConfidence,
+    /// Selected for removal by default in the UI
+    pub auto_select: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColdArtifactsCleanResult {
+    pub total_freed_bytes: u64,
+    pub items_removed: usize,
+    pub success: bool,
+    pub message: String,
+    pub path_results: Vec<PathResult>,
+}
+
+/// Where artifacts sit below a cache root: `(subdirectory, depth)`.
+/// Cargo keeps `cache/<registry>/<crate>.crate` and `src/<registry>/<crate>`,
+/// Gradle `modules-2/files-2.1/<group>/<artifact>`, CocoaPods
+/// `Pods/Release/<pod>/<version>`.
+fn artifact_layout(cache_type: &CacheType) -> Option<&'static [(&'static str, usize)]> {
+    match cache_type {
+        CacheType::Cargo => Some(&[("cache", 2), ("src", 2)]),
+        CacheType::Gradle => Some(&[("modules-2/files-2.1", 2), ("transforms-3", 1)]),
+        CacheType::CocoaPods => Some(&[("Pods/Release", 2), ("Pods/External", 2)]),
+        _ => None,
+    }
+}
+
+fn cache_root(cache_type: &CacheType) -> Result<PathBuf> {
+    match cache_type {
+        CacheType::Cargo => Ok(MacPaths::cargo_cache()?),
+        CacheType::Gradle => Ok(MacPaths::gradle_cache()?),
+        CacheType::CocoaPods => Ok(MacPaths::cocoapods_cache()?),
+        _ => Err(anyhow!("Cold artifact detection is not supported for {:?}", cache_type)),
+    }
+}
+
+/// Artifacts in a Gradle, Cargo or CocoaPods cache unused for `cold_days`
+pub async fn scan_cold_artifacts(cache_type: CacheType, cold_days: u64) -> Result<Vec<LargeCacheEntry>> {
+    let root = cache_root(&cache_type)?;
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+    tokio::task::spawn_blocking(move || scan_cold_artifacts_in(&root, &cache_type, now, cold_days)).await?
+}
+
+/// Remove the selected artifacts, which must lie inside the cache
+pub async fn remove_cold_artifacts(cache_type: CacheType, paths: Vec<String>) -> Result<ColdArtifactsCleanResult> {
+    guard::ensure_deletion_enabled()?;
+    let root = cache_root(&cache_type)?;
+    tokio::task::spawn_blocking(move || remove_cold_artifacts_in(&root, &paths)).await?
+}
+
+fn scan_cold_artifacts_in(
+    root: &Path,
+    cache_type: &CacheType,
+    now_secs: u64,
+    cold_days: u64,
+) -> Result<Vec<LargeCacheEntry>> {
+    let layout = artifact_layout(cache_type)
+        .ok_or_else(|| anyhow!("Cold artifact detection is not supported for {:?}", cache_type))?;
+
+    let mut entries = Vec::new();
+    for (subdir, depth) in layout {
+        let base = root.join(subdir);
+        if !base.is_dir() {
+            continue;
+        }
+        for entry in WalkDir::new(&base).min_depth(*depth).max_depth(*depth).into_iter().flatten() {
+            let path = entry.path();
+            let last_used_days = newest_use(path).map(|secs| now_secs.saturating_sub(secs) / SECS_PER_DAY);
+            // Entries with no usable timestamps are left alone
+            if last_used_days.is_none_or(|days| days < cold_days) {
+                continue;
+            }
+            let size_bytes = if entry.file_type().is_dir() {
+                filesystem::calculate_dir_size_sync(path).unwrap_or(0)
+            } else {
+                filesystem::calculate_file_size_sync(path).unwrap_or(0)
+            };
+            entries.push(LargeCacheEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: path.display().to_string(),
+                size_bytes,
+                last_used_days,
+                confidence: CleanupConfidence::Safe,
+                auto_select: true,
+            });
+        }
+    }
+
+    entries.sort_by_key(|entry| Reverse(entry.size_bytes));
+    Ok(entries)
+}
+
+/// Latest use of `path` or anything below it, seconds since epoch
+fn newest_use(path: &Path) -> Option<u64> {
+    WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| access_tracker::get_access_info(&e.path().to_path_buf()).ok())
+        .filter_map(|info| access_tracker::last_used(&info))
+        .max()
+}
+
+fn remove_cold_artifacts_in(root: &Path, paths: &[String]) -> Result<ColdArtifactsCleanResult> {
+    let mut total_freed = 0u64;
+    let mut items_removed = 0usize;
+    let mut path_results = Vec::new();
+
+    for path_str in paths {
+        let path = PathBuf::from(path_str);
+        let escapes = path.components().any(|c| matches!(c, Component::ParentDir));
+        if escapes || !path.starts_with(root) || path == root {
+            path_results.push(PathResult::failed(path_str, "Path is outside the cache".to_string()));
+            continue;
+        }
+        if !path.exists() {
+            path_results.push(PathResult::failed(path_str, "Artifact not found".to_string()));
+            continue;
+        }
+        if let Err(e) = guard::assert_safe_to_delete(&path) {
+            path_results.push(PathResult::failed(path_str, e.to_string()));
+            continue;
+        }
+
+        let (size, removed) = if path.is_dir() {
+            (filesystem::calculate_dir_size_sync(&path).unwrap_or(0), filesystem::delete_dir_all(&path))
+        } else {
+            (filesystem::calculate_file_size_sync(&path).unwrap_or(0), filesystem::delete_file(&path))
+        };
+        match removed {
+            Ok(()) => {
+                total_freed += size;
+                items_removed += 1;
+                path_results.push(PathResult::removed(path_str, size));
+            }
+            Err(e) => path_results.push(PathResult::from_error(path_str, &CacheError::from_io(&path, e))),
+        }
+    }
+
+    let errors: Vec<String> = path_results.iter().filter_map(|r| r.error.clone()).collect();
+    let message = if errors.is_empty() {
+        format!("Removed {} cold artifact(s)", items_removed)
+    } else {
+        format!(
+            "Removed {} cold artifact(s), {} errors: {}",
+            items_removed,
+            errors.len(),
+            errors.join("; ")
+        )
+    };
+
+    Ok(ColdArtifactsCleanResult {
+        total_freed_bytes: total_freed,
+        items_removed,
+        success: errors.is_empty(),
+        message,
+        path_results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File, FileTimes};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    const REGISTRY: &str = "index.crates.io-6f17d22bba15001f";
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn backdate(path: &Path, days: u64) {
+        let when = SystemTime::now() - Duration::from_secs(days * SECS_PER_DAY);
+        let times = FileTimes::new().set_accessed(when).set_modified(when);
+        File::open(path).unwrap().set_times(times).unwrap();
+    }
+
+    /// `serde-1.0.0` untouched for 100 days, `tokio-1.0.0` used today
+    fn create_cargo_registry(root: &Path) {
+        for krate in ["serde-1.0.0", "tokio-1.0.0"] {
+            let src = root.join("src").join(REGISTRY).join(krate);
+            fs::create_dir_all(src.join("src")).unwrap();
+            fs::write(src.join("src/lib.rs"), [b' '; 200]).unwrap();
+            let archive = root.join("cache").join(REGISTRY).join(format!("{}.crate", krate));
+            fs::create_dir_all(archive.parent().unwrap()).unwrap();
+            fs::write(&archive, [0u8; 50]).unwrap();
+        }
+
+        let old_src = root.join("src").join(REGISTRY).join("serde-1.0.0");
+        for path in [old_src.join("src/lib.rs"), old_src.join("src"), old_src] {
+            backdate(&path, 100);
+        }
+        backdate(&root.join("cache").join(REGISTRY).join("serde-1.0.0.crate"), 100);
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    #[test]
+    fn test_scan_reports_only_cold_entries() {
+        let root = create_test_dir();
+        create_cargo_registry(root.path());
+
+        let entries = scan_cold_artifacts_in(root.path(), &CacheType::Cargo, now(), 30).unwrap();
+        let mut names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["serde-1.0.0", "serde-1.0.0.crate"]);
+        assert_eq!(entries[0].size_bytes, 200);
+        assert!(entries.iter().all(|e| e.last_used_days.is_some_and(|d| d >= 99)));
+    }
+
+    #[test]
+    fn test_scan_threshold_above_age_reports_nothing() {
+        let root = create_test_dir();
+        create_cargo_registry(root.path());
+
+        let entries = scan_cold_artifacts_in(root.path(), &CacheType::Cargo, now(), 200).unwrap();
+        assert!(entries.is_empty());
+    }
+
+    #[test]
+    fn test_scan_rejects_unsupported_type() {
+        let root = create_test_dir();
+        assert!(scan_cold_artifacts_in(root.path(), &CacheType::Npm, now(), 30).is_err());
+    }
+
+    #[test]
+    fn test_remove_only_inside_cache() {
+        let root = create_test_dir();
+        create_cargo_registry(root.path());
+        let cold = root.path().join("src").join(REGISTRY).join("serde-1.0.0");
+        let escape = root.path().join("src/../../elsewhere");
+
+        let result = remove_cold_artifacts_in(
+            root.path(),
+            &[cold.display().to_string(), escape.display().to_string(), "/tmp".to_string()],
+        )
+        .unwrap();
+        assert!(!result.success);
+        assert_eq!(result.items_removed, 1);
+        assert_eq!(result.total_freed_bytes, 200);
+        assert!(!cold.exists());
+        assert!(root.path().join("src").join(REGISTRY).join("tokio-1.0.0").exists());
+    }
+}
@@ -0,0 +1,334 @@
+//! conda / mamba package caches.
+//!
+//! Every conda install keeps downloaded tarballs and extracted packages in
+//! `<prefix>/pkgs`, which grows by gigabytes for anyone who creates a few
+//! environments. The install is found through `CONDA_PREFIX` (an activated
+//! environment points inside it) or the usual installer locations. Cleaning
+//! goes through the install's own `conda clean --all -y`, or `mamba` when that
+//! is all it ships, and empties `pkgs` otherwise. Environments hard-link their
+//! files from `pkgs`, so they keep working either way.
+
+use super::{CacheInfo, CacheType, CleanResult};
+use crate::utils::filesystem;
+use crate::utils::home::require_home;
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// Installer defaults, relative to the home directory
+const HOME_INSTALL_DIRS: &[&str] = &[
+    "miniconda3",
+    "anaconda3",
+    "miniforge3",
+    "mambaforge",
+    "opt/miniconda3",
+    "opt/anaconda3",
+    "micromamba",
+];
+
+/// Installer defaults outside the home directory
+const SYSTEM_INSTALL_DIRS: &[&str] = &[
+    "/opt/homebrew/Caskroom/miniconda/base",
+    "/opt/homebrew/Caskroom/miniforge/base",
+    "/usr/local/Caskroom/miniconda/base",
+    "/opt/anaconda3",
+    "/opt/miniconda3",
+];
+
+/// Arguments shared by `conda clean` and `mamba clean`
+const CLEAN_ARGS: [&str; 3] = ["clean", "--all", "-y"];
+
+/// How a conda package cache is cleaned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CondaCleanMethod {
+    /// Run `<tool> clean --all -y` with this binary from the install
+    Tool(PathBuf),
+    /// Empty `<prefix>/pkgs` directly
+    PrunePkgs,
+}
+
+/// A directory is a conda install when it has `conda-meta`
+pub fn is_conda_install(prefix: &Path) -> bool {
+    prefix.join("conda-meta").is_dir()
+}
+
+/// Root prefix of the conda install in use: the base of `CONDA_PREFIX` (or
+/// `MAMBA_ROOT_PREFIX`), else the first common install directory that exists
+pub fn find_install_in(
+    home: &Path,
+    env: impl Fn(&str) -> Option<String>,
+    is_install: impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
+    let from_env = ["CONDA_PREFIX", "MAMBA_ROOT_PREFIX"]
+        .into_iter()
+        .filter_map(|key| env(key).map(PathBuf::from).filter(|p| p.is_absolute()))
+        .map(|prefix| base_prefix(&prefix));
+    let common = HOME_INSTALL_DIRS
+        .iter()
+        .map(|dir| home.join(dir))
+        .chain(SYSTEM_INSTALL_DIRS.iter().map(PathBuf::from));
+
+    from_env.chain(common).find(|prefix| is_install(prefix))
+}
+
+/// The conda install on this machine, if any
+pub fn find_install() -> Option<PathBuf> {
+    let home = require_home().ok()?;
+    find_install_in(&home, |key| std::env::var(key).ok(), is_conda_install)
+}
+
+/// `<base>/envs/<name>` belongs to `<base>`, whose `pkgs` all environments share
+fn base_prefix(prefix: &Path) -> PathBuf {
+    match prefix.parent() {
+        Some(envs) if envs.file_name().is_some_and(|name| name == "envs") => envs
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| prefix.to_path_buf()),
+        _ => prefix.to_path_buf(),
+    }
+}
+
+/// The install's own `conda`, else its `mamba`, else pruning `pkgs`. Binaries
+/// are taken from the prefix because the app does not inherit the shell PATH.
+pub fn clean_method(prefix: &Path, is_executable: impl Fn(&Path) -> bool) -> CondaCleanMethod {
+    ["conda", "mamba"]
+        .into_iter()
+        .map(|tool| prefix.join("bin").join(tool))
+        .find(|tool| is_executable(tool))
+        .map(CondaCleanMethod::Tool)
+        .unwrap_or(CondaCleanMethod::PrunePkgs)
+}
+
+fn is_executable(path: &Path) -> bool {
+    Command::new(path)
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// `<prefix>/pkgs` of the detected install
+pub fn conda_pkgs_path() -> Result<PathBuf> {
+    find_install()
+        .map(|prefix| prefix.join("pkgs"))
+        .ok_or_else(|| anyhow!("No conda installation found"))
+}
+
+pub fn get_conda_cache_info() -> Result<CacheInfo> {
+    let prefix = find_install().ok_or_else(|| anyhow!("No conda installation found"))?;
+    Ok(conda_cache_info_at(&prefix))
+}
+
+fn conda_cache_info_at(prefix: &Path) -> CacheInfo {
+    let path = prefix.join("pkgs");
+    let exists = path.exists();
+    let (size, item_count) = if exists {
+        (
+            filesystem::calculate_dir_size_sync(&path).unwrap_or(0),
+            filesystem::count_items(&path).unwrap_or(0),
+        )
+    } else {
+        (0, 0)
+    };
+
+    CacheInfo {
+        cache_type: CacheType::Conda,
+        path: path.display().to_string(),
+        size,
+        exists,
+        item_count,
+        size_margin: None,
+        note: Some(format!("conda install at {}", prefix.display())),
+    }
+}
+
+pub fn clean_conda_cache(dry_run: bool, protect_recent: Option<Duration>) -> Result<CleanResult> {
+    let prefix = find_install().ok_or_else(|| anyhow!("No conda installation found"))?;
+    let method = clean_method(&prefix, is_executable);
+    clean_conda_cache_at(&prefix, method, dry_run, protect_recent)
+}
+
+fn clean_conda_cache_at(
+    prefix: &Path,
+    method: CondaCleanMethod,
+    dry_run: bool,
+    protect_recent: Option<Duration>,
+) -> Result<CleanResult> {
+    let path = prefix.join("pkgs");
+    if !path.exists() {
+        return Ok(CleanResult {
+            cache_type: CacheType::Conda,
+            freed_bytes: 0,
+            items_removed: 0,
+            success: true,
+            message: format!("No package cache in {}", prefix.display()),
+            dry_run,
+            ..Default::default()
+        });
+    }
+
+    let size_before = filesystem::calculate_dir_size_sync(&path)?;
+    let items_before = filesystem::count_items(&path)?;
+
+    if dry_run || filesystem::skip_deletion() {
+        return Ok(CleanResult {
+            cache_type: CacheType::Conda,
+            freed_bytes: size_before,
+            items_removed: items_before,
+            success: true,
+            message: format!(
+                "Would clean {} items ({} bytes) from {}",
+                items_before,
+                size_before,
+                prefix.display()
+            ),
+            dry_run,
+            ..Default::default()
+        });
+    }
+
+    if let CondaCleanMethod::Tool(tool) = method {
+        let output = Command::new(&tool).args(CLEAN_ARGS).output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "`{} {}` failed: {}",
+                tool.display(),
+                CLEAN_ARGS.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        let size_after = filesystem::calculate_dir_size_sync(&path).unwrap_or(0);
+        let freed_bytes = size_before.saturating_sub(size_after);
+        return Ok(CleanResult {
+            cache_type: CacheType::Conda,
+            freed_bytes,
+            items_removed: items_before,
+            success: true,
+            message: format!(
+                "Ran `{} {}` for {}, freed {} bytes",
+                tool.display(),
+                CLEAN_ARGS.join(" "),
+                prefix.display(),
+                freed_bytes
+            ),
+            dry_run: false,
+            ..Default::default()
+        });
+    }
+
+    let outcome = filesystem::empty_dir_measured(&path, size_before, items_before, protect_recent);
+    Ok(CleanResult::from_removal(
+        CacheType::Conda,
+        &outcome,
+        format!("Pruned {} items from {}", items_before, path.display()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    fn installs(prefixes: &[&str]) -> impl Fn(&Path) -> bool {
+        let prefixes: Vec<PathBuf> = prefixes.iter().map(PathBuf::from).collect();
+        move |path| prefixes.iter().any(|p| p == path)
+    }
+
+    #[test]
+    fn test_find_install_from_common_dirs() {
+        let home = Path::new("/Users/test");
+        assert_eq!(find_install_in(home, env(&[]), installs(&[])), None);
+        assert_eq!(
+            find_install_in(home, env(&[]), installs(&["/Users/test/anaconda3"])),
+            Some(home.join("anaconda3"))
+        );
+        assert_eq!(
+            find_install_in(home, env(&[]), installs(&["/opt/homebrew/Caskroom/miniconda/base"])),
+            Some(PathBuf::from("/opt/homebrew/Caskroom/miniconda/base"))
+        );
+        // miniconda3 is checked before anaconda3
+        assert_eq!(
+            find_install_in(home, env(&[]), installs(&["/Users/test/anaconda3", "/Users/test/miniconda3"])),
+            Some(home.join("miniconda3"))
+        );
+    }
+
+    #[test]
+    fn test_find_install_prefers_conda_prefix() {
+        let home = Path::new("/Users/test");
+        let detected = installs(&["/Users/test/miniconda3", "/data/conda"]);
+        assert_eq!(
+            find_install_in(home, env(&[("CONDA_PREFIX", "/data/conda")]), &detected),
+            Some(PathBuf::from("/data/conda"))
+        );
+        // An activated environment resolves to the base install that owns pkgs
+        assert_eq!(
+            find_install_in(home, env(&[("CONDA_PREFIX", "/data/conda/envs/ml")]), &detected),
+            Some(PathBuf::from("/data/conda"))
+        );
+        // A stale or relative CONDA_PREFIX falls through to the common dirs
+        assert_eq!(
+            find_install_in(home, env(&[("CONDA_PREFIX", "/gone")]), &detected),
+            Some(home.join("miniconda3"))
+        );
+        assert_eq!(
+            find_install_in(home, env(&[("CONDA_PREFIX", "conda")]), &detected),
+            Some(home.join("miniconda3"))
+        );
+    }
+
+    #[test]
+    fn test_is_conda_install_needs_conda_meta() {
+        let root = create_test_dir();
+        assert!(!is_conda_install(root.path()));
+        fs::create_dir(root.path().join("conda-meta")).unwrap();
+        assert!(is_conda_install(root.path()));
+    }
+
+    #[test]
+    fn test_clean_method_prefers_conda_then_mamba() {
+        let prefix = Path::new("/Users/test/miniforge3");
+        let conda = prefix.join("bin/conda");
+        let mamba = prefix.join("bin/mamba");
+
+        assert_eq!(
+            clean_method(prefix, |p| p == conda || p == mamba),
+            CondaCleanMethod::Tool(conda.clone())
+        );
+        assert_eq!(clean_method(prefix, |p| p == mamba), CondaCleanMethod::Tool(mamba.clone()));
+        assert_eq!(clean_method(prefix, |_| false), CondaCleanMethod::PrunePkgs);
+    }
+
+    #[test]
+    fn test_fallback_prunes_pkgs_only() {
+        let root = create_test_dir();
+        let pkgs = root.path().join("pkgs/numpy-1.26.4-py311h7125741_0");
+        fs::create_dir_all(&pkgs).unwrap();
+        fs::write(pkgs.join("info.json"), [0u8; 100]).unwrap();
+        fs::create_dir(root.path().join("conda-meta")).unwrap();
+        fs::write(root.path().join("conda-meta/history"), "keep").unwrap();
+
+        let info = conda_cache_info_at(root.path());
+        assert_eq!(info.size, 100);
+        assert!(info.note.unwrap().contains(&root.path().display().to_string()));
+
+        let result = clean_conda_cache_at(root.path(), CondaCleanMethod::PrunePkgs, false, None).unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.freed_bytes, 100);
+        assert!(result.message.contains(&root.path().display().to_string()));
+        assert_eq!(fs::read_dir(root.path().join("pkgs")).unwrap().count(), 0);
+        assert!(root.path().join("conda-meta/history").exists());
+    }
+}
@@ -1,12 +1,109 @@
 use super::custom_scanner::CustomScannerConfig;
+use super::Verbosity;
+use super::scan_results::DEFAULT_SCAN_CACHE_TTL_SECS;
+use super::smart_suggestions::RankingStrategy;
+use crate::utils::home::app_data_dir;
+use crate::utils::format::SizeUnitPreference;
+use crate::utils::io_throttle::ScanThrottle;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Current config format version, bumped on incompatible changes
+pub const CONFIG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    #[serde(default = "default_version")]
+    pub version: u32,
     #[serde(default)]
     pub custom_scanners: Vec<CustomScannerConfig>,
+    /// Paths that should never be cleaned
+    #[serde(default)]
+    pub exclusions: Vec<String>,
+    /// Paths kept when the cache containing them is cleaned
+    #[serde(default)]
+    pub pinned_paths: Vec<String>,
+    /// Globs for files kept inside any cache, e.g. `**/*.keep`
+    #[serde(default)]
+    pub preserve_globs: Vec<String>,
+    /// Move cleaned entries to the app trash instead of deleting them
+    #[serde(default)]
+    pub use_trash: bool,
+    /// Let scheduled work run while the Mac is on battery
+    #[serde(default)]
+    pub run_on_battery: bool,
+    /// How long a full scan result is reused before the disk is walked again
+    #[serde(default = "default_scan_cache_ttl_secs")]
+    pub scan_cache_ttl_secs: u64,
+    /// How much clean results report beyond their counters
+    #[serde(default)]
+    pub verbosity: Verbosity,
+    /// Extra folders project scanners search when no roots are given
+    #[serde(default)]
+    pub project_roots: Vec<String>,
+    /// Pacing for directory walks, so scans don't starve other apps of I/O
+    #[serde(default)]
+    pub scan_throttle: ScanThrottle,
+    /// Units and precision of sizes written into messages and reasons
+    #[serde(default)]
+    pub size_units: SizeUnitPreference,
+    /// Report processes holding cache files open before cleaning (runs `lsof`)
+    #[serde(default)]
+    pub check_open_files: bool,
+    /// Count dot-entries such as `.DS_Store` toward sizes and item counts
+    #[serde(default = "default_include_hidden_in_size")]
+    pub include_hidden_in_size: bool,
+    /// How smart suggestions are ordered
+    #[serde(default)]
+    pub suggestion_ranking: RankingStrategy,
+    /// Compare disk free space before and after each clean (runs `diskutil`)
+    #[serde(default)]
+    pub verify_disk_space: bool,
+}
+
+/// Outcome of importing a config file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub scanners_added: usize,
+    pub scanners_skipped: usize,
+    pub exclusions_added: usize,
+    pub exclusions_skipped: usize,
+}
+
+fn default_version() -> u32 {
+    CONFIG_VERSION
+}
+
+fn default_scan_cache_ttl_secs() -> u64 {
+    DEFAULT_SCAN_CACHE_TTL_SECS
+}
+
+fn default_include_hidden_in_size() -> bool {
+    true
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            custom_scanners: Vec::new(),
+            exclusions: Vec::new(),
+            pinned_paths: Vec::new(),
+            preserve_globs: Vec::new(),
+            use_trash: false,
+            run_on_battery: false,
+            scan_cache_ttl_secs: DEFAULT_SCAN_CACHE_TTL_SECS,
+            verbosity: Verbosity::default(),
+            project_roots: Vec::new(),
+            scan_throttle: ScanThrottle::default(),
+            size_units: SizeUnitPreference::default(),
+            check_open_files: false,
+            include_hidden_in_size: true,
+            suggestion_ranking: RankingStrategy::default(),
+            verify_disk_space: false,
+        }
+    }
 }
 
 impl AppConfig {
@@ -29,6 +126,22 @@ impl AppConfig {
         Ok(())
     }
 
+    /// Put every tunable (scan cache TTL, verbosity, battery schedule, I/O
+    /// throttle) back to its default. What the user curated is kept: custom
+    /// scanners, exclusions, pinned paths, preserve globs, project roots and the
+    /// trash preference. Premium status lives in its own file and is not touched.
+    pub fn reset_tunables(&mut self) {
+        *self = AppConfig {
+            custom_scanners: std::mem::take(&mut self.custom_scanners),
+            exclusions: std::mem::take(&mut self.exclusions),
+            pinned_paths: std::mem::take(&mut self.pinned_paths),
+            preserve_globs: std::mem::take(&mut self.preserve_globs),
+            project_roots: std::mem::take(&mut self.project_roots),
+            use_trash: self.use_trash,
+            ..AppConfig::default()
+        };
+    }
+
     pub fn add_scanner(&mut self, config: CustomScannerConfig) {
         self.custom_scanners.retain(|s| s.id != config.id);
         self.custom_scanners.push(config);
@@ -39,9 +152,249 @@ impl AppConfig {
         self.custom_scanners.retain(|s| s.id != id);
         self.custom_scanners.len() < len
     }
+
+    /// Write this config to an arbitrary file for sharing across machines
+    pub fn export_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Import a previously exported config.
+    ///
+    /// With `merge`, scanners are unioned by id and exclusions by path, keeping
+    /// existing entries. Without it, scanners and exclusions are replaced.
+    pub fn import_from(&mut self, path: &Path, merge: bool) -> Result<ImportSummary> {
+        let content = std::fs::read_to_string(path)?;
+        let imported = parse_import(&content)?;
+
+        let mut summary = ImportSummary {
+            scanners_added: 0,
+            scanners_skipped: 0,
+            exclusions_added: 0,
+            exclusions_skipped: 0,
+        };
+
+        if !merge {
+            summary.scanners_added = imported.custom_scanners.len();
+            summary.exclusions_added = imported.exclusions.len();
+            self.custom_scanners = imported.custom_scanners;
+            self.exclusions = imported.exclusions;
+            return Ok(summary);
+        }
+
+        for scanner in imported.custom_scanners {
+            if self.custom_scanners.iter().any(|s| s.id == scanner.id) {
+                summary.scanners_skipped += 1;
+            } else {
+                self.custom_scanners.push(scanner);
+                summary.scanners_added += 1;
+            }
+        }
+
+        for exclusion in imported.exclusions {
+            if self.exclusions.contains(&exclusion) {
+                summary.exclusions_skipped += 1;
+            } else {
+                self.exclusions.push(exclusion);
+                summary.exclusions_added += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Validate the structure and version of an imported config
+fn parse_import(content: &str) -> Result<AppConfig> {
+    let value: serde_json::Value = serde_json::from_str(content)?;
+    let object = value
+        .as_object()
+        .ok_or_else(|| anyhow::anyhow!("Invalid config: expected a JSON object"))?;
+
+    let version = object
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| anyhow::anyhow!("Invalid config: missing version"))?;
+    if version != CONFIG_VERSION as u64 {
+        return Err(anyhow::anyhow!(
+            "Unsupported config version {} (expected {})",
+            version,
+            CONFIG_VERSION
+        ));
+    }
+
+    let config: AppConfig = serde_json::from_value(value)?;
+    for scanner in &config.custom_scanners {
+        if scanner.id.trim().is_empty() || scanner.path.trim().is_empty() {
+            return Err(anyhow::anyhow!("Invalid config: custom scanner with empty id or path"));
+        }
+    }
+    Ok(config)
+}
+
+/// Reset the saved config's tunables to their defaults, returning the result
+pub fn reset_settings_to_defaults() -> Result<AppConfig> {
+    let mut config = AppConfig::load()?;
+    config.reset_tunables();
+    config.save()?;
+    Ok(config)
 }
 
 fn config_path() -> Result<PathBuf> {
-    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
-    Ok(home.join(".cache-cleaner/config.json"))
+    Ok(app_data_dir()?.join("config.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::format::SizeUnits;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn scanner(id: &str, path: &str) -> CustomScannerConfig {
+        CustomScannerConfig {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: path.to_string(),
+            min_size_mb: None,
+            clean_command: None,
+            allow_clean_command: false,
+        }
+    }
+
+    #[test]
+    fn test_default_has_current_version() {
+        assert_eq!(AppConfig::default().version, CONFIG_VERSION);
+    }
+
+    #[test]
+    fn test_legacy_config_without_version_loads() {
+        let config: AppConfig = serde_json::from_str(r#"{"custom_scanners": []}"#).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert!(config.exclusions.is_empty());
+        assert_eq!(config.scan_cache_ttl_secs, DEFAULT_SCAN_CACHE_TTL_SECS);
+    }
+
+    #[test]
+    fn test_reset_keeps_curated_settings() {
+        let mut config = AppConfig {
+            custom_scanners: vec![scanner("bazel", "~/.cache/bazel")],
+            exclusions: vec!["~/Library/Caches/keep".to_string()],
+            pinned_paths: vec!["~/.npm/_cacache/pinned".to_string()],
+            preserve_globs: vec!["**/*.keep".to_string()],
+            use_trash: true,
+            run_on_battery: true,
+            scan_cache_ttl_secs: 600,
+            verbosity: Verbosity::Verbose,
+            project_roots: vec!["~/clients".to_string()],
+            scan_throttle: ScanThrottle { max_dirs_per_sec: Some(50) },
+            size_units: SizeUnitPreference { units: SizeUnits::Decimal, decimals: 2 },
+            check_open_files: true,
+            include_hidden_in_size: false,
+            suggestion_ranking: RankingStrategy::SizeFirst,
+            verify_disk_space: true,
+            ..AppConfig::default()
+        };
+        config.reset_tunables();
+
+        assert!(!config.run_on_battery);
+        assert_eq!(config.scan_cache_ttl_secs, DEFAULT_SCAN_CACHE_TTL_SECS);
+        assert_eq!(config.verbosity, Verbosity::Normal);
+        assert_eq!(config.scan_throttle, ScanThrottle::default());
+        assert_eq!(config.size_units, SizeUnitPreference::default());
+        assert!(!config.check_open_files);
+        assert!(config.include_hidden_in_size);
+        assert_eq!(config.suggestion_ranking, RankingStrategy::Default);
+        assert!(!config.verify_disk_space);
+
+        assert_eq!(config.custom_scanners.len(), 1);
+        assert_eq!(config.custom_scanners[0].id, "bazel");
+        assert_eq!(config.exclusions, vec!["~/Library/Caches/keep"]);
+        assert_eq!(config.pinned_paths, vec!["~/.npm/_cacache/pinned"]);
+        assert_eq!(config.preserve_globs, vec!["**/*.keep"]);
+        assert_eq!(config.project_roots, vec!["~/clients"]);
+        assert!(config.use_trash);
+    }
+
+    #[test]
+    fn test_clean_import() {
+        let dir = create_test_dir();
+        let file = dir.path().join("export.json");
+
+        let mut exported = AppConfig::default();
+        exported.add_scanner(scanner("gradle", "~/.gradle/caches"));
+        exported.exclusions.push("/Users/test/Library/Caches/keep".to_string());
+        exported.export_to(&file).unwrap();
+
+        let mut config = AppConfig::default();
+        config.add_scanner(scanner("old", "~/old"));
+        let summary = config.import_from(&file, false).unwrap();
+
+        assert_eq!(summary.scanners_added, 1);
+        assert_eq!(summary.exclusions_added, 1);
+        assert_eq!(config.custom_scanners.len(), 1);
+        assert_eq!(config.custom_scanners[0].id, "gradle");
+        assert_eq!(config.exclusions.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_dedups_by_id_and_path() {
+        let dir = create_test_dir();
+        let file = dir.path().join("export.json");
+
+        let mut exported = AppConfig::default();
+        exported.add_scanner(scanner("gradle", "~/.gradle/caches"));
+        exported.add_scanner(scanner("go", "~/go/pkg/mod"));
+        exported.exclusions = vec!["/keep/a".to_string(), "/keep/b".to_string()];
+        exported.export_to(&file).unwrap();
+
+        let mut config = AppConfig::default();
+        config.add_scanner(scanner("gradle", "~/custom/gradle"));
+        config.exclusions.push("/keep/a".to_string());
+
+        let summary = config.import_from(&file, true).unwrap();
+        assert_eq!(summary.scanners_added, 1);
+        assert_eq!(summary.scanners_skipped, 1);
+        assert_eq!(summary.exclusions_added, 1);
+        assert_eq!(summary.exclusions_skipped, 1);
+
+        assert_eq!(config.custom_scanners.len(), 2);
+        // Existing scanner wins on id conflict
+        let gradle = config.custom_scanners.iter().find(|s| s.id == "gradle").unwrap();
+        assert_eq!(gradle.path, "~/custom/gradle");
+        assert_eq!(config.exclusions.len(), 2);
+    }
+
+    #[test]
+    fn test_version_mismatch_rejected() {
+        let dir = create_test_dir();
+        let file = dir.path().join("export.json");
+        std::fs::write(&file, r#"{"version": 99, "custom_scanners": []}"#).unwrap();
+
+        let mut config = AppConfig::default();
+        config.add_scanner(scanner("keep", "~/keep"));
+        let result = config.import_from(&file, false);
+
+        assert!(result.is_err());
+        assert_eq!(config.custom_scanners.len(), 1);
+    }
+
+    #[test]
+    fn test_missing_version_rejected() {
+        assert!(parse_import(r#"{"custom_scanners": []}"#).is_err());
+        assert!(parse_import("[]").is_err());
+    }
+
+    #[test]
+    fn test_invalid_scanner_rejected() {
+        let content = r#"{"version": 1, "custom_scanners": [{"id": "", "name": "x", "path": "~/x"}]}"#;
+        assert!(parse_import(content).is_err());
+    }
 }
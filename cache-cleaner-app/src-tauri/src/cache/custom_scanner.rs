@@ -1,9 +1,17 @@
+use super::guard;
 use super::scanner_trait::{CacheCleaner, CacheScanner, CleanResultGeneric, ScanResult};
+use crate::utils::command::split_command_line;
 use crate::utils::filesystem;
+use crate::utils::format::parse_reclaimed_space;
+use crate::utils::home::require_home;
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::time::Duration;
+
+/// Longest a clean command may run before it is killed
+const CLEAN_COMMAND_TIMEOUT: Duration = Duration::from_secs(10 * 60);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CustomScannerConfig {
@@ -12,6 +20,12 @@ pub struct CustomScannerConfig {
     pub path: String,
     #[serde(default)]
     pub min_size_mb: Option<u64>,
+    /// Tool command to run instead of deleting `path`, e.g. `go clean -cache`
+    #[serde(default)]
+    pub clean_command: Option<String>,
+    /// The user explicitly allowed `clean_command` to run
+    #[serde(default)]
+    pub allow_clean_command: bool,
 }
 
 pub struct CustomScanner {
@@ -28,6 +42,23 @@ impl CustomScanner {
     pub fn config(&self) -> &CustomScannerConfig {
         &self.config
     }
+
+    /// Reject a path the guard would never let us clean, or a clean command
+    /// that could not be run safely
+    pub fn validate(&self) -> Result<()> {
+        guard::assert_safe_to_delete(&self.resolved_path)?;
+        if let Some(command) = &self.config.clean_command {
+            split_command_line(command)?;
+        }
+        Ok(())
+    }
+}
+
+/// Validate `config` and scan its path without registering or saving it
+pub async fn preview_custom_scanner(config: CustomScannerConfig) -> Result<ScanResult> {
+    let scanner = CustomScanner::new(config)?;
+    scanner.validate()?;
+    scanner.scan().await
 }
 
 #[async_trait]
@@ -68,6 +99,10 @@ impl CacheCleaner for CustomScanner {
     }
 
     async fn clean(&self, dry_run: bool) -> Result<CleanResultGeneric> {
+        if let Some(command) = &self.config.clean_command {
+            return self.clean_with_command(command, dry_run).await;
+        }
+
         if !self.resolved_path.exists() {
             return Ok(CleanResultGeneric {
                 id: self.config.id.clone(),
@@ -93,6 +128,7 @@ impl CacheCleaner for CustomScanner {
             });
         }
 
+        guard::ensure_deletion_enabled()?;
         filesystem::remove_dir_contents(&self.resolved_path)?;
 
         Ok(CleanResultGeneric {
@@ -106,11 +142,181 @@ impl CacheCleaner for CustomScanner {
     }
 }
 
+impl CustomScanner {
+    /// Run the configured tool command; `path` is only measured
+    async fn clean_with_command(&self, command: &str, dry_run: bool) -> Result<CleanResultGeneric> {
+        let result = |freed_bytes: u64, success: bool, message: String| CleanResultGeneric {
+            id: self.config.id.clone(),
+            freed_bytes,
+            items_removed: 0,
+            success,
+            message,
+            dry_run,
+        };
+
+        if !self.config.allow_clean_command {
+            return Ok(result(0, false, "Clean command has not been allowed".to_string()));
+        }
+        let argv = split_command_line(command)?;
+
+        let size_before = if self.resolved_path.exists() {
+            filesystem::calculate_dir_size(&self.resolved_path).await?
+        } else {
+            0
+        };
+        if dry_run {
+            return Ok(result(size_before, true, format!("Would run `{}`", command)));
+        }
+
+        guard::ensure_deletion_enabled()?;
+        if filesystem::skip_deletion() {
+            return Ok(result(size_before, true, format!("Would run `{}` (read-only mode)", command)));
+        }
+
+        let child = tokio::process::Command::new(&argv[0])
+            .args(&argv[1..])
+            .stdin(std::process::Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = match tokio::time::timeout(CLEAN_COMMAND_TIMEOUT, child).await {
+            Ok(output) => output?,
+            Err(_) => {
+                return Ok(result(
+                    0,
+                    false,
+                    format!("`{}` timed out after {}s", command, CLEAN_COMMAND_TIMEOUT.as_secs()),
+                ))
+            }
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() {
+            return Ok(result(0, false, format!("`{}` failed: {}", command, stderr.trim())));
+        }
+
+        // Prefer what the tool reports, otherwise measure the path again
+        let freed_bytes = match parse_reclaimed_space(&format!("{}\n{}", stdout, stderr)) {
+            0 if self.resolved_path.exists() => {
+                let size_after = filesystem::calculate_dir_size(&self.resolved_path).await.unwrap_or(size_before);
+                size_before.saturating_sub(size_after)
+            }
+            0 => size_before,
+            reported => reported,
+        };
+        Ok(result(freed_bytes, true, format!("Ran `{}`", command)))
+    }
+}
+
 fn expand_path(path: &str) -> Result<PathBuf> {
     if path.starts_with("~/") {
-        let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+        let home = require_home()?;
         Ok(home.join(&path[2..]))
     } else {
         Ok(PathBuf::from(path))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn scanner(path: &std::path::Path, clean_command: Option<&str>) -> CustomScanner {
+        CustomScanner::new(CustomScannerConfig {
+            id: "custom".to_string(),
+            name: "Custom".to_string(),
+            path: path.display().to_string(),
+            min_size_mb: None,
+            clean_command: clean_command.map(|c| c.to_string()),
+            allow_clean_command: true,
+        })
+        .unwrap()
+    }
+
+    #[cfg(not(feature = "readonly"))]
+    #[tokio::test]
+    async fn test_clean_command_estimated_read_only() {
+        guard::allow_root_for_tests();
+        let dir = create_test_dir();
+        fs::write(dir.path().join("kept.bin"), [0u8; 100]).unwrap();
+        let marker = dir.path().join("ran");
+        let command = format!("touch {}", marker.display());
+
+        crate::cache::guard::set_read_only_for_thread(true);
+        let result = scanner(dir.path(), Some(&command)).clean(false).await;
+        crate::cache::guard::set_read_only_for_thread(false);
+
+        let result = result.unwrap();
+        assert!(result.success);
+        assert_eq!(result.freed_bytes, 100);
+        assert!(result.message.contains("read-only mode"));
+        assert!(!marker.exists());
+    }
+
+    #[tokio::test]
+    async fn test_clean_command_runs_instead_of_deleting() {
+        guard::allow_root_for_tests();
+        let dir = create_test_dir();
+        fs::write(dir.path().join("kept.bin"), [0u8; 100]).unwrap();
+
+        let result = scanner(dir.path(), Some("echo Total reclaimed space: 1KB"))
+            .clean(false)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.freed_bytes, 1024);
+        assert!(dir.path().join("kept.bin").exists());
+    }
+
+    #[tokio::test]
+    async fn test_clean_command_requires_opt_in() {
+        guard::allow_root_for_tests();
+        let dir = create_test_dir();
+        let mut config = scanner(dir.path(), Some("echo hi")).config().clone();
+        config.allow_clean_command = false;
+
+        let result = CustomScanner::new(config).unwrap().clean(false).await.unwrap();
+        assert!(!result.success);
+    }
+
+    #[tokio::test]
+    async fn test_preview_reports_size() {
+        let dir = create_test_dir();
+        fs::write(dir.path().join("cache.bin"), [0u8; 100]).unwrap();
+
+        let config = scanner(dir.path(), Some("go clean -cache")).config().clone();
+        let result = preview_custom_scanner(config).await.unwrap();
+        assert!(result.exists);
+        assert_eq!(result.size_bytes, 100);
+        assert!(dir.path().join("cache.bin").exists());
+    }
+
+    #[tokio::test]
+    async fn test_preview_rejects_unsafe_config() {
+        let mut config = scanner(std::path::Path::new("/"), None).config().clone();
+        assert!(preview_custom_scanner(config.clone()).await.is_err());
+
+        let dir = create_test_dir();
+        config.path = dir.path().display().to_string();
+        config.clean_command = Some("rm -rf ~ ; echo".to_string());
+        assert!(preview_custom_scanner(config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_without_command_falls_back_to_deletion() {
+        guard::allow_root_for_tests();
+        let dir = create_test_dir();
+        fs::write(dir.path().join("cache.bin"), [0u8; 100]).unwrap();
+
+        let result = scanner(dir.path(), None).clean(false).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.freed_bytes, 100);
+        assert!(!dir.path().join("cache.bin").exists());
+    }
+}
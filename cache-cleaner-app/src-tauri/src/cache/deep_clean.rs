@@ -0,0 +1,461 @@
+//! One-button "deep clean" of every cache that is safe to clear.
+//!
+//! Steps run in a fixed order: package managers first (cheap to rebuild),
+//! then browsers, Xcode DerivedData, logs and finally temp files. A step is
+//! skipped when the app owning it is running or its directory is excluded.
+//! Inside a step, top-level entries matching an exclusion or a pinned path are
+//! kept, and with `use_trash` everything else goes to the app trash instead of
+//! being deleted.
+
+use super::config::AppConfig;
+use super::paths::MacPaths;
+use super::{guard, trash, CacheError, CacheType, CleanStrategy};
+use crate::utils::filesystem;
+use crate::utils::home::app_data_dir;
+use crate::utils::permissions;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeepCleanOptions {
+    /// Paths that are never cleaned
+    #[serde(default)]
+    pub exclusions: Vec<String>,
+    /// Paths kept even when the cache around them is cleaned
+    #[serde(default)]
+    pub pinned_paths: Vec<String>,
+    /// Move entries to the app trash instead of deleting them
+    #[serde(default)]
+    pub use_trash: bool,
+}
+
+impl DeepCleanOptions {
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self {
+            exclusions: config.exclusions.clone(),
+            pinned_paths: config.pinned_paths.clone(),
+            use_trash: config.use_trash,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeepCleanStatus {
+    Cleaned,
+    Skipped,
+    Failed,
+}
+
+/// Outcome of one deep clean step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepCleanCategoryResult {
+    pub cache_type: CacheType,
+    pub status: DeepCleanStatus,
+    pub freed_bytes: u64,
+    pub items_removed: usize,
+    pub kept_entries: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepCleanResult {
+    pub results: Vec<DeepCleanCategoryResult>,
+    pub total_freed_bytes: u64,
+    pub cleaned: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub dry_run: bool,
+}
+
+/// A cache directory cleaned by deep clean
+#[derive(Debug, Clone)]
+pub struct DeepCleanStep {
+    pub cache_type: CacheType,
+    pub path: PathBuf,
+    /// Process name of the app that owns the cache, if any
+    pub owning_app: Option<&'static str>,
+}
+
+/// Deep clean the safe subset of caches, in order
+pub fn deep_clean(options: DeepCleanOptions, dry_run: bool) -> Result<DeepCleanResult> {
+    if !dry_run {
+        guard::ensure_deletion_enabled()?;
+    }
+    let trash_root = app_data_dir()?.join("trash");
+    Ok(deep_clean_steps(
+        default_steps()?,
+        &options,
+        dry_run,
+        |app| permissions::is_app_running(app).unwrap_or(false),
+        &trash_root,
+    ))
+}
+
+/// The safe subset, in the order it is cleaned
+pub fn default_steps() -> Result<Vec<DeepCleanStep>> {
+    let step = |cache_type: CacheType, root: PathBuf, owning_app: Option<&'static str>| {
+        let path = match cache_type.clean_strategy() {
+            CleanStrategy::RemoveSubpath(subpath) => root.join(subpath),
+            CleanStrategy::RemoveContents | CleanStrategy::RunCommand(_) => root,
+        };
+        DeepCleanStep {
+            cache_type,
+            path,
+            owning_app,
+        }
+    };
+
+    // Roots of the cache types, as `cleaner` uses them; the clean strategy
+    // adds any subpath
+    let home = MacPaths::home()?;
+    Ok(vec![
+        // Package managers rebuild their caches on the next install
+        step(CacheType::Npm, home.join(".npm"), None),
+        step(CacheType::Yarn, MacPaths::yarn_cache()?, None),
+        step(CacheType::Pnpm, MacPaths::pnpm_cache()?, None),
+        step(CacheType::Pip, MacPaths::pip_cache()?, None),
+        step(CacheType::CocoaPods, MacPaths::cocoapods_cache()?, None),
+        step(CacheType::Gradle, MacPaths::gradle_cache()?, None),
+        step(CacheType::Cargo, home.join(".cargo"), None),
+        // Browsers, only while they are closed
+        step(CacheType::Chrome, MacPaths::chrome_cache()?, Some("Google Chrome")),
+        step(CacheType::Safari, MacPaths::safari_cache()?, Some("Safari")),
+        step(CacheType::Firefox, MacPaths::firefox_profiles()?, Some("firefox")),
+        step(CacheType::Arc, MacPaths::arc_cache()?, Some("Arc")),
+        // Build output, logs and temp files last
+        step(CacheType::XcodeDerivedData, MacPaths::xcode_derived_data()?, Some("Xcode")),
+        step(CacheType::UserLogs, MacPaths::user_logs()?, None),
+        step(CacheType::TempFiles, MacPaths::tmp(), None),
+    ])
+}
+
+fn deep_clean_steps<F>(
+    steps: Vec<DeepCleanStep>,
+    options: &DeepCleanOptions,
+    dry_run: bool,
+    is_running: F,
+    trash_root: &Path,
+) -> DeepCleanResult
+where
+    F: Fn(&str) -> bool,
+{
+    let exclusions: Vec<PathBuf> = options.exclusions.iter().map(PathBuf::from).collect();
+    let mut kept: Vec<PathBuf> = exclusions.clone();
+    kept.extend(options.pinned_paths.iter().map(PathBuf::from));
+
+    let mut results = Vec::new();
+    for step in steps {
+        let skip_reason = if let Some(app) = step.owning_app.filter(|app| is_running(app)) {
+            Some(format!("{} is running", app))
+        } else if exclusions.iter().any(|e| step.path.starts_with(e)) {
+            Some("Excluded".to_string())
+        } else if !step.path.exists() {
+            Some("Cache directory does not exist".to_string())
+        } else {
+            None
+        };
+
+        let result = match skip_reason {
+            Some(message) => DeepCleanCategoryResult {
+                cache_type: step.cache_type,
+                status: DeepCleanStatus::Skipped,
+                freed_bytes: 0,
+                items_removed: 0,
+                kept_entries: 0,
+                message,
+            },
+            None => clean_step(&step, &kept, options.use_trash, dry_run, trash_root),
+        };
+        results.push(result);
+    }
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    DeepCleanResult {
+        total_freed_bytes: results.iter().map(|r| r.freed_bytes).sum(),
+        cleaned: count(DeepCleanStatus::Cleaned),
+        skipped: count(DeepCleanStatus::Skipped),
+        failed: count(DeepCleanStatus::Failed),
+        results,
+        dry_run,
+    }
+}
+
+/// Clean the top-level entries of one step, keeping protected ones
+fn clean_step(
+    step: &DeepCleanStep,
+    kept: &[PathBuf],
+    use_trash: bool,
+    dry_run: bool,
+    trash_root: &Path,
+) -> DeepCleanCategoryResult {
+    let mut freed_bytes = 0u64;
+    let mut items_removed = 0usize;
+    let mut kept_entries = 0usize;
+    let mut errors = Vec::new();
+
+    let entries = match std::fs::read_dir(&step.path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            return DeepCleanCategoryResult {
+                cache_type: step.cache_type.clone(),
+                status: DeepCleanStatus::Failed,
+                freed_bytes: 0,
+                items_removed: 0,
+                kept_entries: 0,
+                message: CacheError::from_io(&step.path, e).to_string(),
+            };
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Keep an entry that is, contains, or sits inside a protected path
+        if kept.iter().any(|k| k.starts_with(&path) || path.starts_with(k)) {
+            kept_entries += 1;
+            continue;
+        }
+        if let Err(e) = guard::assert_safe_to_delete(&path) {
+            errors.push(e.to_string());
+            continue;
+        }
+
+        let size = entry_size(&path);
+        let items = if path.is_dir() {
+            filesystem::count_items(&path).unwrap_or(0) + 1
+        } else {
+            1
+        };
+        if dry_run {
+            freed_bytes += size;
+            items_removed += items;
+            continue;
+        }
+
+        match remove_entry(&path, use_trash, trash_root) {
+            Ok(()) => {
+                freed_bytes += size;
+                items_removed += items;
+            }
+            Err(e) => {
+                // A partial delete still freed whatever is gone now
+                freed_bytes += size.saturating_sub(entry_size(&path));
+                errors.push(e.to_string());
+            }
+        }
+    }
+
+    let verb = if dry_run { "Would free" } else { "Freed" };
+    let (status, message) = if errors.is_empty() {
+        (
+            DeepCleanStatus::Cleaned,
+            format!("{} {} bytes ({} items)", verb, freed_bytes, items_removed),
+        )
+    } else {
+        (
+            DeepCleanStatus::Failed,
+            format!("{} {} bytes, {} errors: {}", verb, freed_bytes, errors.len(), errors.join("; ")),
+        )
+    };
+
+    DeepCleanCategoryResult {
+        cache_type: step.cache_type.clone(),
+        status,
+        freed_bytes,
+        items_removed,
+        kept_entries,
+        message,
+    }
+}
+
+fn remove_entry(path: &Path, use_trash: bool, trash_root: &Path) -> Result<()> {
+    // In read-only mode nothing is moved either; the delete below is a no-op
+    if use_trash && !filesystem::skip_deletion() {
+        trash::move_to_trash_in(trash_root, path)?;
+        return Ok(());
+    }
+    let removed = match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => filesystem::delete_dir_all(path),
+        Ok(_) => filesystem::delete_file(path),
+        Err(e) => Err(e),
+    };
+    removed.map_err(|e| CacheError::from_io(path, e).into())
+}
+
+fn entry_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        filesystem::calculate_dir_size_sync(path).unwrap_or(0)
+    } else {
+        std::fs::symlink_metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::home::set_home_for_thread;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn create_test_file(path: &Path, size: usize) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![0u8; size]).unwrap();
+    }
+
+    /// One step per cache type, each with a 100-byte entry
+    fn fixture_steps(root: &Path, types: &[(CacheType, Option<&'static str>)]) -> Vec<DeepCleanStep> {
+        types
+            .iter()
+            .enumerate()
+            .map(|(i, (cache_type, owning_app))| {
+                let path = root.join(format!("step{}", i));
+                create_test_file(&path.join("entry/data.bin"), 100);
+                DeepCleanStep {
+                    cache_type: cache_type.clone(),
+                    path,
+                    owning_app: *owning_app,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_default_steps_order() {
+        let types: Vec<CacheType> = default_steps().unwrap().into_iter().map(|s| s.cache_type).collect();
+        let position = |t: CacheType| types.iter().position(|c| *c == t).unwrap();
+
+        assert!(position(CacheType::Gradle) < position(CacheType::Chrome));
+        assert!(position(CacheType::Arc) < position(CacheType::XcodeDerivedData));
+        assert!(position(CacheType::XcodeDerivedData) < position(CacheType::UserLogs));
+        assert_eq!(types.last(), Some(&CacheType::TempFiles));
+    }
+
+    #[test]
+    fn test_npm_step_empties_cacache() {
+        let home = create_test_dir();
+        let cacache = home.path().join(".npm/_cacache");
+        create_test_file(&cacache.join("content-v2/blob"), 100);
+        let logs = home.path().join(".npm/_logs/debug.log");
+        create_test_file(&logs, 10);
+
+        set_home_for_thread(Some(home.path().to_path_buf()));
+        let steps = default_steps();
+        set_home_for_thread(None);
+        let npm: Vec<DeepCleanStep> = steps
+            .unwrap()
+            .into_iter()
+            .filter(|s| s.cache_type == CacheType::Npm)
+            .collect();
+        assert_eq!(npm[0].path, cacache);
+
+        let trash = home.path().join("trash");
+        let result = deep_clean_steps(npm, &DeepCleanOptions::default(), false, |_| false, &trash);
+        assert_eq!(result.results[0].status, DeepCleanStatus::Cleaned);
+        assert_eq!(result.total_freed_bytes, 100);
+        assert!(cacache.exists());
+        assert_eq!(fs::read_dir(&cacache).unwrap().count(), 0);
+        assert!(logs.exists());
+    }
+
+    #[test]
+    fn test_results_follow_step_order() {
+        let root = create_test_dir();
+        let steps = fixture_steps(
+            root.path(),
+            &[(CacheType::Npm, None), (CacheType::Safari, None), (CacheType::UserLogs, None)],
+        );
+        let result = deep_clean_steps(steps, &DeepCleanOptions::default(), true, |_| false, root.path());
+
+        let types: Vec<CacheType> = result.results.iter().map(|r| r.cache_type.clone()).collect();
+        assert_eq!(types, vec![CacheType::Npm, CacheType::Safari, CacheType::UserLogs]);
+        assert_eq!(result.cleaned, 3);
+        assert_eq!(result.total_freed_bytes, 300);
+    }
+
+    #[test]
+    fn test_running_app_category_is_skipped() {
+        let root = create_test_dir();
+        let steps = fixture_steps(
+            root.path(),
+            &[(CacheType::Chrome, Some("Google Chrome")), (CacheType::Npm, None)],
+        );
+        let chrome_entry = steps[0].path.join("entry");
+        let npm_entry = steps[1].path.join("entry");
+
+        let result = deep_clean_steps(
+            steps,
+            &DeepCleanOptions::default(),
+            false,
+            |app| app == "Google Chrome",
+            &root.path().join("trash"),
+        );
+
+        assert_eq!(result.results[0].status, DeepCleanStatus::Skipped);
+        assert_eq!(result.results[0].message, "Google Chrome is running");
+        assert!(chrome_entry.exists());
+        assert_eq!(result.results[1].status, DeepCleanStatus::Cleaned);
+        assert!(!npm_entry.exists());
+        assert_eq!(result.total_freed_bytes, 100);
+    }
+
+    #[test]
+    fn test_dry_run_deletes_nothing() {
+        let root = create_test_dir();
+        let steps = fixture_steps(root.path(), &[(CacheType::Npm, None), (CacheType::TempFiles, None)]);
+        let entries: Vec<PathBuf> = steps.iter().map(|s| s.path.join("entry/data.bin")).collect();
+
+        let result = deep_clean_steps(steps, &DeepCleanOptions::default(), true, |_| false, root.path());
+
+        assert!(result.dry_run);
+        assert_eq!(result.total_freed_bytes, 200);
+        for entry in entries {
+            assert!(entry.exists());
+        }
+    }
+
+    #[test]
+    fn test_exclusions_and_pinned_paths_are_kept() {
+        let root = create_test_dir();
+        let steps = fixture_steps(root.path(), &[(CacheType::Npm, None), (CacheType::Yarn, None)]);
+        let pinned = steps[1].path.join("pinned/keep.bin");
+        create_test_file(&pinned, 50);
+
+        let options = DeepCleanOptions {
+            exclusions: vec![steps[0].path.display().to_string()],
+            pinned_paths: vec![pinned.display().to_string()],
+            use_trash: false,
+        };
+        let npm_entry = steps[0].path.join("entry");
+        let yarn_entry = steps[1].path.join("entry");
+        let result = deep_clean_steps(steps, &options, false, |_| false, root.path());
+
+        assert_eq!(result.results[0].status, DeepCleanStatus::Skipped);
+        assert!(npm_entry.exists());
+        assert_eq!(result.results[1].kept_entries, 1);
+        assert!(pinned.exists());
+        assert!(!yarn_entry.exists());
+    }
+
+    #[test]
+    fn test_use_trash_moves_entries() {
+        let root = create_test_dir();
+        let trash_root = root.path().join("trash");
+        let steps = fixture_steps(root.path(), &[(CacheType::Npm, None)]);
+        let entry = steps[0].path.join("entry");
+
+        let options = DeepCleanOptions {
+            use_trash: true,
+            ..Default::default()
+        };
+        let result = deep_clean_steps(steps, &options, false, |_| false, &trash_root);
+
+        assert_eq!(result.results[0].status, DeepCleanStatus::Cleaned);
+        assert!(!entry.exists());
+        assert_eq!(fs::read_dir(&trash_root).unwrap().count(), 1);
+    }
+}
@@ -1,9 +1,11 @@
 use crate::cache::{CacheInfo, CacheType, CleanResult};
 use crate::cache::paths::MacPaths;
+use crate::utils::filesystem;
 use std::fs;
+use std::time::Duration;
 
 pub fn get_xcode_derived_data_info() -> CacheInfo {
-    let path = MacPaths::xcode_derived_data();
+    let path = MacPaths::xcode_derived_data().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = get_cache_stats(&path);
     
@@ -13,11 +15,13 @@ pub fn get_xcode_derived_data_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
 pub fn get_xcode_archives_info() -> CacheInfo {
-    let path = MacPaths::xcode_archives();
+    let path = MacPaths::xcode_archives().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = get_cache_stats(&path);
     
@@ -27,11 +31,13 @@ pub fn get_xcode_archives_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
 pub fn get_xcode_simulators_info() -> CacheInfo {
-    let path = MacPaths::xcode_simulators();
+    let path = MacPaths::xcode_simulators().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = get_cache_stats(&path);
     
@@ -41,10 +47,12 @@ pub fn get_xcode_simulators_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
-pub fn clean_xcode_cache(cache_type: CacheType, dry_run: bool) -> CleanResult {
+pub fn clean_xcode_cache(cache_type: CacheType, dry_run: bool, protect_recent: Option<Duration>) -> CleanResult {
     let path = match cache_type {
         CacheType::XcodeDerivedData => MacPaths::xcode_derived_data(),
         CacheType::XcodeArchives => MacPaths::xcode_archives(),
@@ -56,6 +64,19 @@ pub fn clean_xcode_cache(cache_type: CacheType, dry_run: bool) -> CleanResult {
             success: false,
             message: "Invalid Xcode cache type".to_string(),
             dry_run,
+            ..Default::default()
+        },
+    };
+    let path = match path {
+        Ok(p) => p,
+        Err(e) => return CleanResult {
+            cache_type,
+            freed_bytes: 0,
+            items_removed: 0,
+            success: false,
+            message: e.to_string(),
+            dry_run,
+            ..Default::default()
         },
     };
 
@@ -67,6 +88,7 @@ pub fn clean_xcode_cache(cache_type: CacheType, dry_run: bool) -> CleanResult {
             success: true,
             message: "Cache directory does not exist".to_string(),
             dry_run,
+            ..Default::default()
         };
     }
 
@@ -79,6 +101,7 @@ pub fn clean_xcode_cache(cache_type: CacheType, dry_run: bool) -> CleanResult {
             success: false,
             message: format!("Failed to calculate size: {}", e),
             dry_run,
+            ..Default::default()
         },
     };
 
@@ -90,14 +113,15 @@ pub fn clean_xcode_cache(cache_type: CacheType, dry_run: bool) -> CleanResult {
             success: true,
             message: format!("Would clean {} items ({} bytes)", items_before, size_before),
             dry_run,
+            ..Default::default()
         };
     }
 
     // For Xcode caches, we might want to be more careful
     match cache_type {
-        CacheType::XcodeDerivedData => clean_derived_data(&path, size_before, items_before, dry_run),
-        CacheType::XcodeArchives => clean_archives(&path, size_before, items_before, dry_run),
-        CacheType::XcodeSimulators => clean_simulators(&path, size_before, items_before, dry_run),
+        CacheType::XcodeDerivedData => clean_derived_data(&path, size_before, items_before, protect_recent),
+        CacheType::XcodeArchives => clean_archives(&path, size_before, items_before, protect_recent),
+        CacheType::XcodeSimulators => clean_simulators(&path, size_before, items_before, protect_recent),
         _ => CleanResult {
             cache_type,
             freed_bytes: 0,
@@ -105,71 +129,39 @@ pub fn clean_xcode_cache(cache_type: CacheType, dry_run: bool) -> CleanResult {
             success: false,
             message: "Invalid cache type".to_string(),
             dry_run,
+            ..Default::default()
         },
     }
 }
 
-fn clean_derived_data(path: &std::path::Path, size_before: u64, items_before: usize, dry_run: bool) -> CleanResult {
-    match fs::remove_dir_all(path) {
-        Ok(_) => CleanResult {
-            cache_type: CacheType::XcodeDerivedData,
-            freed_bytes: size_before,
-            items_removed: items_before,
-            success: true,
-            message: "Successfully cleaned Xcode DerivedData".to_string(),
-            dry_run,
-        },
-        Err(e) => CleanResult {
-            cache_type: CacheType::XcodeDerivedData,
-            freed_bytes: 0,
-            items_removed: 0,
-            success: false,
-            message: format!("Failed to clean DerivedData: {}", e),
-            dry_run,
-        },
-    }
+fn clean_derived_data(
+    path: &std::path::Path,
+    size_before: u64,
+    items_before: usize,
+    protect_recent: Option<Duration>,
+) -> CleanResult {
+    let outcome = filesystem::empty_dir_measured(path, size_before, items_before, protect_recent);
+    CleanResult::from_removal(CacheType::XcodeDerivedData, &outcome, "Successfully cleaned Xcode DerivedData".to_string())
 }
 
-fn clean_archives(path: &std::path::Path, size_before: u64, items_before: usize, dry_run: bool) -> CleanResult {
-    match fs::remove_dir_all(path) {
-        Ok(_) => CleanResult {
-            cache_type: CacheType::XcodeArchives,
-            freed_bytes: size_before,
-            items_removed: items_before,
-            success: true,
-            message: "Successfully cleaned Xcode Archives".to_string(),
-            dry_run,
-        },
-        Err(e) => CleanResult {
-            cache_type: CacheType::XcodeArchives,
-            freed_bytes: 0,
-            items_removed: 0,
-            success: false,
-            message: format!("Failed to clean Archives: {}", e),
-            dry_run,
-        },
-    }
+fn clean_archives(
+    path: &std::path::Path,
+    size_before: u64,
+    items_before: usize,
+    protect_recent: Option<Duration>,
+) -> CleanResult {
+    let outcome = filesystem::empty_dir_measured(path, size_before, items_before, protect_recent);
+    CleanResult::from_removal(CacheType::XcodeArchives, &outcome, "Successfully cleaned Xcode Archives".to_string())
 }
 
-fn clean_simulators(path: &std::path::Path, size_before: u64, items_before: usize, dry_run: bool) -> CleanResult {
-    match fs::remove_dir_all(path) {
-        Ok(_) => CleanResult {
-            cache_type: CacheType::XcodeSimulators,
-            freed_bytes: size_before,
-            items_removed: items_before,
-            success: true,
-            message: "Successfully cleaned Xcode Simulators".to_string(),
-            dry_run,
-        },
-        Err(e) => CleanResult {
-            cache_type: CacheType::XcodeSimulators,
-            freed_bytes: 0,
-            items_removed: 0,
-            success: false,
-            message: format!("Failed to clean Simulators: {}", e),
-            dry_run,
-        },
-    }
+fn clean_simulators(
+    path: &std::path::Path,
+    size_before: u64,
+    items_before: usize,
+    protect_recent: Option<Duration>,
+) -> CleanResult {
+    let outcome = filesystem::empty_dir_measured(path, size_before, items_before, protect_recent);
+    CleanResult::from_removal(CacheType::XcodeSimulators, &outcome, "Successfully cleaned Xcode Simulators".to_string())
 }
 
 fn get_cache_stats(path: &std::path::Path) -> (u64, usize, bool) {
@@ -0,0 +1,191 @@
+//! Health checks for caches left broken or half-written.
+//!
+//! A crashed install or browser can leave a cache that causes real errors,
+//! not just wasted space: an npm content cache without its index, Gradle
+//! lock files nobody holds anymore, or browser lock files while the browser
+//! is closed. Cleaning such a cache fixes the problem, so the UI can
+//! recommend it.
+
+use super::paths::MacPaths;
+use super::CacheType;
+use crate::utils::permissions;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+/// Gradle holds its locks only while a build runs
+const STALE_LOCK_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How deep lock files are looked for below a cache root
+const LOCK_SEARCH_DEPTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendedAction {
+    None,
+    Clean,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheDiagnosis {
+    pub cache_type: CacheType,
+    pub healthy: bool,
+    pub issues: Vec<String>,
+    pub recommended_action: RecommendedAction,
+}
+
+impl CacheDiagnosis {
+    fn from_issues(cache_type: CacheType, issues: Vec<String>) -> Self {
+        let healthy = issues.is_empty();
+        Self {
+            cache_type,
+            healthy,
+            issues,
+            recommended_action: if healthy {
+                RecommendedAction::None
+            } else {
+                RecommendedAction::Clean
+            },
+        }
+    }
+}
+
+/// Run the health checks that exist for `cache_type`; other types report healthy
+pub fn diagnose_cache(cache_type: CacheType) -> Result<CacheDiagnosis> {
+    let issues = match &cache_type {
+        CacheType::Npm => diagnose_npm_in(&MacPaths::npm_cache()?),
+        CacheType::Gradle => diagnose_gradle_in(&MacPaths::gradle_cache()?, SystemTime::now()),
+        CacheType::Chrome => diagnose_browser(&MacPaths::chrome_cache()?, "Google Chrome"),
+        CacheType::Safari => diagnose_browser(&MacPaths::safari_cache()?, "Safari"),
+        CacheType::Firefox => diagnose_browser(&MacPaths::firefox_profiles()?, "firefox"),
+        CacheType::Arc => diagnose_browser(&MacPaths::arc_cache()?, "Arc"),
+        _ => Vec::new(),
+    };
+    Ok(CacheDiagnosis::from_issues(cache_type, issues))
+}
+
+/// A content cache whose `index-v5` is missing or unreadable cannot be used
+fn diagnose_npm_in(cacache: &Path) -> Vec<String> {
+    if !cacache.exists() {
+        return Vec::new();
+    }
+    let index = cacache.join("index-v5");
+    if !index.is_dir() {
+        return vec!["npm cache index (_cacache/index-v5) is missing".to_string()];
+    }
+    if std::fs::read_dir(&index).is_err() {
+        return vec!["npm cache index (_cacache/index-v5) is unreadable".to_string()];
+    }
+    Vec::new()
+}
+
+fn diagnose_gradle_in(caches: &Path, now: SystemTime) -> Vec<String> {
+    let stale: Vec<_> = lock_files(caches)
+        .into_iter()
+        .filter(|path| {
+            std::fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| age > STALE_LOCK_AGE)
+        })
+        .collect();
+    if stale.is_empty() {
+        Vec::new()
+    } else {
+        vec![format!("{} Gradle lock file(s) older than a day", stale.len())]
+    }
+}
+
+fn diagnose_browser(cache: &Path, app: &str) -> Vec<String> {
+    let running = permissions::is_app_running(app).unwrap_or(true);
+    diagnose_browser_in(cache, app, running)
+}
+
+/// Lock files are expected while the browser runs, and leftovers otherwise
+fn diagnose_browser_in(cache: &Path, app: &str, app_running: bool) -> Vec<String> {
+    if app_running {
+        return Vec::new();
+    }
+    let locks = lock_files(cache);
+    if locks.is_empty() {
+        Vec::new()
+    } else {
+        vec![format!("{} lock file(s) left behind while {} is closed", locks.len(), app)]
+    }
+}
+
+/// `*.lock` and `LOCK` files below `root`
+fn lock_files(root: &Path) -> Vec<std::path::PathBuf> {
+    if !root.exists() {
+        return Vec::new();
+    }
+    WalkDir::new(root)
+        .max_depth(LOCK_SEARCH_DEPTH)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            name == "LOCK" || name.ends_with(".lock")
+        })
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    #[test]
+    fn test_npm_missing_index() {
+        let root = create_test_dir();
+        fs::create_dir_all(root.path().join("content-v2/sha512")).unwrap();
+        assert_eq!(diagnose_npm_in(root.path()).len(), 1);
+
+        fs::create_dir(root.path().join("index-v5")).unwrap();
+        assert!(diagnose_npm_in(root.path()).is_empty());
+    }
+
+    #[test]
+    fn test_gradle_stale_lock() {
+        let root = create_test_dir();
+        let lock = root.path().join("modules-2/modules-2.lock");
+        fs::create_dir_all(lock.parent().unwrap()).unwrap();
+        fs::write(&lock, b"").unwrap();
+        let two_days_ago = SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60);
+        File::options().write(true).open(&lock).unwrap().set_modified(two_days_ago).unwrap();
+
+        let issues = diagnose_gradle_in(root.path(), SystemTime::now());
+        assert_eq!(issues, vec!["1 Gradle lock file(s) older than a day"]);
+
+        let diagnosis = CacheDiagnosis::from_issues(CacheType::Gradle, issues);
+        assert!(!diagnosis.healthy);
+        assert_eq!(diagnosis.recommended_action, RecommendedAction::Clean);
+    }
+
+    #[test]
+    fn test_gradle_fresh_lock_is_healthy() {
+        let root = create_test_dir();
+        fs::write(root.path().join("journal-1.lock"), b"").unwrap();
+        assert!(diagnose_gradle_in(root.path(), SystemTime::now()).is_empty());
+    }
+
+    #[test]
+    fn test_browser_lock_only_when_closed() {
+        let root = create_test_dir();
+        fs::create_dir_all(root.path().join("Default/Cache")).unwrap();
+        fs::write(root.path().join("Default/LOCK"), b"").unwrap();
+
+        assert!(diagnose_browser_in(root.path(), "Google Chrome", true).is_empty());
+        assert_eq!(diagnose_browser_in(root.path(), "Google Chrome", false).len(), 1);
+    }
+}
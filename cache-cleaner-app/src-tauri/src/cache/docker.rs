@@ -6,6 +6,10 @@
 //! - Clean up resources with smart suggestions
 //! - Handle Docker daemon connectivity
 
+use super::guard;
+use super::reasons::{reason_messages, ReasonCode};
+use crate::utils::format::{format_size, parse_reclaimed_space, parse_size};
+use crate::utils::metrics;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::process::Command;
@@ -117,13 +121,16 @@ pub struct DockerScanResult {
 }
 
 /// Result of a Docker cleanup operation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DockerCleanResult {
     pub freed_bytes: u64,
     pub containers_removed: usize,
     pub images_removed: usize,
     pub volumes_removed: usize,
     pub networks_removed: usize,
+    /// Images that could only go once the stopped containers using them were removed
+    #[serde(default)]
+    pub images_with_dependents: Vec<String>,
     pub success: bool,
     pub message: String,
 }
@@ -137,6 +144,9 @@ pub struct DockerSuggestion {
     pub size: u64,
     pub score: f64,
     pub reasons: Vec<String>,
+    /// Structured form of `reasons` for localization
+    #[serde(default)]
+    pub reason_codes: Vec<ReasonCode>,
     pub auto_select: bool,
 }
 
@@ -171,7 +181,7 @@ const SIZE_SMALL: u64 = 100 * 1024 * 1024;  // 100MB
 
 /// Check if Docker is installed
 pub fn is_docker_installed() -> bool {
-    Command::new("docker")
+    docker_command()
         .arg("--version")
         .output()
         .map(|o| o.status.success())
@@ -181,7 +191,7 @@ pub fn is_docker_installed() -> bool {
 /// Check if Docker daemon is running
 pub async fn is_docker_running() -> bool {
     tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["info"])
             .output()
             .map(|o| o.status.success())
@@ -260,11 +270,8 @@ pub async fn scan_docker_resources() -> Result<DockerScanResult> {
         .map(|c| c.size)
         .sum();
     
-    let unused_image_size: u64 = images
-        .iter()
-        .filter(|i| i.used_by_containers.is_empty())
-        .map(|i| i.size)
-        .sum();
+    // Images share layers, so count each unused layer once instead of summing image sizes
+    let unused_image_size = image_prune_reclaim(&images).await;
     
     let orphan_volume_size: u64 = volumes
         .iter()
@@ -293,7 +300,7 @@ pub async fn scan_docker_resources() -> Result<DockerScanResult> {
 /// Scan all containers
 async fn scan_containers() -> Result<Vec<DockerContainer>> {
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args([
                 "ps", "-a", "--no-trunc",
                 "--format", "{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.Status}}\t{{.State}}\t{{.Size}}\t{{.CreatedAt}}\t{{.Ports}}"
@@ -316,7 +323,7 @@ async fn scan_containers() -> Result<Vec<DockerContainer>> {
 
         let parts: Vec<&str> = line.split('\t').collect();
         if parts.len() >= 6 {
-            let size = parse_docker_size(parts.get(5).unwrap_or(&"0"));
+            let size = parse_size(parts.get(5).unwrap_or(&"0"));
             
             containers.push(DockerContainer {
                 id: parts[0].to_string(),
@@ -338,7 +345,7 @@ async fn scan_containers() -> Result<Vec<DockerContainer>> {
 async fn scan_images() -> Result<Vec<DockerImage>> {
     // Get all images
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args([
                 "images", "-a", "--no-trunc",
                 "--format", "{{.ID}}\t{{.Repository}}\t{{.Tag}}\t{{.Size}}\t{{.CreatedAt}}"
@@ -353,7 +360,7 @@ async fn scan_images() -> Result<Vec<DockerImage>> {
 
     // Get dangling image IDs
     let dangling_output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["images", "-f", "dangling=true", "-q", "--no-trunc"])
             .output()
     })
@@ -381,7 +388,7 @@ async fn scan_images() -> Result<Vec<DockerImage>> {
             let id = parts[0].to_string();
             let repository = parts[1].to_string();
             let tag = parts[2].to_string();
-            let size = parse_docker_size(parts[3]);
+            let size = parse_size(parts[3]);
             
             let is_dangling = dangling_ids.contains(&id) 
                 || (repository == "<none>" && tag == "<none>");
@@ -409,7 +416,7 @@ async fn scan_images() -> Result<Vec<DockerImage>> {
 /// Get which containers are using which images
 async fn get_container_image_usage() -> Result<std::collections::HashMap<String, Vec<String>>> {
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["ps", "-a", "--no-trunc", "--format", "{{.ID}}\t{{.Image}}"])
             .output()
     })
@@ -419,7 +426,7 @@ async fn get_container_image_usage() -> Result<std::collections::HashMap<String,
     
     // Also get image IDs for each container
     let image_id_output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["inspect", "--format", "{{.Id}}\t{{.Image}}", "-a"])
             .output()
     })
@@ -456,10 +463,146 @@ async fn get_container_image_usage() -> Result<std::collections::HashMap<String,
     Ok(usage)
 }
 
+/// Layers of one image, bottom first, from `docker image inspect`
+#[derive(Debug, Clone, Default)]
+struct ImageLayers {
+    id: String,
+    size: u64,
+    layers: Vec<String>,
+    /// Size of each layer, aligned with `layers`; empty when the history did not line up
+    layer_sizes: Vec<u64>,
+}
+
+/// Estimated space `docker image prune -a` frees, with shared layers counted once
+pub async fn estimated_prune_reclaim() -> u64 {
+    if !is_docker_running().await {
+        return 0;
+    }
+    let images = scan_images().await.unwrap_or_default();
+    image_prune_reclaim(&images).await
+}
+
+/// Layer-aware reclaimable size of the images no container uses, falling back
+/// to the plain sum of image sizes when the layers cannot be inspected
+async fn image_prune_reclaim(images: &[DockerImage]) -> u64 {
+    let naive: u64 = images
+        .iter()
+        .filter(|i| i.used_by_containers.is_empty())
+        .map(|i| i.size)
+        .sum();
+    if images.is_empty() {
+        return 0;
+    }
+
+    let ids: Vec<String> = images.iter().map(|i| i.id.clone()).collect();
+    let in_use: HashSet<String> = images
+        .iter()
+        .filter(|i| !i.used_by_containers.is_empty())
+        .map(|i| normalize_image_id(&i.id))
+        .collect();
+    let Ok(layers) = inspect_image_layers(ids, in_use.clone()).await else {
+        return naive;
+    };
+    layer_aware_reclaim(&layers, &in_use)
+}
+
+/// Layer digests of the given images, with per-layer sizes only for the
+/// prune candidates: in-use images just need their layers marked as kept
+async fn inspect_image_layers(ids: Vec<String>, in_use: HashSet<String>) -> Result<Vec<ImageLayers>> {
+    tokio::task::spawn_blocking(move || {
+        let output = docker_command()
+            .args(["image", "inspect"])
+            .args(&ids)
+            .output()?;
+        if !output.status.success() {
+            return Err(anyhow!("Failed to inspect images"));
+        }
+        let mut images = parse_image_inspect(&String::from_utf8_lossy(&output.stdout))?;
+
+        for image in images.iter_mut().filter(|image| !in_use.contains(&image.id)) {
+            let history = docker_command()
+                .args(["history", "--human=false", "--no-trunc", "--format", "{{.Size}}", &image.id])
+                .output()?;
+            if !history.status.success() {
+                continue;
+            }
+            let sizes: Vec<u64> = String::from_utf8_lossy(&history.stdout)
+                .lines()
+                .filter_map(|line| line.trim().parse().ok())
+                .collect();
+            image.layer_sizes = align_layer_sizes(image.layers.len(), &sizes).unwrap_or_default();
+        }
+        Ok(images)
+    })
+    .await?
+}
+
+fn normalize_image_id(id: &str) -> String {
+    id.trim_start_matches("sha256:").to_string()
+}
+
+/// Parse the JSON array printed by `docker image inspect`
+fn parse_image_inspect(json: &str) -> Result<Vec<ImageLayers>> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(json)?;
+    Ok(entries
+        .iter()
+        .map(|entry| ImageLayers {
+            id: normalize_image_id(entry["Id"].as_str().unwrap_or_default()),
+            size: entry["Size"].as_u64().unwrap_or(0),
+            layers: entry["RootFS"]["Layers"]
+                .as_array()
+                .map(|layers| {
+                    layers
+                        .iter()
+                        .filter_map(|l| l.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            layer_sizes: Vec::new(),
+        })
+        .collect())
+}
+
+/// Match `docker history` sizes (newest first) to RootFS layers (oldest first).
+///
+/// History also lists metadata-only steps, which are always 0 bytes. Once those
+/// are dropped, the remaining steps are the layers, unless some real layer was
+/// empty too; then the counts differ and the sizes cannot be assigned.
+fn align_layer_sizes(layer_count: usize, history_sizes: &[u64]) -> Option<Vec<u64>> {
+    let sizes: Vec<u64> = history_sizes.iter().rev().copied().filter(|size| *size > 0).collect();
+    (sizes.len() == layer_count).then_some(sizes)
+}
+
+/// Bytes freed by removing every image not in `in_use`: layers that an image
+/// in use still needs stay, and each remaining layer counts once
+fn layer_aware_reclaim(images: &[ImageLayers], in_use: &HashSet<String>) -> u64 {
+    let kept: HashSet<&str> = images
+        .iter()
+        .filter(|image| in_use.contains(&image.id))
+        .flat_map(|image| image.layers.iter().map(String::as_str))
+        .collect();
+
+    let mut counted: HashSet<&str> = HashSet::new();
+    let mut total = 0u64;
+    for image in images.iter().filter(|image| !in_use.contains(&image.id)) {
+        if image.layer_sizes.len() != image.layers.len() {
+            // No per-layer breakdown: the image size is the best guess left
+            total += image.size;
+            continue;
+        }
+        for (layer, size) in image.layers.iter().zip(&image.layer_sizes) {
+            if !kept.contains(layer.as_str()) && counted.insert(layer.as_str()) {
+                total += size;
+            }
+        }
+    }
+    total
+}
+
 /// Scan all volumes
 async fn scan_volumes() -> Result<Vec<DockerVolume>> {
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["volume", "ls", "--format", "{{.Name}}\t{{.Driver}}\t{{.Mountpoint}}"])
             .output()
     })
@@ -471,6 +614,7 @@ async fn scan_volumes() -> Result<Vec<DockerVolume>> {
 
     // Get volume usage from containers
     let volume_usage = get_volume_usage().await.unwrap_or_default();
+    let volume_sizes = get_volume_sizes().await.unwrap_or_default();
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let mut volumes = Vec::new();
@@ -484,9 +628,7 @@ async fn scan_volumes() -> Result<Vec<DockerVolume>> {
         if parts.len() >= 2 {
             let name = parts[0].to_string();
             let used_by = volume_usage.get(&name).cloned().unwrap_or_default();
-            
-            // Try to get volume size
-            let size = get_volume_size(&name).await.ok();
+            let size = volume_sizes.get(&name).copied();
 
             volumes.push(DockerVolume {
                 name: name.clone(),
@@ -504,7 +646,7 @@ async fn scan_volumes() -> Result<Vec<DockerVolume>> {
 /// Get volume usage by containers
 async fn get_volume_usage() -> Result<std::collections::HashMap<String, Vec<String>>> {
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["ps", "-a", "--format", "{{.ID}}\t{{.Mounts}}"])
             .output()
     })
@@ -533,33 +675,47 @@ async fn get_volume_usage() -> Result<std::collections::HashMap<String, Vec<Stri
     Ok(usage)
 }
 
-/// Get volume size (approximate)
-async fn get_volume_size(name: &str) -> Result<u64> {
-    let name = name.to_string();
-    let output = tokio::task::spawn_blocking(move || {
-        Command::new("docker")
+/// Size of every volume, from one `docker system df -v` call
+async fn get_volume_sizes() -> Result<std::collections::HashMap<String, u64>> {
+    let output = tokio::task::spawn_blocking(|| {
+        docker_command()
             .args(["system", "df", "-v", "--format", "{{json .}}"])
             .output()
     })
     .await??;
 
-    // Parse JSON output to find volume size
-    // This is simplified - in production, would parse the full JSON
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    
-    // For now, return 0 as getting exact volume size requires more complex parsing
-    // The docker system df command gives aggregate info, not per-volume
-    if stdout.contains(&name) {
-        Ok(0) // Placeholder - would need proper JSON parsing
-    } else {
-        Ok(0)
+    if !output.status.success() {
+        return Err(anyhow!("Failed to read volume sizes"));
+    }
+    Ok(parse_volume_sizes(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Volume name to size from the `Volumes` array of `docker system df -v` JSON
+fn parse_volume_sizes(json: &str) -> std::collections::HashMap<String, u64> {
+    let mut sizes = std::collections::HashMap::new();
+    for line in json.lines().filter(|line| !line.trim().is_empty()) {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let Some(volumes) = value.get("Volumes").and_then(|v| v.as_array()) else {
+            continue;
+        };
+        for volume in volumes {
+            if let (Some(name), Some(size)) = (
+                volume.get("Name").and_then(|v| v.as_str()),
+                volume.get("Size").and_then(|v| v.as_str()),
+            ) {
+                sizes.insert(name.to_string(), parse_size(size));
+            }
+        }
     }
+    sizes
 }
 
 /// Scan all networks
 async fn scan_networks() -> Result<Vec<DockerNetwork>> {
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["network", "ls", "--no-trunc", "--format", "{{.ID}}\t{{.Name}}\t{{.Driver}}\t{{.Scope}}"])
             .output()
     })
@@ -601,7 +757,7 @@ async fn scan_networks() -> Result<Vec<DockerNetwork>> {
 /// Get network usage by containers
 async fn get_network_usage() -> Result<std::collections::HashMap<String, Vec<String>>> {
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["ps", "-a", "--format", "{{.ID}}\t{{.Networks}}"])
             .output()
     })
@@ -633,7 +789,7 @@ async fn get_network_usage() -> Result<std::collections::HashMap<String, Vec<Str
 /// Get build cache size
 async fn get_build_cache_size() -> Result<u64> {
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["system", "df", "--format", "{{.Type}}\t{{.Size}}"])
             .output()
     })
@@ -644,7 +800,7 @@ async fn get_build_cache_size() -> Result<u64> {
     for line in stdout.lines() {
         let parts: Vec<&str> = line.split('\t').collect();
         if parts.len() >= 2 && parts[0].to_lowercase().contains("build") {
-            return Ok(parse_docker_size(parts[1]));
+            return Ok(parse_size(parts[1]));
         }
     }
 
@@ -709,6 +865,7 @@ pub async fn get_unused_networks() -> Result<Vec<DockerNetwork>> {
 
 /// Remove specific containers
 pub async fn remove_containers(ids: Vec<String>, force: bool) -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
     if !is_docker_running().await {
         return Ok(DockerCleanResult {
             freed_bytes: 0,
@@ -718,6 +875,7 @@ pub async fn remove_containers(ids: Vec<String>, force: bool) -> Result<DockerCl
             networks_removed: 0,
             success: false,
             message: "Docker daemon is not running".to_string(),
+            ..Default::default()
         });
     }
 
@@ -730,6 +888,7 @@ pub async fn remove_containers(ids: Vec<String>, force: bool) -> Result<DockerCl
             networks_removed: 0,
             success: true,
             message: "No containers to remove".to_string(),
+            ..Default::default()
         });
     }
 
@@ -757,7 +916,7 @@ pub async fn remove_containers(ids: Vec<String>, force: bool) -> Result<DockerCl
         let args_clone = args.clone();
         
         let output = tokio::task::spawn_blocking(move || {
-            Command::new("docker").args(&args_clone).output()
+            docker_command().args(&args_clone).output()
         })
         .await??;
 
@@ -790,11 +949,13 @@ pub async fn remove_containers(ids: Vec<String>, force: bool) -> Result<DockerCl
         networks_removed: 0,
         success,
         message,
+        ..Default::default()
     })
 }
 
 /// Remove specific images
 pub async fn remove_images(ids: Vec<String>, force: bool) -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
     if !is_docker_running().await {
         return Ok(DockerCleanResult {
             freed_bytes: 0,
@@ -804,6 +965,7 @@ pub async fn remove_images(ids: Vec<String>, force: bool) -> Result<DockerCleanR
             networks_removed: 0,
             success: false,
             message: "Docker daemon is not running".to_string(),
+            ..Default::default()
         });
     }
 
@@ -816,6 +978,7 @@ pub async fn remove_images(ids: Vec<String>, force: bool) -> Result<DockerCleanR
             networks_removed: 0,
             success: true,
             message: "No images to remove".to_string(),
+            ..Default::default()
         });
     }
 
@@ -843,7 +1006,7 @@ pub async fn remove_images(ids: Vec<String>, force: bool) -> Result<DockerCleanR
         let args_clone = args.clone();
         
         let output = tokio::task::spawn_blocking(move || {
-            Command::new("docker").args(&args_clone).output()
+            docker_command().args(&args_clone).output()
         })
         .await??;
 
@@ -876,11 +1039,79 @@ pub async fn remove_images(ids: Vec<String>, force: bool) -> Result<DockerCleanR
         networks_removed: 0,
         success,
         message,
+        ..Default::default()
+    })
+}
+
+/// Remove images, first removing the stopped containers that would make
+/// `docker rmi` fail. Images with a running user are still attempted and
+/// reported as errors. Every step re-reads Docker's state, so running this
+/// again after a partial failure picks up where it stopped.
+pub async fn remove_images_with_deps(ids: Vec<String>, force: bool) -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
+    if !is_docker_running().await {
+        return remove_images(ids, force).await;
+    }
+
+    let containers = scan_containers().await.unwrap_or_default();
+    let images = scan_images().await.unwrap_or_default();
+    let stopped: HashSet<String> = containers
+        .iter()
+        .filter(|c| c.state != ContainerState::Running)
+        .map(|c| c.id.clone())
+        .collect();
+    let plan = plan_image_removal(&ids, &images, &containers, &stopped);
+
+    let mut containers_removed = 0;
+    let mut freed_bytes = 0u64;
+    let mut errors = Vec::new();
+    if !plan.containers.is_empty() {
+        let result = remove_containers(plan.containers, false).await?;
+        containers_removed = result.containers_removed;
+        freed_bytes += result.freed_bytes;
+        if !result.success {
+            errors.push(result.message);
+        }
+    }
+
+    let result = remove_images(ids, force).await?;
+    freed_bytes += result.freed_bytes;
+    if !result.success {
+        errors.push(result.message);
+    }
+
+    let success = errors.is_empty();
+    let message = if success {
+        format!(
+            "Removed {} image(s), {} after removing {} stopped container(s)",
+            result.images_removed,
+            plan.with_dependents.len(),
+            containers_removed
+        )
+    } else {
+        format!(
+            "Removed {} image(s) and {} container(s). Errors: {}",
+            result.images_removed,
+            containers_removed,
+            errors.join("; ")
+        )
+    };
+
+    Ok(DockerCleanResult {
+        freed_bytes,
+        containers_removed,
+        images_removed: result.images_removed,
+        volumes_removed: 0,
+        networks_removed: 0,
+        images_with_dependents: plan.with_dependents,
+        success,
+        message,
     })
 }
 
 /// Remove specific volumes
 pub async fn remove_volumes(names: Vec<String>) -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
     if !is_docker_running().await {
         return Ok(DockerCleanResult {
             freed_bytes: 0,
@@ -890,6 +1121,7 @@ pub async fn remove_volumes(names: Vec<String>) -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: "Docker daemon is not running".to_string(),
+            ..Default::default()
         });
     }
 
@@ -902,27 +1134,54 @@ pub async fn remove_volumes(names: Vec<String>) -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: true,
             message: "No volumes to remove".to_string(),
+            ..Default::default()
         });
     }
 
-    let mut removed = 0;
-    let mut errors = Vec::new();
+    // Get sizes before removal
+    let volumes = scan_volumes().await.unwrap_or_default();
+    let size_map: std::collections::HashMap<String, u64> = volumes
+        .into_iter()
+        .filter_map(|v| v.size.map(|size| (v.name, size)))
+        .collect();
 
-    for name in &names {
+    let mut outcomes = Vec::new();
+    for name in names {
         let name_clone = name.clone();
-        
         let output = tokio::task::spawn_blocking(move || {
-            Command::new("docker")
+            docker_command()
                 .args(["volume", "rm", &name_clone])
                 .output()
         })
         .await??;
 
-        if output.status.success() {
-            removed += 1;
+        let outcome = if output.status.success() {
+            Ok(())
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            errors.push(format!("{}: {}", name, stderr.trim()));
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        };
+        outcomes.push((name, outcome));
+    }
+
+    Ok(volume_removal_result(&outcomes, &size_map))
+}
+
+/// Counts and freed bytes for `docker volume rm` outcomes; only volumes that
+/// were actually removed count toward the freed total
+fn volume_removal_result(
+    outcomes: &[(String, std::result::Result<(), String>)],
+    size_map: &std::collections::HashMap<String, u64>,
+) -> DockerCleanResult {
+    let mut removed = 0;
+    let mut freed_bytes = 0u64;
+    let mut errors = Vec::new();
+    for (name, outcome) in outcomes {
+        match outcome {
+            Ok(()) => {
+                removed += 1;
+                freed_bytes += size_map.get(name).copied().unwrap_or(0);
+            }
+            Err(stderr) => errors.push(format!("{}: {}", name, stderr)),
         }
     }
 
@@ -938,19 +1197,21 @@ pub async fn remove_volumes(names: Vec<String>) -> Result<DockerCleanResult> {
         )
     };
 
-    Ok(DockerCleanResult {
-        freed_bytes: 0, // Volume sizes are hard to determine
+    DockerCleanResult {
+        freed_bytes,
         containers_removed: 0,
         images_removed: 0,
         volumes_removed: removed,
         networks_removed: 0,
         success,
         message,
-    })
+        ..Default::default()
+    }
 }
 
 /// Remove specific networks
 pub async fn remove_networks(ids: Vec<String>) -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
     if !is_docker_running().await {
         return Ok(DockerCleanResult {
             freed_bytes: 0,
@@ -960,6 +1221,7 @@ pub async fn remove_networks(ids: Vec<String>) -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: "Docker daemon is not running".to_string(),
+            ..Default::default()
         });
     }
 
@@ -978,6 +1240,7 @@ pub async fn remove_networks(ids: Vec<String>) -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: true,
             message: "No networks to remove".to_string(),
+            ..Default::default()
         });
     }
 
@@ -988,7 +1251,7 @@ pub async fn remove_networks(ids: Vec<String>) -> Result<DockerCleanResult> {
         let id_clone = id.clone();
         
         let output = tokio::task::spawn_blocking(move || {
-            Command::new("docker")
+            docker_command()
                 .args(["network", "rm", &id_clone])
                 .output()
         })
@@ -1022,11 +1285,13 @@ pub async fn remove_networks(ids: Vec<String>) -> Result<DockerCleanResult> {
         networks_removed: removed,
         success,
         message,
+        ..Default::default()
     })
 }
 
 /// Prune all unused Docker resources
 pub async fn docker_system_prune(all: bool, volumes: bool) -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
     if !is_docker_running().await {
         return Ok(DockerCleanResult {
             freed_bytes: 0,
@@ -1036,6 +1301,7 @@ pub async fn docker_system_prune(all: bool, volumes: bool) -> Result<DockerClean
             networks_removed: 0,
             success: false,
             message: "Docker daemon is not running".to_string(),
+            ..Default::default()
         });
     }
 
@@ -1048,25 +1314,26 @@ pub async fn docker_system_prune(all: bool, volumes: bool) -> Result<DockerClean
     }
 
     let output = tokio::task::spawn_blocking(move || {
-        Command::new("docker").args(&args).output()
+        docker_command().args(&args).output()
     })
     .await??;
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
 
-    // Parse reclaimed space from output
-    let freed_bytes = parse_reclaimed_space(&stdout);
+    // Parse per-type counts and reclaimed space from output
+    let summary = parse_system_prune_output(&stdout);
 
     if output.status.success() {
         Ok(DockerCleanResult {
-            freed_bytes,
-            containers_removed: 0, // Not easily parseable from output
-            images_removed: 0,
-            volumes_removed: 0,
-            networks_removed: 0,
+            freed_bytes: summary.reclaimed_bytes,
+            containers_removed: summary.containers,
+            images_removed: summary.images,
+            volumes_removed: summary.volumes,
+            networks_removed: summary.networks,
             success: true,
-            message: format!("System prune completed. Reclaimed {} bytes", freed_bytes),
+            message: format!("System prune completed. Reclaimed {} bytes", summary.reclaimed_bytes),
+            ..Default::default()
         })
     } else {
         Ok(DockerCleanResult {
@@ -1077,12 +1344,14 @@ pub async fn docker_system_prune(all: bool, volumes: bool) -> Result<DockerClean
             networks_removed: 0,
             success: false,
             message: format!("System prune failed: {}", stderr.trim()),
+            ..Default::default()
         })
     }
 }
 
 /// Prune Docker builder cache
 pub async fn docker_builder_prune() -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
     if !is_docker_running().await {
         return Ok(DockerCleanResult {
             freed_bytes: 0,
@@ -1092,11 +1361,12 @@ pub async fn docker_builder_prune() -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: "Docker daemon is not running".to_string(),
+            ..Default::default()
         });
     }
 
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["builder", "prune", "-af"])
             .output()
     })
@@ -1116,6 +1386,7 @@ pub async fn docker_builder_prune() -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: true,
             message: format!("Builder cache pruned. Reclaimed {} bytes", freed_bytes),
+            ..Default::default()
         })
     } else {
         Ok(DockerCleanResult {
@@ -1126,12 +1397,14 @@ pub async fn docker_builder_prune() -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: format!("Builder prune failed: {}", stderr.trim()),
+            ..Default::default()
         })
     }
 }
 
 /// Prune stopped containers
 pub async fn prune_containers() -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
     if !is_docker_running().await {
         return Ok(DockerCleanResult {
             freed_bytes: 0,
@@ -1141,11 +1414,12 @@ pub async fn prune_containers() -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: "Docker daemon is not running".to_string(),
+            ..Default::default()
         });
     }
 
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["container", "prune", "-f"])
             .output()
     })
@@ -1165,6 +1439,7 @@ pub async fn prune_containers() -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: true,
             message: "Containers pruned successfully".to_string(),
+            ..Default::default()
         })
     } else {
         Ok(DockerCleanResult {
@@ -1175,12 +1450,14 @@ pub async fn prune_containers() -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: format!("Container prune failed: {}", stderr.trim()),
+            ..Default::default()
         })
     }
 }
 
 /// Prune dangling images
 pub async fn prune_images(all: bool) -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
     if !is_docker_running().await {
         return Ok(DockerCleanResult {
             freed_bytes: 0,
@@ -1190,6 +1467,7 @@ pub async fn prune_images(all: bool) -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: "Docker daemon is not running".to_string(),
+            ..Default::default()
         });
     }
 
@@ -1199,7 +1477,7 @@ pub async fn prune_images(all: bool) -> Result<DockerCleanResult> {
     }
 
     let output = tokio::task::spawn_blocking(move || {
-        Command::new("docker").args(&args).output()
+        docker_command().args(&args).output()
     })
     .await??;
 
@@ -1217,6 +1495,7 @@ pub async fn prune_images(all: bool) -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: true,
             message: "Images pruned successfully".to_string(),
+            ..Default::default()
         })
     } else {
         Ok(DockerCleanResult {
@@ -1227,12 +1506,14 @@ pub async fn prune_images(all: bool) -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: format!("Image prune failed: {}", stderr.trim()),
+            ..Default::default()
         })
     }
 }
 
 /// Prune unused volumes
 pub async fn prune_volumes() -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
     if !is_docker_running().await {
         return Ok(DockerCleanResult {
             freed_bytes: 0,
@@ -1242,11 +1523,12 @@ pub async fn prune_volumes() -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: "Docker daemon is not running".to_string(),
+            ..Default::default()
         });
     }
 
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["volume", "prune", "-f"])
             .output()
     })
@@ -1266,6 +1548,7 @@ pub async fn prune_volumes() -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: true,
             message: "Volumes pruned successfully".to_string(),
+            ..Default::default()
         })
     } else {
         Ok(DockerCleanResult {
@@ -1276,12 +1559,14 @@ pub async fn prune_volumes() -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: format!("Volume prune failed: {}", stderr.trim()),
+            ..Default::default()
         })
     }
 }
 
 /// Prune unused networks
 pub async fn prune_networks() -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
     if !is_docker_running().await {
         return Ok(DockerCleanResult {
             freed_bytes: 0,
@@ -1291,11 +1576,12 @@ pub async fn prune_networks() -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: "Docker daemon is not running".to_string(),
+            ..Default::default()
         });
     }
 
     let output = tokio::task::spawn_blocking(|| {
-        Command::new("docker")
+        docker_command()
             .args(["network", "prune", "-f"])
             .output()
     })
@@ -1313,6 +1599,7 @@ pub async fn prune_networks() -> Result<DockerCleanResult> {
             networks_removed: count_deleted_items(&stdout),
             success: true,
             message: "Networks pruned successfully".to_string(),
+            ..Default::default()
         })
     } else {
         Ok(DockerCleanResult {
@@ -1323,6 +1610,7 @@ pub async fn prune_networks() -> Result<DockerCleanResult> {
             networks_removed: 0,
             success: false,
             message: format!("Network prune failed: {}", stderr.trim()),
+            ..Default::default()
         })
     }
 }
@@ -1334,9 +1622,13 @@ pub async fn prune_networks() -> Result<DockerCleanResult> {
 /// Generate smart suggestions for Docker cleanup
 pub async fn get_docker_suggestions() -> Result<Vec<DockerSuggestion>> {
     let scan_result = scan_docker_resources().await?;
-    
+    Ok(suggestions_from_scan(&scan_result))
+}
+
+/// Suggestions for the reclaimable resources in a scan, highest score first
+fn suggestions_from_scan(scan_result: &DockerScanResult) -> Vec<DockerSuggestion> {
     if !scan_result.daemon_running {
-        return Ok(vec![]);
+        return vec![];
     }
 
     let mut suggestions = Vec::new();
@@ -1344,14 +1636,15 @@ pub async fn get_docker_suggestions() -> Result<Vec<DockerSuggestion>> {
     // Suggest stopped containers
     for container in &scan_result.containers {
         if container.state != ContainerState::Running {
-            let (score, reasons, auto_select) = score_container(container);
+            let (score, reason_codes, auto_select) = score_container(container);
             suggestions.push(DockerSuggestion {
                 resource_type: DockerResourceType::Container,
                 id: container.id.clone(),
                 name: container.name.clone(),
                 size: container.size,
                 score,
-                reasons,
+                reasons: reason_messages(&reason_codes),
+                reason_codes,
                 auto_select,
             });
         }
@@ -1360,7 +1653,7 @@ pub async fn get_docker_suggestions() -> Result<Vec<DockerSuggestion>> {
     // Suggest dangling and unused images
     for image in &scan_result.images {
         if image.is_dangling || image.used_by_containers.is_empty() {
-            let (score, reasons, auto_select) = score_image(image);
+            let (score, reason_codes, auto_select) = score_image(image);
             let name = if image.repository == "<none>" {
                 format!("{}...", &image.id[..12.min(image.id.len())])
             } else {
@@ -1373,7 +1666,8 @@ pub async fn get_docker_suggestions() -> Result<Vec<DockerSuggestion>> {
                 name,
                 size: image.size,
                 score,
-                reasons,
+                reasons: reason_messages(&reason_codes),
+                reason_codes,
                 auto_select,
             });
         }
@@ -1382,14 +1676,15 @@ pub async fn get_docker_suggestions() -> Result<Vec<DockerSuggestion>> {
     // Suggest orphan volumes
     for volume in &scan_result.volumes {
         if volume.used_by_containers.is_empty() {
-            let (score, reasons, auto_select) = score_volume(volume);
+            let (score, reason_codes, auto_select) = score_volume(volume);
             suggestions.push(DockerSuggestion {
                 resource_type: DockerResourceType::Volume,
                 id: volume.name.clone(),
                 name: volume.name.clone(),
                 size: volume.size.unwrap_or(0),
                 score,
-                reasons,
+                reasons: reason_messages(&reason_codes),
+                reason_codes,
                 auto_select,
             });
         }
@@ -1400,14 +1695,15 @@ pub async fn get_docker_suggestions() -> Result<Vec<DockerSuggestion>> {
         if network.used_by_containers.is_empty() 
             && !DEFAULT_NETWORKS.contains(&network.name.as_str()) 
         {
-            let (score, reasons, auto_select) = score_network(network);
+            let (score, reason_codes, auto_select) = score_network(network);
             suggestions.push(DockerSuggestion {
                 resource_type: DockerResourceType::Network,
                 id: network.id.clone(),
                 name: network.name.clone(),
                 size: 0,
                 score,
-                reasons,
+                reasons: reason_messages(&reason_codes),
+                reason_codes,
                 auto_select,
             });
         }
@@ -1415,16 +1711,18 @@ pub async fn get_docker_suggestions() -> Result<Vec<DockerSuggestion>> {
 
     // Add build cache suggestion if there's any
     if scan_result.build_cache_size > 0 {
+        let build_cache_codes = vec![
+            ReasonCode::BuildCache { bytes: scan_result.build_cache_size },
+            ReasonCode::SafeToRemove,
+        ];
         suggestions.push(DockerSuggestion {
             resource_type: DockerResourceType::BuildCache,
             id: "build_cache".to_string(),
             name: "Docker Build Cache".to_string(),
             size: scan_result.build_cache_size,
             score: 0.8,
-            reasons: vec![
-                format!("Build cache: {}", format_size(scan_result.build_cache_size)),
-                "Can be safely removed".to_string(),
-            ],
+            reasons: reason_messages(&build_cache_codes),
+            reason_codes: build_cache_codes,
             auto_select: false,
         });
     }
@@ -1432,11 +1730,11 @@ pub async fn get_docker_suggestions() -> Result<Vec<DockerSuggestion>> {
     // Sort by score descending
     suggestions.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
 
-    Ok(suggestions)
+    suggestions
 }
 
 /// Score a container for cleanup suggestion
-fn score_container(container: &DockerContainer) -> (f64, Vec<String>, bool) {
+fn score_container(container: &DockerContainer) -> (f64, Vec<ReasonCode>, bool) {
     let mut reasons = Vec::new();
     let mut score: f64 = 0.0;
     
@@ -1445,24 +1743,24 @@ fn score_container(container: &DockerContainer) -> (f64, Vec<String>, bool) {
     score += size_score * SIZE_WEIGHT;
     
     if container.size >= SIZE_LARGE {
-        reasons.push(format!("Large size: {}", format_size(container.size)));
+        reasons.push(ReasonCode::LargeSize { bytes: container.size });
     } else if container.size >= SIZE_MEDIUM {
-        reasons.push(format!("Size: {}", format_size(container.size)));
+        reasons.push(ReasonCode::Size { bytes: container.size });
     }
 
     // State score - stopped containers get higher scores
     match container.state {
         ContainerState::Exited => {
             score += USAGE_WEIGHT; // Full usage weight since it's not running
-            reasons.push("Container has exited".to_string());
+            reasons.push(ReasonCode::ContainerExited);
         }
         ContainerState::Dead => {
             score += USAGE_WEIGHT;
-            reasons.push("Container is dead".to_string());
+            reasons.push(ReasonCode::ContainerDead);
         }
         ContainerState::Created => {
             score += USAGE_WEIGHT * 0.5;
-            reasons.push("Container was created but never started".to_string());
+            reasons.push(ReasonCode::ContainerNeverStarted);
         }
         _ => {}
     }
@@ -1472,17 +1770,17 @@ fn score_container(container: &DockerContainer) -> (f64, Vec<String>, bool) {
     score += age_score * AGE_WEIGHT;
     
     if age_score >= 0.8 {
-        reasons.push("Not used for a long time".to_string());
+        reasons.push(ReasonCode::NotUsedLong);
     }
 
-    // Auto-select dead containers
-    let auto_select = container.state == ContainerState::Dead;
+    // Auto-select dead containers and ones that never ran, which hold no state
+    let auto_select = matches!(container.state, ContainerState::Dead | ContainerState::Created);
 
     (score.min(1.0), reasons, auto_select)
 }
 
 /// Score an image for cleanup suggestion
-fn score_image(image: &DockerImage) -> (f64, Vec<String>, bool) {
+fn score_image(image: &DockerImage) -> (f64, Vec<ReasonCode>, bool) {
     let mut reasons = Vec::new();
     let mut score: f64 = 0.0;
     let mut auto_select = false;
@@ -1490,13 +1788,13 @@ fn score_image(image: &DockerImage) -> (f64, Vec<String>, bool) {
     // Dangling images always get high score and auto-select
     if image.is_dangling {
         score = 1.0;
-        reasons.push("Dangling image (untagged)".to_string());
+        reasons.push(ReasonCode::Dangling);
         auto_select = true;
     } else {
         // Unused images
         if image.used_by_containers.is_empty() {
             score += USAGE_WEIGHT;
-            reasons.push("Not used by any container".to_string());
+            reasons.push(ReasonCode::Unused);
         }
 
         // Size score
@@ -1505,31 +1803,31 @@ fn score_image(image: &DockerImage) -> (f64, Vec<String>, bool) {
     }
 
     if image.size >= SIZE_LARGE {
-        reasons.push(format!("Large size: {}", format_size(image.size)));
+        reasons.push(ReasonCode::LargeSize { bytes: image.size });
     } else if image.size >= SIZE_MEDIUM {
-        reasons.push(format!("Size: {}", format_size(image.size)));
+        reasons.push(ReasonCode::Size { bytes: image.size });
     }
 
     (score.min(1.0), reasons, auto_select)
 }
 
 /// Score a volume for cleanup suggestion
-fn score_volume(volume: &DockerVolume) -> (f64, Vec<String>, bool) {
+fn score_volume(volume: &DockerVolume) -> (f64, Vec<ReasonCode>, bool) {
     let mut reasons = Vec::new();
     let mut score: f64 = 0.0;
 
     // Orphan volumes (not used by any container) get high score
     if volume.used_by_containers.is_empty() {
         score = 1.0;
-        reasons.push("Orphan volume (not used by any container)".to_string());
+        reasons.push(ReasonCode::Orphan);
     }
 
     // Size score if available
     if let Some(size) = volume.size {
         if size >= SIZE_LARGE {
-            reasons.push(format!("Large size: {}", format_size(size)));
+            reasons.push(ReasonCode::LargeSize { bytes: size });
         } else if size >= SIZE_MEDIUM {
-            reasons.push(format!("Size: {}", format_size(size)));
+            reasons.push(ReasonCode::Size { bytes: size });
         }
     }
 
@@ -1540,12 +1838,12 @@ fn score_volume(volume: &DockerVolume) -> (f64, Vec<String>, bool) {
 }
 
 /// Score a network for cleanup suggestion
-fn score_network(network: &DockerNetwork) -> (f64, Vec<String>, bool) {
+fn score_network(network: &DockerNetwork) -> (f64, Vec<ReasonCode>, bool) {
     let mut reasons = Vec::new();
     
     // Unused networks get high score
     let score = if network.used_by_containers.is_empty() {
-        reasons.push("Not used by any container".to_string());
+        reasons.push(ReasonCode::Unused);
         0.9
     } else {
         0.3
@@ -1612,28 +1910,9 @@ fn extract_number(s: &str, unit: &str) -> Option<u64> {
     None
 }
 
-/// Format size in human-readable form
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-    const TB: u64 = GB * 1024;
-
-    if bytes >= TB {
-        format!("{:.1} TB", bytes as f64 / TB as f64)
-    } else if bytes >= GB {
-        format!("{:.1} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
-    }
-}
-
 /// Clean Docker resources based on suggestions
 pub async fn clean_docker_suggestions(suggestions: Vec<DockerSuggestion>) -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
     let mut total_freed = 0u64;
     let mut containers_removed = 0;
     let mut images_removed = 0;
@@ -1647,6 +1926,7 @@ pub async fn clean_docker_suggestions(suggestions: Vec<DockerSuggestion>) -> Res
     let mut volume_names = Vec::new();
     let mut network_ids = Vec::new();
     let mut has_build_cache = false;
+    let mut images_with_dependents = Vec::new();
 
     for suggestion in &suggestions {
         match suggestion.resource_type {
@@ -1658,7 +1938,14 @@ pub async fn clean_docker_suggestions(suggestions: Vec<DockerSuggestion>) -> Res
         }
     }
 
-    // Remove containers first (images might depend on them)
+    // Containers go first, so images whose only users are among them can follow
+    if !image_ids.is_empty() && !container_ids.is_empty() {
+        let containers = scan_containers().await.unwrap_or_default();
+        let images = scan_images().await.unwrap_or_default();
+        let slated: HashSet<String> = container_ids.iter().cloned().collect();
+        images_with_dependents = plan_image_removal(&image_ids, &images, &containers, &slated).with_dependents;
+    }
+
     if !container_ids.is_empty() {
         match remove_containers(container_ids, true).await {
             Ok(result) => {
@@ -1747,80 +2034,163 @@ pub async fn clean_docker_suggestions(suggestions: Vec<DockerSuggestion>) -> Res
         images_removed,
         volumes_removed,
         networks_removed,
+        images_with_dependents,
         success,
         message,
     })
 }
 
-// ============================================================================
-// Helper Functions
-// ============================================================================
-
-/// Parse Docker size string (e.g., "1.5GB", "500MB", "100kB") to bytes
-fn parse_docker_size(size_str: &str) -> u64 {
-    let size_str = size_str.trim();
-    
-    // Handle "0B" or empty
-    if size_str.is_empty() || size_str == "0B" || size_str == "0" {
-        return 0;
+/// Conservative one-click cleanup: removes only what the suggestions
+/// auto-select (dead or never-started containers, dangling images, orphan
+/// volumes). Unlike `docker_system_prune`, tagged images, networks and the
+/// build cache are never touched.
+pub async fn docker_safe_cleanup() -> Result<DockerCleanResult> {
+    guard::ensure_commands_enabled()?;
+    let scan_result = scan_docker_resources().await?;
+    let targets = safe_cleanup_targets(&scan_result);
+    if targets.is_empty() {
+        return Ok(DockerCleanResult {
+            freed_bytes: 0,
+            containers_removed: 0,
+            images_removed: 0,
+            volumes_removed: 0,
+            networks_removed: 0,
+            success: true,
+            message: "Nothing safe to clean".to_string(),
+            ..Default::default()
+        });
     }
+    clean_docker_suggestions(targets).await
+}
 
-    // Extract number and unit
-    let mut num_str = String::new();
-    let mut unit_str = String::new();
-    let mut in_unit = false;
+/// Auto-selected suggestions `docker_safe_cleanup` may remove
+fn safe_cleanup_targets(scan_result: &DockerScanResult) -> Vec<DockerSuggestion> {
+    // A dangling image can still back a container; leave it for `docker rmi`
+    let images_in_use: HashSet<&str> = scan_result
+        .images
+        .iter()
+        .filter(|i| !i.used_by_containers.is_empty())
+        .map(|i| i.id.as_str())
+        .collect();
 
-    for c in size_str.chars() {
-        if c.is_ascii_digit() || c == '.' {
-            if !in_unit {
-                num_str.push(c);
-            }
-        } else if c.is_alphabetic() {
-            in_unit = true;
-            unit_str.push(c);
+    suggestions_from_scan(scan_result)
+        .into_iter()
+        .filter(|s| s.auto_select)
+        .filter(|s| match s.resource_type {
+            DockerResourceType::Container | DockerResourceType::Volume => true,
+            DockerResourceType::Image => !images_in_use.contains(s.id.as_str()),
+            DockerResourceType::Network | DockerResourceType::BuildCache => false,
+        })
+        .collect()
+}
+
+/// The current suggestions as a shell script to review or run by hand
+pub async fn export_docker_plan() -> Result<String> {
+    let suggestions = get_docker_suggestions().await?;
+    Ok(render_docker_plan(&suggestions))
+}
+
+/// Shell script running the same commands as `clean_docker_suggestions`, in
+/// the same order (containers before the images they use), each preceded by
+/// a comment with its reclaimable size and reasons
+pub fn render_docker_plan(suggestions: &[DockerSuggestion]) -> String {
+    let mut script = String::from("#!/bin/sh\n# Docker cleanup plan\n");
+    let total: u64 = suggestions.iter().map(|s| s.size).sum();
+    script.push_str(&format!("# {} item(s), about {} reclaimable\n", suggestions.len(), format_size(total)));
+    if suggestions.is_empty() {
+        script.push_str("# Nothing to clean\n");
+        return script;
+    }
+    script.push_str("set -e\n");
+
+    let order = [
+        DockerResourceType::Container,
+        DockerResourceType::Image,
+        DockerResourceType::Volume,
+        DockerResourceType::Network,
+        DockerResourceType::BuildCache,
+    ];
+    for resource_type in &order {
+        for suggestion in suggestions.iter().filter(|s| &s.resource_type == resource_type) {
+            let command = match resource_type {
+                DockerResourceType::Container => format!("docker rm -f {}", shell_quote(&suggestion.id)),
+                DockerResourceType::Image => format!("docker rmi -f {}", shell_quote(&suggestion.id)),
+                DockerResourceType::Volume => format!("docker volume rm {}", shell_quote(&suggestion.id)),
+                DockerResourceType::Network => format!("docker network rm {}", shell_quote(&suggestion.id)),
+                DockerResourceType::BuildCache => "docker builder prune -af".to_string(),
+            };
+            script.push_str(&format!(
+                "\n# {} ({}): {}\n",
+                comment_line(&suggestion.name),
+                format_size(suggestion.size),
+                comment_line(&suggestion.reasons.join("; "))
+            ));
+            script.push_str(&command);
+            script.push('\n');
         }
     }
+    script
+}
 
-    let num: f64 = num_str.parse().unwrap_or(0.0);
-    let unit = unit_str.to_uppercase();
+/// Single-quote `value` for sh
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
 
-    let multiplier: u64 = match unit.as_str() {
-        "B" => 1,
-        "KB" | "K" => 1024,
-        "MB" | "M" => 1024 * 1024,
-        "GB" | "G" => 1024 * 1024 * 1024,
-        "TB" | "T" => 1024 * 1024 * 1024 * 1024,
-        _ => 1,
-    };
+/// `text` with line breaks flattened so it stays inside one comment
+fn comment_line(text: &str) -> String {
+    text.replace(['\n', '\r'], " ")
+}
 
-    (num * multiplier as f64) as u64
+/// Stopped containers to remove before a set of images
+#[derive(Debug, Default, PartialEq)]
+struct ImageRemovalPlan {
+    containers: Vec<String>,
+    /// Images that can only be removed once `containers` are gone
+    with_dependents: Vec<String>,
 }
 
-/// Parse reclaimed space from Docker prune output
-fn parse_reclaimed_space(output: &str) -> u64 {
-    // Look for patterns like "Total reclaimed space: 1.5GB"
-    for line in output.lines() {
-        let lower = line.to_lowercase();
-        if lower.contains("reclaimed") || lower.contains("freed") {
-            // Find the size part
-            let parts: Vec<&str> = line.split(':').collect();
-            if parts.len() >= 2 {
-                return parse_docker_size(parts[1].trim());
-            }
-            // Try to find size pattern in the line
-            for word in line.split_whitespace() {
-                if word.chars().any(|c| c.is_ascii_digit()) 
-                    && word.chars().any(|c| c.is_alphabetic()) 
-                {
-                    let size = parse_docker_size(word);
-                    if size > 0 {
-                        return size;
-                    }
-                }
+/// Plan removing `image_ids` when the containers in `removable` may go too.
+/// An image whose users are all stopped and removable gets them removed
+/// first; one with any other user is left to fail in `docker rmi`.
+fn plan_image_removal(
+    image_ids: &[String],
+    images: &[DockerImage],
+    containers: &[DockerContainer],
+    removable: &HashSet<String>,
+) -> ImageRemovalPlan {
+    let running: HashSet<&str> = containers
+        .iter()
+        .filter(|c| c.state == ContainerState::Running)
+        .map(|c| c.id.as_str())
+        .collect();
+
+    let mut plan = ImageRemovalPlan::default();
+    for image in images.iter().filter(|i| image_ids.contains(&i.id)) {
+        let users = &image.used_by_containers;
+        let freeable = !users.is_empty()
+            && users.iter().all(|u| removable.contains(u) && !running.contains(u.as_str()));
+        if !freeable {
+            continue;
+        }
+        plan.with_dependents.push(image.id.clone());
+        for user in users {
+            if !plan.containers.contains(user) {
+                plan.containers.push(user.clone());
             }
         }
     }
-    0
+    plan
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// A `docker` invocation, counted in the metrics
+fn docker_command() -> Command {
+    metrics::DOCKER_COMMANDS_RUN.incr();
+    Command::new("docker")
 }
 
 /// Count deleted items from prune output
@@ -1836,6 +2206,50 @@ fn count_deleted_items(output: &str) -> usize {
     count
 }
 
+/// Counts parsed from `docker system prune` output
+#[derive(Debug, Default, PartialEq)]
+struct SystemPruneSummary {
+    containers: usize,
+    images: usize,
+    volumes: usize,
+    networks: usize,
+    reclaimed_bytes: u64,
+}
+
+/// Parse the "Deleted Containers:", "Deleted Images:", ... sections of
+/// `docker system prune` output
+fn parse_system_prune_output(output: &str) -> SystemPruneSummary {
+    let mut summary = SystemPruneSummary::default();
+    let mut section = String::new();
+
+    for line in output.lines() {
+        let line = line.trim();
+        let lower = line.to_lowercase();
+        if line.is_empty() {
+            continue;
+        }
+        if lower.contains("reclaimed space") {
+            summary.reclaimed_bytes = parse_reclaimed_space(line);
+            section.clear();
+            continue;
+        }
+        if lower.starts_with("deleted ") && lower.ends_with(':') {
+            section = lower;
+            continue;
+        }
+
+        match section.as_str() {
+            "deleted containers:" => summary.containers += 1,
+            "deleted networks:" => summary.networks += 1,
+            "deleted volumes:" => summary.volumes += 1,
+            // Each image lists "untagged:" references before its "deleted:" layers
+            "deleted images:" if lower.starts_with("deleted:") => summary.images += 1,
+            _ => {}
+        }
+    }
+    summary
+}
+
 // ============================================================================
 // Tests
 // ============================================================================
@@ -1843,17 +2257,35 @@ fn count_deleted_items(output: &str) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cache::CacheError;
 
     #[test]
-    fn test_parse_docker_size() {
-        assert_eq!(parse_docker_size("0B"), 0);
-        assert_eq!(parse_docker_size("100B"), 100);
-        assert_eq!(parse_docker_size("1KB"), 1024);
-        assert_eq!(parse_docker_size("1.5KB"), 1536);
-        assert_eq!(parse_docker_size("1MB"), 1024 * 1024);
-        assert_eq!(parse_docker_size("1.5MB"), (1.5 * 1024.0 * 1024.0) as u64);
-        assert_eq!(parse_docker_size("1GB"), 1024 * 1024 * 1024);
-        assert_eq!(parse_docker_size("2.5GB"), (2.5 * 1024.0 * 1024.0 * 1024.0) as u64);
+    fn test_parse_volume_sizes() {
+        let json = r#"{"Images":[],"Containers":[],"Volumes":[{"Driver":"local","Links":"1","Name":"pgdata","Size":"1.5GB"},{"Driver":"local","Links":"0","Name":"cache","Size":"0B"}],"BuildCache":[]}"#;
+        let sizes = parse_volume_sizes(json);
+        assert_eq!(sizes.get("pgdata"), Some(&(1536 * 1024 * 1024)));
+        assert_eq!(sizes.get("cache"), Some(&0));
+        assert!(parse_volume_sizes("not json").is_empty());
+    }
+
+    #[test]
+    fn test_volume_freed_bytes_skip_failed_removals() {
+        let sizes: std::collections::HashMap<String, u64> =
+            [("pgdata".to_string(), 500), ("redis".to_string(), 300), ("busy".to_string(), 200)]
+                .into_iter()
+                .collect();
+        let outcomes = vec![
+            ("pgdata".to_string(), Ok(())),
+            ("busy".to_string(), Err("volume is in use".to_string())),
+            ("redis".to_string(), Ok(())),
+            ("unsized".to_string(), Ok(())),
+        ];
+
+        let result = volume_removal_result(&outcomes, &sizes);
+        assert_eq!(result.freed_bytes, 800);
+        assert_eq!(result.volumes_removed, 3);
+        assert!(!result.success);
+        assert!(result.message.contains("busy: volume is in use"));
     }
 
     #[test]
@@ -1866,25 +2298,51 @@ mod tests {
         assert_eq!(ContainerState::from("unknown_state"), ContainerState::Unknown);
     }
 
-    #[test]
-    fn test_parse_reclaimed_space() {
-        assert_eq!(
-            parse_reclaimed_space("Total reclaimed space: 1.5GB"),
-            (1.5 * 1024.0 * 1024.0 * 1024.0) as u64
-        );
-        assert_eq!(
-            parse_reclaimed_space("Reclaimed space: 500MB"),
-            500 * 1024 * 1024
-        );
-        assert_eq!(parse_reclaimed_space("No space reclaimed"), 0);
-    }
-
     #[test]
     fn test_count_deleted_items() {
         let output = "Deleted: abc123\nDeleted: def456\nsha256:789xyz\nTotal: 3";
         assert_eq!(count_deleted_items(output), 3);
     }
 
+    #[test]
+    fn test_parse_system_prune_output() {
+        let output = "Deleted Containers:
+4a7f7eebae0f63178aff7eb0aa39cd3f0627a203ab2df258c1a00b456cf20063
+f98f9c2aa1eaf727e4ec9c0283bc7d4aa4762fbdba7f26191f26c97f64090360
+
+Deleted Networks:
+my_app_default
+
+Deleted Volumes:
+db_data
+cache_data
+logs
+
+Deleted Images:
+untagged: nginx:1.19
+untagged: nginx@sha256:df13abe416e37eb3db4722840dd479b00ba193ac6606e7902331dcea50f4f1f2
+deleted: sha256:f6d0b4767a6c466c178bf718f99bea0d3742b26679081e52dbf8e0c7c4c42d74
+deleted: sha256:4dd8cd6bf4a1c1a0e2f1b6e0ecad3e69e2d5c51c8b6c3d4b5b0b7a9d4a7b2c1e
+
+Deleted build cache objects:
+x0v6bxvbhnhd81ne1bbvbbp8q
+
+Total reclaimed space: 1.5GB
+";
+        let summary = parse_system_prune_output(output);
+        assert_eq!(summary.containers, 2);
+        assert_eq!(summary.networks, 1);
+        assert_eq!(summary.volumes, 3);
+        assert_eq!(summary.images, 2);
+        assert_eq!(summary.reclaimed_bytes, (1.5 * 1024.0 * 1024.0 * 1024.0) as u64);
+    }
+
+    #[test]
+    fn test_parse_system_prune_output_nothing_removed() {
+        let summary = parse_system_prune_output("Total reclaimed space: 0B\n");
+        assert_eq!(summary, SystemPruneSummary::default());
+    }
+
     #[test]
     fn test_default_networks_protection() {
         assert!(DEFAULT_NETWORKS.contains(&"bridge"));
@@ -1965,6 +2423,7 @@ mod tests {
             networks_removed: 1,
             success: true,
             message: "Cleanup successful".to_string(),
+            ..Default::default()
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -1982,6 +2441,7 @@ mod tests {
             size: 500 * 1024 * 1024,
             score: 0.95,
             reasons: vec!["Dangling image".to_string(), "Large size".to_string()],
+            reason_codes: vec![ReasonCode::Dangling],
             auto_select: true,
         };
 
@@ -1991,6 +2451,25 @@ mod tests {
         assert!(deserialized.auto_select);
     }
 
+    #[test]
+    fn test_suggestion_without_reason_codes() {
+        let mut json = serde_json::to_value(DockerSuggestion {
+            resource_type: DockerResourceType::Volume,
+            id: "orphan".to_string(),
+            name: "orphan".to_string(),
+            size: 0,
+            score: 0.5,
+            reasons: vec!["Not used by any container".to_string()],
+            reason_codes: vec![ReasonCode::Dangling],
+            auto_select: false,
+        })
+        .unwrap();
+        json.as_object_mut().unwrap().remove("reason_codes");
+
+        let deserialized: DockerSuggestion = serde_json::from_value(json).unwrap();
+        assert!(deserialized.reason_codes.is_empty());
+    }
+
     #[test]
     fn test_calculate_size_score() {
         assert_eq!(calculate_size_score(SIZE_LARGE), 1.0);
@@ -2020,16 +2499,6 @@ mod tests {
         assert_eq!(extract_number("no number here", "weeks"), None);
     }
 
-    #[test]
-    fn test_format_size() {
-        assert_eq!(format_size(500), "500 B");
-        assert_eq!(format_size(1024), "1.0 KB");
-        assert_eq!(format_size(1024 * 1024), "1.0 MB");
-        assert_eq!(format_size(1024 * 1024 * 1024), "1.0 GB");
-        assert_eq!(format_size(1024 * 1024 * 1024 * 1024), "1.0 TB");
-        assert_eq!(format_size((1.5 * 1024.0 * 1024.0 * 1024.0) as u64), "1.5 GB");
-    }
-
     #[test]
     fn test_score_container_exited() {
         let container = DockerContainer {
@@ -2066,6 +2535,144 @@ mod tests {
         assert!(auto_select); // Dead containers should auto-select
     }
 
+    #[test]
+    fn test_safe_cleanup_targets_only_auto_selected() {
+        let container = |id: &str, state: ContainerState| DockerContainer {
+            id: id.to_string(),
+            name: id.to_string(),
+            image: "nginx".to_string(),
+            status: String::new(),
+            state,
+            size: SIZE_SMALL,
+            created: String::new(),
+            ports: String::new(),
+        };
+        let image = |id: &str, tag: &str, used_by: &[&str]| DockerImage {
+            id: id.to_string(),
+            repository: if tag == "<none>" { "<none>" } else { "nginx" }.to_string(),
+            tag: tag.to_string(),
+            size: SIZE_MEDIUM,
+            created: String::new(),
+            is_dangling: tag == "<none>",
+            used_by_containers: used_by.iter().map(|s| s.to_string()).collect(),
+        };
+        let volume = |name: &str, used_by: &[&str]| DockerVolume {
+            name: name.to_string(),
+            driver: "local".to_string(),
+            mountpoint: String::new(),
+            size: Some(SIZE_SMALL),
+            used_by_containers: used_by.iter().map(|s| s.to_string()).collect(),
+        };
+        let scan = DockerScanResult {
+            daemon_running: true,
+            containers: vec![
+                container("running", ContainerState::Running),
+                container("exited", ContainerState::Exited),
+                container("dead", ContainerState::Dead),
+                container("created", ContainerState::Created),
+            ],
+            images: vec![
+                image("sha256:dangling", "<none>", &[]),
+                image("sha256:dangling-used", "<none>", &["exited"]),
+                image("sha256:tagged", "latest", &[]),
+            ],
+            volumes: vec![volume("orphan", &[]), volume("data", &["running"])],
+            networks: vec![DockerNetwork {
+                id: "net1".to_string(),
+                name: "project_default".to_string(),
+                driver: "bridge".to_string(),
+                scope: "local".to_string(),
+                used_by_containers: vec![],
+            }],
+            build_cache_size: SIZE_LARGE,
+            total_reclaimable: 0,
+            stopped_containers_count: 3,
+            dangling_images_count: 2,
+            unused_images_count: 2,
+            orphan_volumes_count: 1,
+            unused_networks_count: 1,
+        };
+
+        let targets = safe_cleanup_targets(&scan);
+        assert!(targets.iter().all(|t| t.auto_select));
+        let mut ids: Vec<&str> = targets.iter().map(|t| t.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["created", "dead", "orphan", "sha256:dangling"]);
+    }
+
+    #[test]
+    fn test_plan_removes_stopped_users_first() {
+        let container = |id: &str, state: ContainerState| DockerContainer {
+            id: id.to_string(),
+            name: id.to_string(),
+            image: "app".to_string(),
+            status: String::new(),
+            state,
+            size: 0,
+            created: String::new(),
+            ports: String::new(),
+        };
+        let image = |id: &str, used_by: &[&str]| DockerImage {
+            id: id.to_string(),
+            repository: "app".to_string(),
+            tag: id.to_string(),
+            size: SIZE_MEDIUM,
+            created: String::new(),
+            is_dangling: false,
+            used_by_containers: used_by.iter().map(|s| s.to_string()).collect(),
+        };
+        let containers = vec![container("stopped", ContainerState::Exited), container("web", ContainerState::Running)];
+        let images = vec![image("old", &["stopped"]), image("live", &["web"]), image("unused", &[])];
+        let ids: Vec<String> = ["old", "live", "unused"].iter().map(|s| s.to_string()).collect();
+
+        let removable: HashSet<String> = ["stopped".to_string()].into();
+        let plan = plan_image_removal(&ids, &images, &containers, &removable);
+        assert_eq!(plan.containers, vec!["stopped"]);
+        assert_eq!(plan.with_dependents, vec!["old"]);
+
+        // The stopped container is not slated, so the image stays blocked
+        assert_eq!(plan_image_removal(&ids, &images, &containers, &HashSet::new()), ImageRemovalPlan::default());
+    }
+
+    #[test]
+    fn test_render_docker_plan() {
+        let suggestion = |resource_type: DockerResourceType, id: &str, size: u64, reason: &str| DockerSuggestion {
+            resource_type,
+            id: id.to_string(),
+            name: id.to_string(),
+            size,
+            score: 0.5,
+            reasons: vec![reason.to_string()],
+            reason_codes: vec![],
+            auto_select: true,
+        };
+        // Deliberately not in execution order
+        let suggestions = vec![
+            suggestion(DockerResourceType::BuildCache, "build_cache", SIZE_LARGE, "Build cache"),
+            suggestion(DockerResourceType::Volume, "orphan", SIZE_SMALL, "Not used by any container"),
+            suggestion(DockerResourceType::Image, "sha256:abc", SIZE_MEDIUM, "Dangling image"),
+            suggestion(DockerResourceType::Container, "web's", SIZE_SMALL, "Stopped\nlong ago"),
+            suggestion(DockerResourceType::Network, "net1", 0, "Unused network"),
+        ];
+
+        let script = render_docker_plan(&suggestions);
+        let commands: Vec<&str> = script.lines().filter(|l| l.starts_with("docker ")).collect();
+        assert_eq!(
+            commands,
+            vec![
+                "docker rm -f 'web'\\''s'",
+                "docker rmi -f 'sha256:abc'",
+                "docker volume rm 'orphan'",
+                "docker network rm 'net1'",
+                "docker builder prune -af",
+            ]
+        );
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains(&format!("# sha256:abc ({}): Dangling image\ndocker rmi", format_size(SIZE_MEDIUM))));
+        assert!(script.contains("Stopped long ago"));
+        assert!(render_docker_plan(&[]).contains("Nothing to clean"));
+    }
+
     #[test]
     fn test_score_image_dangling() {
         let image = DockerImage {
@@ -2081,7 +2688,27 @@ mod tests {
         let (score, reasons, auto_select) = score_image(&image);
         assert_eq!(score, 1.0);
         assert!(auto_select);
-        assert!(reasons.iter().any(|r| r.contains("Dangling")));
+        assert!(reasons.contains(&ReasonCode::Dangling));
+    }
+
+    #[test]
+    fn test_image_reason_codes_carry_size() {
+        let image = DockerImage {
+            id: "sha256:abc".to_string(),
+            repository: "<none>".to_string(),
+            tag: "<none>".to_string(),
+            size: SIZE_LARGE,
+            created: "".to_string(),
+            is_dangling: true,
+            used_by_containers: vec![],
+        };
+
+        let (_, codes, _) = score_image(&image);
+        assert_eq!(codes, vec![ReasonCode::Dangling, ReasonCode::LargeSize { bytes: SIZE_LARGE }]);
+        assert_eq!(
+            reason_messages(&codes),
+            vec!["Dangling image (untagged)", "Large size: 1.0 GiB"]
+        );
     }
 
     #[test]
@@ -2099,7 +2726,7 @@ mod tests {
         let (score, reasons, auto_select) = score_image(&image);
         assert!(score >= 0.5);
         assert!(!auto_select); // Non-dangling unused images shouldn't auto-select
-        assert!(reasons.iter().any(|r| r.contains("Not used")));
+        assert!(reasons.contains(&ReasonCode::Unused));
     }
 
     #[test]
@@ -2115,7 +2742,47 @@ mod tests {
         let (score, reasons, auto_select) = score_volume(&volume);
         assert_eq!(score, 1.0);
         assert!(auto_select);
-        assert!(reasons.iter().any(|r| r.contains("Orphan")));
+        assert!(reasons.contains(&ReasonCode::Orphan));
+    }
+
+    /// `app:1` and `app:2` share a 100 MB base and a 50 MB dependency layer;
+    /// `db` is in use and shares the base
+    fn mocked_inspect() -> Vec<ImageLayers> {
+        let json = r#"[
+            {"Id": "sha256:app1", "Size": 180, "RootFS": {"Type": "layers", "Layers": ["sha256:base", "sha256:deps", "sha256:app1code"]}},
+            {"Id": "sha256:app2", "Size": 170, "RootFS": {"Type": "layers", "Layers": ["sha256:base", "sha256:deps", "sha256:app2code"]}},
+            {"Id": "sha256:db", "Size": 160, "RootFS": {"Type": "layers", "Layers": ["sha256:base", "sha256:dbdata"]}}
+        ]"#;
+        let mut images = parse_image_inspect(json).unwrap();
+        let history: [&[u64]; 3] = [&[30, 0, 50, 0, 100], &[20, 50, 0, 100], &[60, 0, 100]];
+        for (image, sizes) in images.iter_mut().zip(history) {
+            image.layer_sizes = align_layer_sizes(image.layers.len(), sizes).unwrap();
+        }
+        images
+    }
+
+    #[test]
+    fn test_layer_aware_reclaim_counts_shared_layers_once() {
+        let images = mocked_inspect();
+        assert_eq!(images[0].id, "app1");
+        assert_eq!(images[0].layer_sizes, vec![100, 50, 30]);
+
+        // Summing image sizes would claim 350; the base stays for db, deps counts once
+        let in_use: HashSet<String> = ["db".to_string()].into_iter().collect();
+        assert_eq!(layer_aware_reclaim(&images, &in_use), 50 + 30 + 20);
+
+        // With nothing in use, the base is freed too
+        assert_eq!(layer_aware_reclaim(&images, &HashSet::new()), 100 + 50 + 30 + 20 + 60);
+    }
+
+    #[test]
+    fn test_unaligned_history_falls_back_to_image_size() {
+        assert_eq!(align_layer_sizes(3, &[30, 0, 100]), None);
+
+        let mut images = mocked_inspect();
+        images[1].layer_sizes.clear();
+        let in_use: HashSet<String> = ["db".to_string()].into_iter().collect();
+        assert_eq!(layer_aware_reclaim(&images, &in_use), 50 + 30 + 170);
     }
 
     #[test]
@@ -2131,7 +2798,20 @@ mod tests {
         let (score, reasons, auto_select) = score_network(&network);
         assert!(score >= 0.8);
         assert!(!auto_select); // Networks shouldn't auto-select
-        assert!(reasons.iter().any(|r| r.contains("Not used")));
+        assert!(reasons.contains(&ReasonCode::Unused));
     }
-}
 
+    #[tokio::test]
+    async fn test_removal_refused_read_only() {
+        guard::set_read_only_for_thread(true);
+        let removed = remove_containers(vec!["abc123".to_string()], true).await;
+        let pruned = prune_images(true).await;
+        let safe = docker_safe_cleanup().await;
+        guard::set_read_only_for_thread(false);
+
+        for result in [removed, pruned, safe] {
+            let error = result.unwrap_err();
+            assert!(matches!(error.downcast_ref::<CacheError>(), Some(CacheError::DeletionDisabled)));
+        }
+    }
+}
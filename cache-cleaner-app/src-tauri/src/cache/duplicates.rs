@@ -0,0 +1,203 @@
+//! Read-only report of identical files across cache directories.
+//!
+//! Files are grouped by size first, then by a hash of their first block,
+//! and only candidates matching on both are fully hashed. Hashing streams
+//! each file so memory stays bounded.
+
+use crate::utils::hash::{hash_file, hash_first_block};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// A set of files with identical content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    pub size_bytes: u64,
+    pub paths: Vec<String>,
+    /// Bytes that would be reclaimed by keeping a single copy
+    pub reclaimable_bytes: u64,
+}
+
+/// Find identical files under `roots` that are at least `min_size_bytes` large
+pub async fn find_duplicate_files(roots: Vec<String>, min_size_bytes: u64) -> Result<Vec<DuplicateGroup>> {
+    let roots: Vec<PathBuf> = roots.iter().map(PathBuf::from).collect();
+    tokio::task::spawn_blocking(move || find_duplicate_files_sync(&roots, min_size_bytes))
+        .await?
+}
+
+fn find_duplicate_files_sync(roots: &[PathBuf], min_size_bytes: u64) -> Result<Vec<DuplicateGroup>> {
+    // Size prefilter: only files sharing a size can be duplicates
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    let mut seen_files = HashSet::new();
+
+    for root in roots {
+        if !root.exists() {
+            continue;
+        }
+        for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let metadata = match entry.metadata() {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            let size = metadata.len();
+            if size < min_size_bytes || size == 0 {
+                continue;
+            }
+            // Skip overlapping roots and existing hardlinks to the same file
+            if !seen_files.insert(file_identity(entry.path(), &metadata)) {
+                continue;
+            }
+            by_size.entry(size).or_default().push(entry.path().to_path_buf());
+        }
+    }
+
+    let mut groups = Vec::new();
+    for (size, candidates) in by_size {
+        if candidates.len() < 2 {
+            continue;
+        }
+
+        // First-block prefilter: most same-size files already differ there
+        let mut by_prefix: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for path in candidates {
+            match hash_first_block(&path) {
+                Ok(prefix) => by_prefix.entry(prefix).or_default().push(path),
+                Err(e) => eprintln!("Warning: Could not hash {}: {}", path.display(), e),
+            }
+        }
+
+        let mut by_hash: HashMap<String, Vec<String>> = HashMap::new();
+        for path in by_prefix.into_values().filter(|paths| paths.len() > 1).flatten() {
+            match hash_file(&path) {
+                Ok(hash) => by_hash
+                    .entry(hash)
+                    .or_default()
+                    .push(path.to_string_lossy().to_string()),
+                Err(e) => eprintln!("Warning: Could not hash {}: {}", path.display(), e),
+            }
+        }
+
+        for (hash, mut paths) in by_hash {
+            if paths.len() < 2 {
+                continue;
+            }
+            paths.sort();
+            groups.push(DuplicateGroup {
+                hash,
+                size_bytes: size,
+                reclaimable_bytes: size * (paths.len() as u64 - 1),
+                paths,
+            });
+        }
+    }
+
+    groups.sort_by_key(|group| Reverse(group.reclaimable_bytes));
+    Ok(groups)
+}
+
+#[cfg(unix)]
+fn file_identity(_path: &Path, metadata: &std::fs::Metadata) -> (u64, u64, PathBuf) {
+    use std::os::unix::fs::MetadataExt;
+    (metadata.dev(), metadata.ino(), PathBuf::new())
+}
+
+#[cfg(not(unix))]
+fn file_identity(path: &Path, _metadata: &std::fs::Metadata) -> (u64, u64, PathBuf) {
+    (0, 0, path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn create_test_file(dir: &Path, name: &str, content: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        let mut file = File::create(&path).unwrap();
+        file.write_all(content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_three_identical_and_one_unique() {
+        let dir = create_test_dir();
+        let npm = dir.path().join("npm");
+        let yarn = dir.path().join("yarn");
+        fs::create_dir_all(&npm).unwrap();
+        fs::create_dir_all(&yarn).unwrap();
+
+        let content = vec![7u8; 64 * 1024];
+        let mut unique = content.clone();
+        unique[0] = 8; // same size, different content
+
+        create_test_file(&npm, "a.tgz", &content);
+        create_test_file(&npm, "b.tgz", &content);
+        create_test_file(&yarn, "c.tgz", &content);
+        create_test_file(&yarn, "unique.tgz", &unique);
+
+        let groups = find_duplicate_files_sync(&[npm, yarn], 1024).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].paths.len(), 3);
+        assert_eq!(groups[0].size_bytes, 64 * 1024);
+        assert_eq!(groups[0].reclaimable_bytes, 2 * 64 * 1024);
+        assert!(groups[0].paths.iter().all(|p| !p.ends_with("unique.tgz")));
+    }
+
+    #[test]
+    fn test_min_size_filters_small_files() {
+        let dir = create_test_dir();
+        create_test_file(dir.path(), "a.txt", b"same");
+        create_test_file(dir.path(), "b.txt", b"same");
+
+        let groups = find_duplicate_files_sync(&[dir.path().to_path_buf()], 1024).unwrap();
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_roots_not_double_counted() {
+        let dir = create_test_dir();
+        let sub = dir.path().join("sub");
+        fs::create_dir_all(&sub).unwrap();
+        create_test_file(&sub, "a.bin", &[1u8; 2048]);
+        create_test_file(&sub, "b.bin", &[1u8; 2048]);
+
+        let groups = find_duplicate_files_sync(&[dir.path().to_path_buf(), sub], 1).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].paths.len(), 2);
+    }
+
+    #[test]
+    fn test_same_first_block_different_tail() {
+        let dir = create_test_dir();
+        let content = vec![3u8; 32 * 1024];
+        let mut tail_differs = content.clone();
+        *tail_differs.last_mut().unwrap() = 4;
+        create_test_file(dir.path(), "a.bin", &content);
+        create_test_file(dir.path(), "b.bin", &content);
+        create_test_file(dir.path(), "c.bin", &tail_differs);
+
+        let groups = find_duplicate_files_sync(&[dir.path().to_path_buf()], 1).unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].paths.len(), 2);
+        assert!(groups[0].paths.iter().all(|p| !p.ends_with("c.bin")));
+    }
+
+    #[tokio::test]
+    async fn test_find_duplicate_files_nonexistent_root() {
+        let groups = find_duplicate_files(vec!["/nonexistent/path".to_string()], 0).await.unwrap();
+        assert!(groups.is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// `errno` for "Operation not permitted"
+const EPERM: i32 = 1;
+
+/// Errors with a specific meaning for the UI, as opposed to generic I/O failures
+#[derive(Debug, Error)]
+pub enum CacheError {
+    #[error("Cannot find home directory")]
+    HomeNotFound,
+
+    /// macOS privacy protection (TCC/SIP) refused the operation, regardless of
+    /// the file's own permissions
+    #[error(
+        "Operation not permitted on {}. Grant Full Disk Access in System Settings → Privacy & Security → Full Disk Access",
+        path.display()
+    )]
+    TccDenied { path: PathBuf },
+
+    /// A directory walk went deeper than any real cache, likely a pathological tree
+    #[error("{} is more than {} levels deep", path.display(), max_depth)]
+    TooDeep { path: PathBuf, max_depth: usize },
+
+    /// Built with the `readonly` feature
+    #[error("Deletion is disabled in this build")]
+    DeletionDisabled,
+
+    /// Running as root without `--allow-root`, where a path bug could reach system files
+    #[error("Refusing to delete while running as root; pass --allow-root to override")]
+    RunningAsRoot,
+
+    #[error("{}: {}", path.display(), source)]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+impl CacheError {
+    /// Classify an I/O failure on `path`, singling out TCC denials
+    pub fn from_io(path: &Path, source: io::Error) -> Self {
+        if is_operation_not_permitted(&source) {
+            CacheError::TccDenied {
+                path: path.to_path_buf(),
+            }
+        } else {
+            CacheError::Io {
+                path: path.to_path_buf(),
+                source,
+            }
+        }
+    }
+
+    pub fn is_tcc_denied(&self) -> bool {
+        matches!(self, CacheError::TccDenied { .. })
+    }
+}
+
+/// EPERM, as opposed to the EACCES that ordinary file permissions produce
+pub fn is_operation_not_permitted(err: &io::Error) -> bool {
+    err.raw_os_error() == Some(EPERM) || err.to_string().contains("Operation not permitted")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eperm_maps_to_tcc_denied() {
+        let path = Path::new("/Users/test/Library/Caches/com.apple.Safari");
+        let error = CacheError::from_io(path, io::Error::from_raw_os_error(EPERM));
+
+        assert!(matches!(&error, CacheError::TccDenied { path: p } if p == path));
+        assert!(error.to_string().contains("Full Disk Access"));
+    }
+
+    #[test]
+    fn test_other_errors_stay_generic() {
+        let path = Path::new("/tmp/cache");
+        let error = CacheError::from_io(path, io::Error::new(io::ErrorKind::NotFound, "missing"));
+
+        assert!(matches!(error, CacheError::Io { .. }));
+        assert_eq!(error.to_string(), "/tmp/cache: missing");
+    }
+
+    #[test]
+    fn test_eacces_is_not_tcc() {
+        let error = CacheError::from_io(Path::new("/tmp/cache"), io::Error::from_raw_os_error(13));
+        assert!(!error.is_tcc_denied());
+    }
+}
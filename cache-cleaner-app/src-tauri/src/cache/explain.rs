@@ -0,0 +1,423 @@
+//! Plain-language description of a path, for UI tooltips.
+//!
+//! Paths inside a known `MacPaths` location are described from the table
+//! below, the most specific location winning. Anything else falls back to
+//! the location heuristics used by smart suggestions, and for folders those
+//! say nothing about, to a look at what the folder holds. Read-only.
+
+use super::paths::MacPaths;
+use super::smart_suggestions::{
+    classify_contents, content_confidence, determine_confidence, determine_location_type, CleanupConfidence,
+    ContentKind, LocationType,
+};
+use super::{CacheCategory, CacheError};
+use crate::utils::home::require_home;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PathExplanation {
+    pub path: String,
+    pub known_as: String,
+    pub owning_app: Option<String>,
+    pub category: Option<CacheCategory>,
+    pub typical_contents: String,
+    pub safety: CleanupConfidence,
+    /// Whether the owner rebuilds the contents on its own after deletion
+    pub regenerable: bool,
+}
+
+struct KnownLocation {
+    known_as: &'static str,
+    owning_app: Option<&'static str>,
+    category: Option<CacheCategory>,
+    typical_contents: &'static str,
+    safety: CleanupConfidence,
+    regenerable: bool,
+    path: fn() -> Result<PathBuf, CacheError>,
+}
+
+const KNOWN_LOCATIONS: &[KnownLocation] = &[
+    KnownLocation {
+        known_as: "Chrome Cache",
+        owning_app: Some("Google Chrome"),
+        category: Some(CacheCategory::Browser),
+        typical_contents: "Cached web pages, scripts and images",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::chrome_cache,
+    },
+    KnownLocation {
+        known_as: "Safari Cache",
+        owning_app: Some("Safari"),
+        category: Some(CacheCategory::Browser),
+        typical_contents: "Cached web pages, scripts and images",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::safari_cache,
+    },
+    KnownLocation {
+        known_as: "Firefox Cache",
+        owning_app: Some("Firefox"),
+        category: Some(CacheCategory::Browser),
+        typical_contents: "Per-profile caches of web content",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::firefox_profiles,
+    },
+    KnownLocation {
+        known_as: "Arc Cache",
+        owning_app: Some("Arc"),
+        category: Some(CacheCategory::Browser),
+        typical_contents: "Cached web pages, scripts and images",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::arc_cache,
+    },
+    KnownLocation {
+        known_as: "npm Cache",
+        owning_app: Some("npm"),
+        category: Some(CacheCategory::PackageManager),
+        typical_contents: "Downloaded package tarballs and their index",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::npm_cache,
+    },
+    KnownLocation {
+        known_as: "Yarn Cache",
+        owning_app: Some("Yarn"),
+        category: Some(CacheCategory::PackageManager),
+        typical_contents: "Downloaded package archives",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::yarn_cache,
+    },
+    KnownLocation {
+        known_as: "pnpm Store",
+        owning_app: Some("pnpm"),
+        category: Some(CacheCategory::PackageManager),
+        typical_contents: "Content-addressed package files shared by projects",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::pnpm_cache,
+    },
+    KnownLocation {
+        known_as: "pip Cache",
+        owning_app: Some("pip"),
+        category: Some(CacheCategory::PackageManager),
+        typical_contents: "Downloaded and built Python wheels",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::pip_cache,
+    },
+    KnownLocation {
+        known_as: "Poetry Cache",
+        owning_app: Some("Poetry"),
+        category: Some(CacheCategory::PackageManager),
+        typical_contents: "Downloaded packages and virtualenvs",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::poetry_cache,
+    },
+    KnownLocation {
+        known_as: "uv Cache",
+        owning_app: Some("uv"),
+        category: Some(CacheCategory::PackageManager),
+        typical_contents: "Downloaded and built Python packages",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::uv_cache,
+    },
+    KnownLocation {
+        known_as: "CocoaPods Cache",
+        owning_app: Some("CocoaPods"),
+        category: Some(CacheCategory::PackageManager),
+        typical_contents: "Downloaded pod sources and specs",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::cocoapods_cache,
+    },
+    KnownLocation {
+        known_as: "Gradle Cache",
+        owning_app: Some("Gradle"),
+        category: Some(CacheCategory::PackageManager),
+        typical_contents: "Downloaded dependencies and build outputs",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::gradle_cache,
+    },
+    KnownLocation {
+        known_as: "Cargo Registry",
+        owning_app: Some("Cargo"),
+        category: Some(CacheCategory::PackageManager),
+        typical_contents: "Downloaded crate archives and their sources",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::cargo_cache,
+    },
+    KnownLocation {
+        known_as: "Go Build Cache",
+        owning_app: Some("Go"),
+        category: Some(CacheCategory::DevTool),
+        typical_contents: "Compiled packages and test results",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::go_build_cache,
+    },
+    KnownLocation {
+        known_as: "Go Module Cache",
+        owning_app: Some("Go"),
+        category: Some(CacheCategory::PackageManager),
+        typical_contents: "Downloaded module sources",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::go_mod_cache,
+    },
+    KnownLocation {
+        known_as: "Xcode DerivedData",
+        owning_app: Some("Xcode"),
+        category: Some(CacheCategory::DevTool),
+        typical_contents: "Build products, indexes and logs per project",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::xcode_derived_data,
+    },
+    KnownLocation {
+        known_as: "Xcode Archives",
+        owning_app: Some("Xcode"),
+        category: Some(CacheCategory::DevTool),
+        typical_contents: "Archived app builds and their debug symbols",
+        safety: CleanupConfidence::Caution,
+        regenerable: false,
+        path: MacPaths::xcode_archives,
+    },
+    KnownLocation {
+        known_as: "Xcode Simulators",
+        owning_app: Some("Xcode"),
+        category: Some(CacheCategory::DevTool),
+        typical_contents: "Simulator devices with their installed apps and data",
+        safety: CleanupConfidence::Caution,
+        regenerable: false,
+        path: MacPaths::xcode_simulators,
+    },
+    KnownLocation {
+        known_as: "iOS Backups",
+        owning_app: Some("Finder"),
+        category: None,
+        typical_contents: "Full backups of iPhones and iPads",
+        safety: CleanupConfidence::Risky,
+        regenerable: false,
+        path: MacPaths::ios_backups,
+    },
+    KnownLocation {
+        known_as: "Mail Downloads",
+        owning_app: Some("Mail"),
+        category: Some(CacheCategory::System),
+        typical_contents: "Attachments opened from Mail",
+        safety: CleanupConfidence::Caution,
+        regenerable: true,
+        path: MacPaths::mail_downloads,
+    },
+    KnownLocation {
+        known_as: "User Caches",
+        owning_app: None,
+        category: Some(CacheCategory::System),
+        typical_contents: "Caches of apps installed for this user",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::user_caches,
+    },
+    KnownLocation {
+        known_as: "User Logs",
+        owning_app: None,
+        category: Some(CacheCategory::System),
+        typical_contents: "Log files and crash reports",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::user_logs,
+    },
+    KnownLocation {
+        known_as: "Cache Directory",
+        owning_app: None,
+        category: Some(CacheCategory::System),
+        typical_contents: "Caches of command-line tools",
+        safety: CleanupConfidence::Safe,
+        regenerable: true,
+        path: MacPaths::cache_dir,
+    },
+    KnownLocation {
+        known_as: "Trash",
+        owning_app: Some("Finder"),
+        category: None,
+        typical_contents: "Files you deleted but have not emptied",
+        safety: CleanupConfidence::Caution,
+        regenerable: false,
+        path: MacPaths::trash,
+    },
+    KnownLocation {
+        known_as: "Downloads",
+        owning_app: None,
+        category: None,
+        typical_contents: "Files you downloaded",
+        safety: CleanupConfidence::Risky,
+        regenerable: false,
+        path: MacPaths::downloads,
+    },
+    KnownLocation {
+        known_as: "Documents",
+        owning_app: None,
+        category: None,
+        typical_contents: "Your own documents",
+        safety: CleanupConfidence::Risky,
+        regenerable: false,
+        path: MacPaths::documents,
+    },
+    KnownLocation {
+        known_as: "System Caches",
+        owning_app: Some("macOS"),
+        category: Some(CacheCategory::System),
+        typical_contents: "Caches shared by all users",
+        safety: CleanupConfidence::Caution,
+        regenerable: true,
+        path: || Ok(MacPaths::system_caches()),
+    },
+];
+
+/// Explain `path` (which may start with `~/`) without touching it
+pub fn explain_path(path: &str) -> Result<PathExplanation> {
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => require_home()?.join(rest),
+        None => PathBuf::from(path),
+    };
+    // `starts_with` compares components, so `Caches/../../Documents` would
+    // still count as inside `Caches`
+    let path = resolve_dot_components(&path);
+
+    if let Some(known) = most_specific_location(&path) {
+        return Ok(PathExplanation {
+            path: path.display().to_string(),
+            known_as: known.known_as.to_string(),
+            owning_app: known.owning_app.map(|a| a.to_string()),
+            category: known.category,
+            typical_contents: known.typical_contents.to_string(),
+            safety: known.safety,
+            regenerable: known.regenerable,
+        });
+    }
+    Ok(explain_unknown(&path))
+}
+
+/// `path` with `.` and `..` applied, without touching the filesystem
+fn resolve_dot_components(path: &Path) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+    resolved
+}
+
+/// The known location containing `path` with the longest path
+fn most_specific_location(path: &Path) -> Option<&'static KnownLocation> {
+    KNOWN_LOCATIONS
+        .iter()
+        .filter_map(|known| (known.path)().ok().map(|root| (known, root)))
+        .filter(|(_, root)| path.starts_with(root))
+        .max_by_key(|(_, root)| root.components().count())
+        .map(|(known, _)| known)
+}
+
+/// Explanation from the smart suggestion heuristics
+fn explain_unknown(path: &Path) -> PathExplanation {
+    let location = determine_location_type(path);
+    let content = if location == LocationType::Unknown && path.is_dir() {
+        classify_contents(path)
+    } else {
+        None
+    };
+
+    let (known_as, typical_contents, regenerable) = match (&location, content) {
+        (LocationType::Cache, _) => ("Cache folder", "Data an app can download or rebuild", true),
+        (LocationType::Log, _) => ("Log folder", "Log files", true),
+        (LocationType::Dev, _) => ("Developer tool data", "Build products and indexes", true),
+        (LocationType::AppSupport, _) => ("Application data", "Settings and data an app keeps", false),
+        (LocationType::Unknown, Some(ContentKind::Logs)) => ("Folder of logs", "Mostly log files", true),
+        (LocationType::Unknown, Some(ContentKind::NodeModules)) => ("Node.js dependencies", "Installed npm packages", true),
+        (LocationType::Unknown, Some(ContentKind::Images)) => ("Folder of images", "Mostly images", false),
+        (LocationType::Unknown, Some(ContentKind::GitRepo)) => ("Git repository", "Source code under version control", false),
+        (LocationType::Unknown, None) => ("Unknown folder", "Unknown", false),
+    };
+
+    PathExplanation {
+        path: path.display().to_string(),
+        known_as: known_as.to_string(),
+        owning_app: None,
+        category: None,
+        typical_contents: typical_contents.to_string(),
+        safety: content.map_or_else(|| determine_confidence(&location), content_confidence),
+        regenerable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn create_test_dir() -> tempfile::TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    #[test]
+    fn test_npm_cache_is_safe() {
+        let npm = MacPaths::npm_cache().unwrap();
+        let explanation = explain_path(&npm.join("index-v5").display().to_string()).unwrap();
+        assert_eq!(explanation.known_as, "npm Cache");
+        assert_eq!(explanation.owning_app.as_deref(), Some("npm"));
+        assert_eq!(explanation.category, Some(CacheCategory::PackageManager));
+        assert_eq!(explanation.safety, CleanupConfidence::Safe);
+        assert!(explanation.regenerable);
+    }
+
+    #[test]
+    fn test_documents_is_risky() {
+        let explanation = explain_path("~/Documents").unwrap();
+        assert_eq!(explanation.known_as, "Documents");
+        assert_eq!(explanation.safety, CleanupConfidence::Risky);
+        assert!(!explanation.regenerable);
+    }
+
+    #[test]
+    fn test_parent_components_are_resolved() {
+        let explanation = explain_path("~/Library/Caches/../../Documents").unwrap();
+        assert_eq!(explanation.known_as, "Documents");
+        assert_eq!(explanation.safety, CleanupConfidence::Risky);
+        assert!(!explanation.path.contains(".."));
+    }
+
+    #[test]
+    fn test_most_specific_location_wins() {
+        let yarn = MacPaths::yarn_cache().unwrap().join("v6");
+        assert_eq!(explain_path(&yarn.display().to_string()).unwrap().known_as, "Yarn Cache");
+        let other = MacPaths::user_caches().unwrap().join("com.example.app");
+        assert_eq!(explain_path(&other.display().to_string()).unwrap().known_as, "User Caches");
+    }
+
+    #[test]
+    fn test_unknown_folder_sniffs_contents() {
+        let dir = create_test_dir();
+        for name in ["a.log", "b.log", "c.log.1"] {
+            fs::write(dir.path().join(name), b"line").unwrap();
+        }
+        let explanation = explain_unknown(dir.path());
+        assert_eq!(explanation.known_as, "Folder of logs");
+        assert_eq!(explanation.safety, CleanupConfidence::Caution);
+        assert!(explanation.owning_app.is_none());
+    }
+}
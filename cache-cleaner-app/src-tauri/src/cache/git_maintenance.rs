@@ -0,0 +1,300 @@
+//! Reclaimable space inside developer git repositories.
+//!
+//! Repositories are found under user-chosen roots, or the usual project
+//! folders when none are chosen. Loose objects, garbage reported by
+//! `git count-objects -v` and prunable worktrees are estimated as
+//! reclaimable; `git gc --prune=now` does the actual cleanup.
+
+use super::guard;
+use crate::utils::{filesystem, project_roots};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use walkdir::WalkDir;
+
+/// How deep below each root repositories are looked for
+const MAX_SCAN_DEPTH: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitRepoEntry {
+    pub path: String,
+    /// Current size of the `.git` directory
+    pub git_dir_bytes: u64,
+    pub loose_objects: u64,
+    pub loose_bytes: u64,
+    pub garbage_bytes: u64,
+    /// Estimate of what `git gc --prune=now` would free
+    pub reclaimable_bytes: u64,
+    /// Worktrees whose directory no longer exists
+    pub stale_worktrees: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitGcResult {
+    pub total_freed_bytes: u64,
+    pub repos_processed: usize,
+    pub success: bool,
+    pub message: String,
+}
+
+/// Parsed `git count-objects -v`, sizes in bytes
+#[derive(Debug, Clone, Default, PartialEq)]
+struct CountObjects {
+    loose_objects: u64,
+    loose_bytes: u64,
+    garbage_bytes: u64,
+}
+
+/// Check if the `git` CLI is installed
+pub fn is_git_available() -> bool {
+    Command::new("git")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Scan `roots`, or the discovered project folders when none are given
+pub async fn scan_git_maintenance(roots: Vec<String>) -> Result<Vec<GitRepoEntry>> {
+    let roots = if roots.is_empty() {
+        project_roots::discover_default_roots()
+            .into_iter()
+            .map(|root| root.display().to_string())
+            .collect()
+    } else {
+        roots
+    };
+    tokio::task::spawn_blocking(move || scan_git_maintenance_sync(&roots)).await?
+}
+
+fn scan_git_maintenance_sync(roots: &[String]) -> Result<Vec<GitRepoEntry>> {
+    if !is_git_available() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for root in roots {
+        for repo in find_repos(Path::new(root)) {
+            let counts = git_output(&repo, &["count-objects", "-v"])
+                .map(|out| parse_count_objects(&out))
+                .unwrap_or_default();
+            let stale_worktrees = git_output(&repo, &["worktree", "list", "--porcelain"])
+                .map(|out| parse_prunable_worktrees(&out))
+                .unwrap_or_default();
+            entries.push(GitRepoEntry {
+                path: repo.display().to_string(),
+                git_dir_bytes: filesystem::calculate_dir_size_sync(&repo.join(".git")).unwrap_or(0),
+                loose_objects: counts.loose_objects,
+                loose_bytes: counts.loose_bytes,
+                garbage_bytes: counts.garbage_bytes,
+                // Packing compresses loose objects, so count them as reclaimable
+                reclaimable_bytes: counts.loose_bytes + counts.garbage_bytes,
+                stale_worktrees,
+            });
+        }
+    }
+
+    entries.sort_by_key(|entry| Reverse(entry.reclaimable_bytes));
+    Ok(entries)
+}
+
+/// Repositories with a `.git` directory under `root`, not descending into them
+fn find_repos(root: &Path) -> Vec<PathBuf> {
+    let mut repos = Vec::new();
+    let mut walker = WalkDir::new(root).max_depth(MAX_SCAN_DEPTH).into_iter();
+    while let Some(entry) = walker.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        if entry.path().join(".git").is_dir() {
+            repos.push(entry.path().to_path_buf());
+            walker.skip_current_dir();
+        } else if entry.file_name() == "node_modules" {
+            walker.skip_current_dir();
+        }
+    }
+    repos
+}
+
+/// Run `git gc --prune=now` and prune stale worktrees in each repository
+pub async fn run_git_gc(paths: Vec<String>) -> Result<GitGcResult> {
+    guard::ensure_deletion_enabled()?;
+    tokio::task::spawn_blocking(move || run_git_gc_sync(&paths)).await?
+}
+
+fn run_git_gc_sync(paths: &[String]) -> Result<GitGcResult> {
+    if !is_git_available() {
+        return Ok(GitGcResult {
+            total_freed_bytes: 0,
+            repos_processed: 0,
+            success: false,
+            message: "git is not installed".to_string(),
+        });
+    }
+
+    let mut total_freed = 0u64;
+    let mut repos_processed = 0usize;
+    let mut errors = Vec::new();
+
+    for path_str in paths {
+        let repo = PathBuf::from(path_str);
+        let git_dir = repo.join(".git");
+        if !git_dir.is_dir() {
+            errors.push(format!("{}: not a git repository", path_str));
+            continue;
+        }
+
+        let size_before = filesystem::calculate_dir_size_sync(&git_dir).unwrap_or(0);
+        if filesystem::skip_deletion() {
+            let counts = git_output(&repo, &["count-objects", "-v"])
+                .map(|out| parse_count_objects(&out))
+                .unwrap_or_default();
+            total_freed += counts.loose_bytes + counts.garbage_bytes;
+            repos_processed += 1;
+            continue;
+        }
+
+        let gc = git_output(&repo, &["gc", "--prune=now"])
+            .and_then(|_| git_output(&repo, &["worktree", "prune"]));
+        match gc {
+            Ok(_) => {
+                let size_after = filesystem::calculate_dir_size_sync(&git_dir).unwrap_or(0);
+                total_freed += size_before.saturating_sub(size_after);
+                repos_processed += 1;
+            }
+            Err(e) => errors.push(format!("{}: {}", path_str, e)),
+        }
+    }
+
+    let message = if errors.is_empty() {
+        format!("Ran git gc in {} repository(ies)", repos_processed)
+    } else {
+        format!(
+            "Ran git gc in {} repository(ies), {} errors: {}",
+            repos_processed,
+            errors.len(),
+            errors.join("; ")
+        )
+    };
+
+    Ok(GitGcResult {
+        total_freed_bytes: total_freed,
+        repos_processed,
+        success: errors.is_empty(),
+        message,
+    })
+}
+
+fn git_output(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git").arg("-C").arg(repo).args(args).output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Parse `git count-objects -v`, whose sizes are in KiB
+fn parse_count_objects(output: &str) -> CountObjects {
+    let mut counts = CountObjects::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Ok(value) = value.trim().parse::<u64>() else {
+            continue;
+        };
+        match key.trim() {
+            "count" => counts.loose_objects = value,
+            "size" => counts.loose_bytes = value * 1024,
+            "size-garbage" => counts.garbage_bytes = value * 1024,
+            _ => {}
+        }
+    }
+    counts
+}
+
+/// Worktree paths `git worktree list --porcelain` marks as prunable
+fn parse_prunable_worktrees(output: &str) -> Vec<String> {
+    output
+        .split("\n\n")
+        .filter(|block| block.lines().any(|line| line.starts_with("prunable")))
+        .filter_map(|block| block.lines().find_map(|line| line.strip_prefix("worktree ")))
+        .map(|path| path.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_count_objects() {
+        let output = "count: 1532\nsize: 10240\nin-pack: 84321\npacks: 3\nsize-pack: 204800\nprune-packable: 12\ngarbage: 2\nsize-garbage: 512\n";
+        assert_eq!(
+            parse_count_objects(output),
+            CountObjects {
+                loose_objects: 1532,
+                loose_bytes: 10240 * 1024,
+                garbage_bytes: 512 * 1024,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_count_objects_clean_repo() {
+        let output = "count: 0\nsize: 0\nin-pack: 10\npacks: 1\nsize-pack: 4\nprune-packable: 0\ngarbage: 0\nsize-garbage: 0\n";
+        assert_eq!(parse_count_objects(output), CountObjects::default());
+    }
+
+    #[test]
+    fn test_parse_prunable_worktrees() {
+        let output = "worktree /Users/test/project\nHEAD 1234\nbranch refs/heads/main\n\n\
+                      worktree /Users/test/project-feature\nHEAD 5678\nbranch refs/heads/feature\nprunable gitdir file points to non-existent location\n\n";
+        assert_eq!(parse_prunable_worktrees(output), vec!["/Users/test/project-feature"]);
+    }
+
+    #[test]
+    fn test_find_repos_stops_at_repo_root() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("a/.git")).unwrap();
+        fs::create_dir_all(root.path().join("a/vendor/b/.git")).unwrap();
+        fs::create_dir_all(root.path().join("work/c/.git")).unwrap();
+        fs::create_dir_all(root.path().join("plain")).unwrap();
+
+        let mut repos = find_repos(root.path());
+        repos.sort();
+        assert_eq!(repos, vec![root.path().join("a"), root.path().join("work/c")]);
+    }
+
+    #[test]
+    fn test_repo_path_with_spaces_and_unicode() {
+        if !is_git_available() {
+            return;
+        }
+        let root = tempfile::tempdir().unwrap();
+        let repo = root.path().join("my projects 🚀/café app");
+        fs::create_dir_all(&repo).unwrap();
+        let init = Command::new("git").arg("init").arg("-q").arg(&repo).status().unwrap();
+        assert!(init.success());
+
+        assert_eq!(find_repos(root.path()), vec![repo.clone()]);
+        // The path reaches git as one argument, not split at the spaces
+        let toplevel = git_output(&repo, &["rev-parse", "--show-toplevel"]).unwrap();
+        assert_eq!(
+            fs::canonicalize(toplevel.trim()).unwrap(),
+            fs::canonicalize(&repo).unwrap()
+        );
+        let counts = parse_count_objects(&git_output(&repo, &["count-objects", "-v"]).unwrap());
+        assert_eq!(counts.loose_objects, 0);
+    }
+}
@@ -0,0 +1,249 @@
+//! Go build cache and module cache.
+//!
+//! Both locations can be moved with `GOCACHE`, `GOMODCACHE` and `GOPATH`.
+//! Cleaning goes through `go clean` when the toolchain is installed; without
+//! it the directories are emptied directly. The module cache is written
+//! read-only by Go, so it is made writable first.
+
+use super::paths::MacPaths;
+use super::{manifest, CacheInfo, CacheType, CleanResult, CleanStrategy};
+use crate::utils::filesystem;
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+use walkdir::WalkDir;
+
+/// `GOCACHE`, or the default `~/Library/Caches/go-build` on macOS
+pub fn go_build_cache_in(home: &Path, env: impl Fn(&str) -> Option<String>) -> PathBuf {
+    match env_path(&env, "GOCACHE") {
+        Some(path) => path,
+        None => home.join("Library/Caches/go-build"),
+    }
+}
+
+/// `GOMODCACHE`, else `pkg/mod` in the first `GOPATH` entry, else `~/go/pkg/mod`
+pub fn go_mod_cache_in(home: &Path, env: impl Fn(&str) -> Option<String>) -> PathBuf {
+    if let Some(path) = env_path(&env, "GOMODCACHE") {
+        return path;
+    }
+    let gopath = env("GOPATH")
+        .and_then(|value| value.split(':').find(|p| !p.is_empty()).map(PathBuf::from))
+        .filter(|p| p.is_absolute());
+    gopath.unwrap_or_else(|| home.join("go")).join("pkg/mod")
+}
+
+/// Absolute path from `key`; Go ignores relative values, and so do we
+fn env_path(env: &impl Fn(&str) -> Option<String>, key: &str) -> Option<PathBuf> {
+    env(key).map(PathBuf::from).filter(|p| p.is_absolute())
+}
+
+/// Check if the `go` toolchain is installed
+pub fn is_go_available() -> bool {
+    Command::new("go")
+        .arg("version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// `go clean` when the toolchain is installed, emptying the directory otherwise
+pub fn clean_method(cache_type: &CacheType, go_available: bool) -> CleanStrategy {
+    match cache_type.clean_strategy() {
+        CleanStrategy::RunCommand(command) if go_available => CleanStrategy::RunCommand(command),
+        _ => CleanStrategy::RemoveContents,
+    }
+}
+
+fn cache_path(cache_type: &CacheType) -> Result<PathBuf> {
+    match cache_type {
+        CacheType::GoBuild => Ok(MacPaths::go_build_cache()?),
+        CacheType::GoModCache => Ok(MacPaths::go_mod_cache()?),
+        _ => Err(anyhow!("Not a Go cache type: {:?}", cache_type)),
+    }
+}
+
+pub fn get_go_cache_info(cache_type: CacheType) -> Result<CacheInfo> {
+    let path = cache_path(&cache_type)?;
+    let exists = path.exists();
+    let (size, item_count) = if exists {
+        (
+            filesystem::calculate_dir_size_sync(&path).unwrap_or(0),
+            filesystem::count_items(&path).unwrap_or(0),
+        )
+    } else {
+        (0, 0)
+    };
+
+    Ok(CacheInfo {
+        cache_type,
+        path: path.display().to_string(),
+        size,
+        exists,
+        item_count,
+        size_margin: None,
+        note: None,
+    })
+}
+
+pub fn clean_go_cache(cache_type: CacheType, dry_run: bool, protect_recent: Option<Duration>) -> Result<CleanResult> {
+    let path = cache_path(&cache_type)?;
+    let method = clean_method(&cache_type, is_go_available());
+    clean_go_cache_at(cache_type, &path, method, dry_run, protect_recent)
+}
+
+fn clean_go_cache_at(
+    cache_type: CacheType,
+    path: &Path,
+    method: CleanStrategy,
+    dry_run: bool,
+    protect_recent: Option<Duration>,
+) -> Result<CleanResult> {
+    if !path.exists() {
+        return Ok(CleanResult {
+            cache_type,
+            freed_bytes: 0,
+            items_removed: 0,
+            success: true,
+            message: "Cache directory does not exist".to_string(),
+            dry_run,
+            ..Default::default()
+        });
+    }
+
+    let size_before = filesystem::calculate_dir_size_sync(path)?;
+    let items_before = filesystem::count_items(path)?;
+
+    if dry_run || filesystem::skip_deletion() {
+        return Ok(CleanResult {
+            cache_type,
+            freed_bytes: size_before,
+            items_removed: items_before,
+            success: true,
+            message: format!("Would clean {} items ({} bytes)", items_before, size_before),
+            dry_run,
+            ..Default::default()
+        });
+    }
+
+    if let CleanStrategy::RunCommand(command) = method {
+        manifest::run_cleaner_command(command)?;
+        let size_after = filesystem::calculate_dir_size_sync(path).unwrap_or(0);
+        let freed_bytes = size_before.saturating_sub(size_after);
+        return Ok(CleanResult {
+            cache_type,
+            freed_bytes,
+            items_removed: items_before,
+            success: true,
+            message: format!("Ran `{}`, freed {} bytes", command, freed_bytes),
+            dry_run: false,
+            ..Default::default()
+        });
+    }
+
+    make_writable(path);
+    let outcome = filesystem::empty_dir_measured(path, size_before, items_before, protect_recent);
+    Ok(CleanResult::from_removal(cache_type, &outcome, format!("Successfully cleaned {} items", items_before)))
+}
+
+/// Add owner write permission to every directory below `path`, so the
+/// read-only trees Go extracts modules into can be deleted
+fn make_writable(path: &Path) {
+    use std::os::unix::fs::PermissionsExt;
+
+    for entry in WalkDir::new(path).into_iter().flatten() {
+        if !entry.file_type().is_dir() {
+            continue;
+        }
+        if let Ok(metadata) = entry.metadata() {
+            let mut permissions = metadata.permissions();
+            if permissions.mode() & 0o200 == 0 {
+                permissions.set_mode(permissions.mode() | 0o200);
+                let _ = std::fs::set_permissions(entry.path(), permissions);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn test_go_build_cache_resolution() {
+        let home = Path::new("/Users/test");
+        assert_eq!(go_build_cache_in(home, env(&[])), home.join("Library/Caches/go-build"));
+        assert_eq!(
+            go_build_cache_in(home, env(&[("GOCACHE", "/Volumes/fast/go-build")])),
+            PathBuf::from("/Volumes/fast/go-build")
+        );
+        // Relative values are ignored, as Go does
+        assert_eq!(
+            go_build_cache_in(home, env(&[("GOCACHE", "cache")])),
+            home.join("Library/Caches/go-build")
+        );
+    }
+
+    #[test]
+    fn test_go_mod_cache_resolution() {
+        let home = Path::new("/Users/test");
+        assert_eq!(go_mod_cache_in(home, env(&[])), home.join("go/pkg/mod"));
+        assert_eq!(
+            go_mod_cache_in(home, env(&[("GOPATH", "/opt/go:/Users/test/other")])),
+            PathBuf::from("/opt/go/pkg/mod")
+        );
+        assert_eq!(
+            go_mod_cache_in(home, env(&[("GOPATH", "/opt/go"), ("GOMODCACHE", "/data/gomod")])),
+            PathBuf::from("/data/gomod")
+        );
+    }
+
+    #[test]
+    fn test_clean_method_prefers_go_clean() {
+        assert_eq!(
+            clean_method(&CacheType::GoBuild, true),
+            CleanStrategy::RunCommand("go clean -cache")
+        );
+        assert_eq!(
+            clean_method(&CacheType::GoModCache, true),
+            CleanStrategy::RunCommand("go clean -modcache")
+        );
+        assert_eq!(clean_method(&CacheType::GoBuild, false), CleanStrategy::RemoveContents);
+        assert_eq!(clean_method(&CacheType::GoModCache, false), CleanStrategy::RemoveContents);
+    }
+
+    #[test]
+    fn test_fallback_empties_read_only_module_cache() {
+        let root = create_test_dir();
+        let module = root.path().join("github.com/example/lib@v1.0.0");
+        fs::create_dir_all(&module).unwrap();
+        fs::write(module.join("lib.go"), [b' '; 100]).unwrap();
+        fs::set_permissions(&module, fs::Permissions::from_mode(0o555)).unwrap();
+
+        let result = clean_go_cache_at(
+            CacheType::GoModCache,
+            root.path(),
+            CleanStrategy::RemoveContents,
+            false,
+            None,
+        )
+        .unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.freed_bytes, 100);
+        assert!(root.path().exists());
+        assert_eq!(fs::read_dir(root.path()).unwrap().count(), 0);
+    }
+}
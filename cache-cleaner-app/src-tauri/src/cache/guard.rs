@@ -0,0 +1,383 @@
+//! Last line of defense before anything is deleted.
+//!
+//! Every destructive operation on a user-supplied path should call
+//! `assert_safe_to_delete` first. It refuses the filesystem root, system
+//! locations, the home directory itself and well-known personal folders.
+//!
+//! `CACHE_CLEANER_READONLY=1` turns every deletion into a dry run: the
+//! removal primitives in `utils::filesystem` consult `is_read_only` and only
+//! measure. Cleaners that hand deletion to an external tool (Docker,
+//! `qlmanage`, manifest cleanup commands) cannot be measured that way and
+//! refuse through `ensure_commands_enabled` instead.
+//!
+//! Running as root, nothing is deleted unless the app was started with
+//! `--allow-root`; scanning still works.
+
+use super::error::CacheError;
+use super::paths::MacPaths;
+use super::smart_suggestions::WHITELIST_PATHS;
+use crate::utils::home::require_home;
+use anyhow::{anyhow, Result};
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Top-level locations that must never be deleted themselves
+const PROTECTED_ROOTS: &[&str] = &[
+    "/",
+    "/Applications",
+    "/Library",
+    "/System",
+    "/Users",
+    "/Volumes",
+    "/private",
+    "/private/tmp",
+    "/private/var",
+    "/var",
+    "/etc",
+    "/tmp",
+    "/opt",
+    "/cores",
+];
+
+/// Home-relative folders that must never be deleted themselves
+const PROTECTED_HOME_DIRS: &[&str] = &[
+    "Applications",
+    "Desktop",
+    "Documents",
+    "Downloads",
+    "Library",
+    "Library/Application Support",
+    "Library/Caches",
+    "Library/Containers",
+    "Library/Logs",
+    "Movies",
+    "Music",
+    "Pictures",
+    "Public",
+];
+
+/// Home-relative folders whose contents must never be deleted
+const PROTECTED_HOME_TREES: &[&str] = &[
+    ".ssh",
+    ".gnupg",
+    "Library/Keychains",
+    "Library/Mail",
+    "Library/Messages",
+];
+
+/// Protected folders that are still cleaned from the inside
+const CACHE_CONTAINERS: &[&str] = &["Library/Caches", "Library/Logs"];
+
+/// Placeholder for `*` when checking whitelist templates
+const WILDCARD_PLACEHOLDER: &str = "com.example.app";
+
+/// Command-line flag that lets a root process delete
+pub const ALLOW_ROOT_FLAG: &str = "--allow-root";
+
+static ALLOW_ROOT: AtomicBool = AtomicBool::new(false);
+
+/// Fail with `CacheError::DeletionDisabled` in `readonly` builds, which can
+/// scan and report but never delete, and with `CacheError::RunningAsRoot`
+/// when running as root without the override
+pub fn ensure_deletion_enabled() -> Result<()> {
+    if cfg!(feature = "readonly") {
+        return Err(CacheError::DeletionDisabled.into());
+    }
+    ensure_not_root()
+}
+
+/// `ensure_deletion_enabled` for cleaners that run an external tool, which
+/// deletes on its own: these also fail in read-only mode
+pub fn ensure_commands_enabled() -> Result<()> {
+    if is_read_only() {
+        return Err(CacheError::DeletionDisabled.into());
+    }
+    ensure_deletion_enabled()
+}
+
+/// Honor `--allow-root` if it is among the process arguments
+pub fn init_allow_root(args: impl IntoIterator<Item = String>) {
+    if args.into_iter().any(|arg| arg == ALLOW_ROOT_FLAG) {
+        ALLOW_ROOT.store(true, Ordering::Relaxed);
+    }
+}
+
+/// What `--allow-root` does, for tests that run real cleaners: test suites
+/// often run as root in containers
+#[cfg(test)]
+pub(crate) fn allow_root_for_tests() {
+    init_allow_root([ALLOW_ROOT_FLAG.to_string()]);
+}
+
+/// Fail with `CacheError::RunningAsRoot` when the effective user is root
+pub fn ensure_not_root() -> Result<()> {
+    // SAFETY: geteuid has no preconditions and cannot fail
+    check_not_root(|| unsafe { libc::geteuid() }, ALLOW_ROOT.load(Ordering::Relaxed))
+}
+
+fn check_not_root(euid: impl Fn() -> u32, allow_root: bool) -> Result<()> {
+    if euid() == 0 && !allow_root {
+        return Err(CacheError::RunningAsRoot.into());
+    }
+    Ok(())
+}
+
+/// Environment variable that makes every deletion a no-op
+pub const READ_ONLY_ENV: &str = "CACHE_CLEANER_READONLY";
+
+#[cfg(test)]
+thread_local! {
+    static READ_ONLY_FOR_THREAD: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// True when `CACHE_CLEANER_READONLY` is set: deletions are measured and
+/// reported but nothing is removed
+pub fn is_read_only() -> bool {
+    #[cfg(test)]
+    if READ_ONLY_FOR_THREAD.with(|flag| flag.get()) {
+        return true;
+    }
+    read_only_value(std::env::var(READ_ONLY_ENV).ok().as_deref())
+}
+
+fn read_only_value(value: Option<&str>) -> bool {
+    matches!(value.map(str::trim), Some("1") | Some("true"))
+}
+
+/// Read-only mode for the current test thread only, since the environment
+/// is shared by tests running in parallel
+#[cfg(test)]
+pub(crate) fn set_read_only_for_thread(enabled: bool) {
+    READ_ONLY_FOR_THREAD.with(|flag| flag.set(enabled));
+}
+
+/// Return an error if `path` is not safe to delete
+pub fn assert_safe_to_delete(path: &Path) -> Result<()> {
+    let home = dirs::home_dir();
+    check_path(path, home.as_deref())?;
+    if is_git_repo_root(path) {
+        return Err(refuse(path, "git repository root"));
+    }
+
+    // Resolve symlinks so a link cannot smuggle in a protected target
+    if let Ok(canonical) = path.canonicalize() {
+        if canonical != path {
+            check_path(&canonical, home.as_deref())?;
+        }
+    }
+    Ok(())
+}
+
+/// `path` holds a `.git` directory or file (worktrees and submodules use a
+/// file), so it is someone's working tree rather than a cache
+pub fn is_git_repo_root(path: &Path) -> bool {
+    std::fs::symlink_metadata(path.join(".git")).is_ok()
+}
+
+/// Check every whitelist template and `MacPaths` cache path against the guard.
+///
+/// Catches a careless edit that points cleaning at a protected location.
+pub fn validate_whitelists() -> Result<()> {
+    let home = require_home()?;
+    let mut roots: Vec<PathBuf> = WHITELIST_PATHS
+        .iter()
+        .map(|template| expand_template(template, &home))
+        .collect();
+    roots.extend(MacPaths::all_cache_paths()?.into_iter().map(|(_, path)| path));
+    validate_roots(&roots, &home)
+}
+
+fn expand_template(template: &str, home: &Path) -> PathBuf {
+    let expanded = template.replace('*', WILDCARD_PLACEHOLDER);
+    if expanded == "~" {
+        home.to_path_buf()
+    } else if let Some(rest) = expanded.strip_prefix("~/") {
+        home.join(rest)
+    } else {
+        PathBuf::from(expanded)
+    }
+}
+
+/// Every root must be deletable itself, or be a known cache container
+fn validate_roots(roots: &[PathBuf], home: &Path) -> Result<()> {
+    let failures: Vec<String> = roots
+        .iter()
+        .filter(|root| !CACHE_CONTAINERS.iter().any(|c| **root == home.join(c)))
+        .filter_map(|root| check_path(root, Some(home)).err())
+        .map(|e| e.to_string())
+        .collect();
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("Unsafe cleaning roots: {}", failures.join("; ")))
+    }
+}
+
+fn check_path(path: &Path, home: Option<&Path>) -> Result<()> {
+    if !path.is_absolute() {
+        return Err(refuse(path, "path is not absolute"));
+    }
+
+    let mut normal_components = 0;
+    for component in path.components() {
+        match component {
+            Component::Normal(_) => normal_components += 1,
+            Component::RootDir => {}
+            _ => return Err(refuse(path, "path contains relative components")),
+        }
+    }
+    if normal_components < 2 {
+        return Err(refuse(path, "path is too close to the filesystem root"));
+    }
+
+    let normalized = strip_trailing_separator(path);
+    if PROTECTED_ROOTS.iter().any(|p| normalized == Path::new(p)) {
+        return Err(refuse(path, "protected system location"));
+    }
+    if MacPaths::is_system_path(&normalized) {
+        return Err(refuse(path, "system-protected path"));
+    }
+
+    if let Some(home) = home {
+        if normalized == home || home.starts_with(&normalized) {
+            return Err(refuse(path, "home directory or one of its parents"));
+        }
+        if PROTECTED_HOME_DIRS.iter().any(|d| normalized == home.join(d)) {
+            return Err(refuse(path, "protected personal folder"));
+        }
+        if PROTECTED_HOME_TREES.iter().any(|d| normalized.starts_with(home.join(d))) {
+            return Err(refuse(path, "protected personal data"));
+        }
+    }
+
+    Ok(())
+}
+
+fn strip_trailing_separator(path: &Path) -> PathBuf {
+    path.components().collect()
+}
+
+fn refuse(path: &Path, reason: &str) -> anyhow::Error {
+    anyhow!("Refusing to delete {}: {}", path.display(), reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_root_blocks_deletion_unless_allowed() {
+        let error = check_not_root(|| 0, false).unwrap_err();
+        assert!(matches!(error.downcast_ref::<CacheError>(), Some(CacheError::RunningAsRoot)));
+        assert!(check_not_root(|| 0, true).is_ok());
+        assert!(check_not_root(|| 501, false).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_root_and_system_paths() {
+        assert!(assert_safe_to_delete(Path::new("/")).is_err());
+        assert!(assert_safe_to_delete(Path::new("/System")).is_err());
+        assert!(assert_safe_to_delete(Path::new("/System/Library/Caches")).is_err());
+        assert!(assert_safe_to_delete(Path::new("/usr/local")).is_err());
+        assert!(assert_safe_to_delete(Path::new("/Applications")).is_err());
+        assert!(assert_safe_to_delete(Path::new("/private/var")).is_err());
+    }
+
+    #[test]
+    fn test_rejects_relative_and_traversal_paths() {
+        assert!(assert_safe_to_delete(Path::new("relative/path")).is_err());
+        assert!(assert_safe_to_delete(Path::new("/tmp/cache/../../etc")).is_err());
+    }
+
+    #[test]
+    fn test_rejects_home_and_personal_folders() {
+        let home = PathBuf::from("/Users/test");
+        assert!(check_path(&home, Some(home.as_path())).is_err());
+        assert!(check_path(Path::new("/Users/test/"), Some(home.as_path())).is_err());
+        assert!(check_path(&home.join("Documents"), Some(home.as_path())).is_err());
+        assert!(check_path(&home.join("Library"), Some(home.as_path())).is_err());
+        assert!(check_path(&home.join("Library/Caches"), Some(home.as_path())).is_err());
+        assert!(check_path(&home.join(".ssh/keys"), Some(home.as_path())).is_err());
+        assert!(check_path(&home.join("Library/Keychains/login.keychain-db"), Some(home.as_path())).is_err());
+    }
+
+    #[test]
+    fn test_allows_cache_folders() {
+        let home = PathBuf::from("/Users/test");
+        assert!(check_path(&home.join("Library/Caches/com.example.app"), Some(home.as_path())).is_ok());
+        assert!(check_path(&home.join(".npm/_cacache"), Some(home.as_path())).is_ok());
+        assert!(check_path(&home.join("Downloads/old-stuff"), Some(home.as_path())).is_ok());
+        assert!(check_path(Path::new("/tmp/some-cache"), Some(home.as_path())).is_ok());
+    }
+
+    #[test]
+    fn test_builtin_whitelist_is_safe() {
+        let home = PathBuf::from("/Users/test");
+        let roots: Vec<PathBuf> = WHITELIST_PATHS.iter().map(|t| expand_template(t, &home)).collect();
+        assert!(validate_roots(&roots, &home).is_ok());
+    }
+
+    #[test]
+    fn test_unsafe_whitelist_entry_fails() {
+        let home = PathBuf::from("/Users/test");
+        let mut templates = WHITELIST_PATHS.to_vec();
+        templates.push("~");
+        let roots: Vec<PathBuf> = templates.iter().map(|t| expand_template(t, &home)).collect();
+
+        let err = validate_roots(&roots, &home).unwrap_err();
+        assert!(err.to_string().contains("home directory"));
+    }
+
+    #[test]
+    fn test_expand_template() {
+        let home = PathBuf::from("/Users/test");
+        assert_eq!(
+            expand_template("~/Library/Containers/*/Data/Library/Caches", &home),
+            home.join("Library/Containers/com.example.app/Data/Library/Caches")
+        );
+        assert_eq!(expand_template("~", &home), home);
+    }
+
+    #[test]
+    fn test_allows_temp_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("to_delete");
+        std::fs::create_dir(&target).unwrap();
+        assert!(assert_safe_to_delete(&target).is_ok());
+    }
+
+    #[test]
+    fn test_refuses_git_repo_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().join("cache");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+        let error = assert_safe_to_delete(&repo).unwrap_err();
+        assert!(error.to_string().contains("git repository root"));
+        assert!(assert_safe_to_delete(&repo.join(".git")).is_ok());
+    }
+
+    #[test]
+    fn test_read_only_value() {
+        assert!(read_only_value(Some("1")));
+        assert!(read_only_value(Some("true")));
+        assert!(!read_only_value(Some("0")));
+        assert!(!read_only_value(Some("")));
+        assert!(!read_only_value(None));
+    }
+
+    #[test]
+    fn test_read_only_refuses_only_commands() {
+        let disabled = |result: Result<()>| {
+            result.is_err_and(|e| matches!(e.downcast_ref::<CacheError>(), Some(CacheError::DeletionDisabled)))
+        };
+        set_read_only_for_thread(true);
+        let commands = ensure_commands_enabled();
+        let deletion = ensure_deletion_enabled();
+        set_read_only_for_thread(false);
+
+        assert!(disabled(commands));
+        // File deletion goes on as an estimate
+        assert_eq!(disabled(deletion), cfg!(feature = "readonly"));
+    }
+}
@@ -0,0 +1,252 @@
+//! Persistent index of cache sizes for instant UI load.
+//!
+//! `refresh_index` runs a full scan and upserts one row per cache into
+//! `~/.cache-cleaner/index.db`; `query_index` answers straight from the
+//! database without touching the disk being measured. The schema version is
+//! kept in SQLite's `user_version` pragma.
+
+use super::{scanner, CacheInfo, CacheType};
+use crate::utils::home::app_data_dir;
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Schema version this build expects
+const SCHEMA_VERSION: i64 = 1;
+
+fn index_path() -> Result<PathBuf> {
+    Ok(app_data_dir()?.join("index.db"))
+}
+
+fn open_index() -> Result<Connection> {
+    let path = index_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let conn = Connection::open(path)?;
+    migrate(&conn)?;
+    Ok(conn)
+}
+
+/// Bring the schema up to `SCHEMA_VERSION`
+fn migrate(conn: &Connection) -> Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version < 1 {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS cache_index (
+                path TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                size INTEGER NOT NULL,
+                item_count INTEGER NOT NULL,
+                last_scanned INTEGER NOT NULL
+            );",
+        )?;
+    }
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    Ok(())
+}
+
+/// Rescan every cache and replace the index contents, returning the row count
+pub async fn refresh_index() -> Result<usize> {
+    let report = scanner::scan_all().await;
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+    let conn = open_index()?;
+    upsert_entries(&conn, &report.caches, now)
+}
+
+/// Cached sizes as of the last refresh, largest first
+pub fn query_index() -> Result<Vec<CacheInfo>> {
+    query_entries(&open_index()?)
+}
+
+/// Store fresh measurements for individual rows; `None` means the cache is gone
+pub(super) fn update_indexed_sizes(updates: &[(String, Option<(u64, usize)>)]) -> Result<()> {
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+    apply_updates(&open_index()?, updates, now)
+}
+
+fn apply_updates(conn: &Connection, updates: &[(String, Option<(u64, usize)>)], scanned_at: u64) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    for (path, measured) in updates {
+        match measured {
+            Some((size, item_count)) => tx.execute(
+                "UPDATE cache_index SET size = ?2, item_count = ?3, last_scanned = ?4 WHERE path = ?1",
+                params![path, *size as i64, *item_count as i64, scanned_at as i64],
+            )?,
+            None => tx.execute("DELETE FROM cache_index WHERE path = ?1", params![path])?,
+        };
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn upsert_entries(conn: &Connection, caches: &[CacheInfo], scanned_at: u64) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    let mut count = 0;
+    for cache in caches.iter().filter(|c| c.exists) {
+        tx.execute(
+            "INSERT INTO cache_index (path, kind, size, item_count, last_scanned)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(path) DO UPDATE SET
+                kind = excluded.kind,
+                size = excluded.size,
+                item_count = excluded.item_count,
+                last_scanned = excluded.last_scanned",
+            params![
+                cache.path,
+                serde_json::to_string(&cache.cache_type)?,
+                cache.size as i64,
+                cache.item_count as i64,
+                scanned_at as i64,
+            ],
+        )?;
+        count += 1;
+    }
+    // Caches that have disappeared since the previous refresh
+    tx.execute(
+        "DELETE FROM cache_index WHERE last_scanned < ?1",
+        params![scanned_at as i64],
+    )?;
+    tx.commit()?;
+    Ok(count)
+}
+
+fn query_entries(conn: &Connection) -> Result<Vec<CacheInfo>> {
+    let mut stmt = conn.prepare(
+        "SELECT path, kind, size, item_count FROM cache_index ORDER BY size DESC, path",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+            row.get::<_, i64>(3)?,
+        ))
+    })?;
+
+    let mut caches = Vec::new();
+    for row in rows {
+        let (path, kind, size, item_count) = row?;
+        // Rows written by a build that knew other cache types are skipped
+        let Ok(cache_type) = serde_json::from_str::<CacheType>(&kind) else {
+            continue;
+        };
+        caches.push(CacheInfo {
+            cache_type,
+            path,
+            size: size as u64,
+            exists: true,
+            item_count: item_count as usize,
+            size_margin: None,
+            note: None,
+        });
+    }
+    Ok(caches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory_index() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        conn
+    }
+
+    fn cache(cache_type: CacheType, path: &str, size: u64) -> CacheInfo {
+        CacheInfo {
+            cache_type,
+            path: path.to_string(),
+            size,
+            exists: true,
+            item_count: 3,
+            size_margin: None,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_migrate_sets_user_version() {
+        let conn = memory_index();
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+        // Running again on an up-to-date schema is a no-op
+        migrate(&conn).unwrap();
+    }
+
+    #[test]
+    fn test_upsert_and_query_round_trip() {
+        let conn = memory_index();
+        let caches = vec![
+            cache(CacheType::Npm, "/Users/test/.npm", 100),
+            cache(CacheType::Manifest("bun".to_string()), "/Users/test/.bun/install/cache", 300),
+        ];
+        assert_eq!(upsert_entries(&conn, &caches, 1_000).unwrap(), 2);
+
+        let indexed = query_entries(&conn).unwrap();
+        assert_eq!(indexed.len(), 2);
+        assert_eq!(indexed[0].cache_type, CacheType::Manifest("bun".to_string()));
+        assert_eq!(indexed[0].size, 300);
+        assert_eq!(indexed[1].path, "/Users/test/.npm");
+        assert_eq!(indexed[1].item_count, 3);
+    }
+
+    #[test]
+    fn test_upsert_updates_and_drops_stale_rows() {
+        let conn = memory_index();
+        upsert_entries(
+            &conn,
+            &[
+                cache(CacheType::Npm, "/Users/test/.npm", 100),
+                cache(CacheType::Yarn, "/Users/test/Library/Caches/Yarn", 50),
+            ],
+            1_000,
+        )
+        .unwrap();
+        upsert_entries(&conn, &[cache(CacheType::Npm, "/Users/test/.npm", 40)], 2_000).unwrap();
+
+        let indexed = query_entries(&conn).unwrap();
+        assert_eq!(indexed.len(), 1);
+        assert_eq!(indexed[0].size, 40);
+    }
+
+    #[test]
+    fn test_apply_updates_touches_only_given_rows() {
+        let conn = memory_index();
+        upsert_entries(
+            &conn,
+            &[
+                cache(CacheType::Npm, "/Users/test/.npm", 100),
+                cache(CacheType::Yarn, "/Users/test/Library/Caches/Yarn", 50),
+                cache(CacheType::Pip, "/Users/test/Library/Caches/pip", 20),
+            ],
+            1_000,
+        )
+        .unwrap();
+        apply_updates(
+            &conn,
+            &[
+                ("/Users/test/.npm".to_string(), Some((400, 9))),
+                ("/Users/test/Library/Caches/pip".to_string(), None),
+            ],
+            2_000,
+        )
+        .unwrap();
+
+        let indexed = query_entries(&conn).unwrap();
+        assert_eq!(indexed.len(), 2);
+        assert_eq!((indexed[0].size, indexed[0].item_count), (400, 9));
+        assert_eq!(indexed[1].size, 50);
+    }
+
+    #[test]
+    fn test_missing_caches_are_not_indexed() {
+        let conn = memory_index();
+        let mut missing = cache(CacheType::Pip, "/Users/test/Library/Caches/pip", 0);
+        missing.exists = false;
+        assert_eq!(upsert_entries(&conn, &[missing], 1_000).unwrap(), 0);
+        assert!(query_entries(&conn).unwrap().is_empty());
+    }
+}
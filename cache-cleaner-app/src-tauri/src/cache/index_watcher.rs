@@ -0,0 +1,282 @@
+//! Keeps the cache index fresh from filesystem events instead of full rescans.
+//!
+//! `start_watching` watches every indexed cache path (FSEvents on macOS) and
+//! marks the rows whose tree changed as dirty. Once events have been quiet
+//! for `DEBOUNCE`, only the dirty rows are measured again and written back to
+//! the index. A cache that keeps changing is still refreshed every
+//! `MAX_DELAY`, so a long build cannot hold its row stale forever.
+
+use super::index;
+use crate::utils::filesystem;
+use anyhow::{anyhow, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Quiet period after the last event before dirty rows are measured
+const DEBOUNCE: Duration = Duration::from_secs(2);
+/// Longest a dirty row waits while events keep coming
+const MAX_DELAY: Duration = Duration::from_secs(30);
+/// How often the flush task checks for dirty rows
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+static ACTIVE: Mutex<Option<ActiveWatcher>> = Mutex::new(None);
+
+struct ActiveWatcher {
+    _watcher: RecommendedWatcher,
+    flush_task: JoinHandle<()>,
+}
+
+/// An indexed cache: the row key and the directories it measures. Rows for
+/// caches spread over several directories store them one per line.
+#[derive(Debug, Clone)]
+struct TrackedCache {
+    row: String,
+    dirs: Vec<PathBuf>,
+}
+
+impl TrackedCache {
+    fn from_row(row: String) -> Self {
+        let dirs = row.lines().map(PathBuf::from).collect();
+        Self { row, dirs }
+    }
+}
+
+/// Rows touched by events since the last flush
+#[derive(Debug, Default)]
+struct DirtySet {
+    rows: BTreeSet<String>,
+    first_event: Option<Instant>,
+    last_event: Option<Instant>,
+}
+
+impl DirtySet {
+    /// Mark every tracked row containing `changed`; nested caches all change size
+    fn mark(&mut self, changed: &Path, tracked: &[TrackedCache]) {
+        let mut matched = false;
+        for cache in tracked {
+            if cache.dirs.iter().any(|dir| changed.starts_with(dir)) {
+                self.rows.insert(cache.row.clone());
+                matched = true;
+            }
+        }
+        if matched {
+            let now = Instant::now();
+            self.first_event.get_or_insert(now);
+            self.last_event = Some(now);
+        }
+    }
+
+    /// Dirty rows, once events went quiet or the oldest has waited too long
+    fn take_due(&mut self) -> Vec<String> {
+        let (Some(first), Some(last)) = (self.first_event, self.last_event) else {
+            return Vec::new();
+        };
+        if last.elapsed() < DEBOUNCE && first.elapsed() < MAX_DELAY {
+            return Vec::new();
+        }
+        self.first_event = None;
+        self.last_event = None;
+        std::mem::take(&mut self.rows).into_iter().collect()
+    }
+}
+
+/// Watch every indexed cache and keep its row up to date. Builds the index
+/// first when it is empty. Calling it while already watching is a no-op.
+pub async fn start_watching() -> Result<()> {
+    if ACTIVE.lock().map_err(|_| anyhow!("Index watcher state is poisoned"))?.is_some() {
+        return Ok(());
+    }
+
+    let mut rows = index::query_index()?;
+    if rows.is_empty() {
+        index::refresh_index().await?;
+        rows = index::query_index()?;
+    }
+    let tracked: Vec<TrackedCache> = rows.into_iter().map(|info| TrackedCache::from_row(info.path)).collect();
+
+    let dirty = Arc::new(Mutex::new(DirtySet::default()));
+    let watcher = watch_caches(tracked, dirty.clone())?;
+    let flush_task = tokio::spawn(flush_loop(dirty));
+
+    let mut active = ACTIVE.lock().map_err(|_| anyhow!("Index watcher state is poisoned"))?;
+    if active.is_some() {
+        // Another caller won the race; keep theirs
+        flush_task.abort();
+        return Ok(());
+    }
+    *active = Some(ActiveWatcher {
+        _watcher: watcher,
+        flush_task,
+    });
+    Ok(())
+}
+
+/// Stop watching, returning whether a watcher was running
+pub fn stop_watching() -> bool {
+    let Ok(mut active) = ACTIVE.lock() else {
+        return false;
+    };
+    match active.take() {
+        Some(watcher) => {
+            watcher.flush_task.abort();
+            true
+        }
+        None => false,
+    }
+}
+
+pub fn is_watching() -> bool {
+    ACTIVE.lock().map(|active| active.is_some()).unwrap_or(false)
+}
+
+/// Start a recursive watcher over the tracked directories that exist
+fn watch_caches(tracked: Vec<TrackedCache>, dirty: Arc<Mutex<DirtySet>>) -> Result<RecommendedWatcher> {
+    let dirs: Vec<PathBuf> = tracked
+        .iter()
+        .flat_map(|cache| cache.dirs.iter().cloned())
+        .filter(|dir| dir.is_dir())
+        .collect();
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if event.kind.is_access() {
+            return;
+        }
+        if let Ok(mut dirty) = dirty.lock() {
+            for path in &event.paths {
+                dirty.mark(path, &tracked);
+            }
+        }
+    })?;
+    for dir in &dirs {
+        if let Err(e) = watcher.watch(dir, RecursiveMode::Recursive) {
+            eprintln!("[Rust] index watcher: cannot watch {}: {}", dir.display(), e);
+        }
+    }
+    Ok(watcher)
+}
+
+async fn flush_loop(dirty: Arc<Mutex<DirtySet>>) {
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let due = match dirty.lock() {
+            Ok(mut dirty) => dirty.take_due(),
+            Err(_) => return,
+        };
+        if due.is_empty() {
+            continue;
+        }
+        let result = tokio::task::spawn_blocking(move || {
+            let updates: Vec<(String, Option<(u64, usize)>)> = due
+                .into_iter()
+                .map(|row| {
+                    let measured = measure(&TrackedCache::from_row(row.clone()));
+                    (row, measured)
+                })
+                .collect();
+            index::update_indexed_sizes(&updates)
+        })
+        .await;
+        if let Ok(Err(e)) = result {
+            eprintln!("[Rust] index watcher: failed to update index: {}", e);
+        }
+    }
+}
+
+/// Size and item count over the cache's directories, `None` once none exist
+fn measure(cache: &TrackedCache) -> Option<(u64, usize)> {
+    let existing: Vec<&PathBuf> = cache.dirs.iter().filter(|dir| dir.exists()).collect();
+    if existing.is_empty() {
+        return None;
+    }
+    Some(existing.iter().fold((0, 0), |(size, items), dir| {
+        (
+            size + filesystem::calculate_dir_size_sync(dir).unwrap_or(0),
+            items + filesystem::count_items(dir).unwrap_or(0),
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn tracked(paths: &[&Path]) -> Vec<TrackedCache> {
+        paths
+            .iter()
+            .map(|p| TrackedCache::from_row(p.display().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_mark_finds_containing_rows() {
+        let caches = tracked(&[
+            Path::new("/Users/test/.cache"),
+            Path::new("/Users/test/.cache/pip"),
+            Path::new("/Users/test/.npm"),
+        ]);
+        let mut dirty = DirtySet::default();
+        dirty.mark(Path::new("/Users/test/.cache/pip/http/a/b"), &caches);
+        dirty.mark(Path::new("/Users/test/.npmrc"), &caches);
+
+        let rows: Vec<&str> = dirty.rows.iter().map(String::as_str).collect();
+        assert_eq!(rows, vec!["/Users/test/.cache", "/Users/test/.cache/pip"]);
+        // Still inside the debounce window
+        assert!(dirty.take_due().is_empty());
+    }
+
+    #[test]
+    fn test_multi_directory_rows() {
+        let caches = vec![TrackedCache::from_row("/a/CachedData\n/a/GPUCache".to_string())];
+        let mut dirty = DirtySet::default();
+        dirty.mark(Path::new("/a/GPUCache/data_1"), &caches);
+        assert!(dirty.rows.contains("/a/CachedData\n/a/GPUCache"));
+    }
+
+    #[test]
+    fn test_created_file_marks_cache_dirty() {
+        let root = create_test_dir();
+        let npm = root.path().join("npm");
+        let yarn = root.path().join("yarn");
+        fs::create_dir_all(&npm).unwrap();
+        fs::create_dir_all(&yarn).unwrap();
+        // Watchers report canonical paths, e.g. /private/var rather than /var
+        let npm = npm.canonicalize().unwrap();
+        let yarn = yarn.canonicalize().unwrap();
+
+        let dirty = Arc::new(Mutex::new(DirtySet::default()));
+        let _watcher = watch_caches(tracked(&[&npm, &yarn]), dirty.clone()).unwrap();
+        // FSEvents may drop events for changes made right after the stream starts
+        std::thread::sleep(Duration::from_millis(200));
+        fs::write(npm.join("index-v5"), b"entry").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline && dirty.lock().unwrap().rows.is_empty() {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        let rows = dirty.lock().unwrap().rows.clone();
+        assert_eq!(rows.into_iter().collect::<Vec<_>>(), vec![npm.display().to_string()]);
+    }
+
+    #[test]
+    fn test_measure_sums_existing_dirs() {
+        let root = create_test_dir();
+        fs::create_dir_all(root.path().join("a")).unwrap();
+        fs::write(root.path().join("a/blob"), [0u8; 100]).unwrap();
+        let row = format!("{}\n{}", root.path().join("a").display(), root.path().join("gone").display());
+        assert_eq!(measure(&TrackedCache::from_row(row)), Some((100, 1)));
+        assert_eq!(measure(&TrackedCache::from_row(root.path().join("gone").display().to_string())), None);
+    }
+}
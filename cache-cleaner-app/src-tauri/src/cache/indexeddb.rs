@@ -1,24 +1,41 @@
-use super::{IndexedDbCleanResult, IndexedDbItem};
+use super::{guard, IndexedDbCleanResult, IndexedDbItem};
 use crate::utils::filesystem;
+use crate::utils::home::require_home;
+use crate::utils::permissions;
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+pub(super) const DEFAULT_PROFILE: &str = "Default";
 
 /// Scan Chrome profiles for IndexedDB origins and their sizes.
 /// Chỉ trả về các origin có dung lượng >= `threshold_bytes`.
-pub fn scan_indexed_db(threshold_bytes: u64) -> Result<Vec<IndexedDbItem>> {
-    let base = match dirs::home_dir() {
-        Some(h) => h.join("Library/Application Support/Google/Chrome"),
-        None => return Ok(vec![]),
+///
+/// With `include_active` false, items of the profile a running Chrome has
+/// open are left out so they are not cleared from under the browser.
+pub fn scan_indexed_db(threshold_bytes: u64, include_active: bool) -> Result<Vec<IndexedDbItem>> {
+    let base = match require_home() {
+        Ok(h) => h.join("Library/Application Support/Google/Chrome"),
+        Err(_) => return Ok(vec![]),
     };
-    
+    let chrome_running = permissions::is_chrome_running().unwrap_or(false);
+    Ok(scan_indexed_db_in(&base, threshold_bytes, include_active, chrome_running))
+}
+
+fn scan_indexed_db_in(
+    base: &Path,
+    threshold_bytes: u64,
+    include_active: bool,
+    chrome_running: bool,
+) -> Vec<IndexedDbItem> {
     if !base.exists() {
-        return Ok(vec![]);
+        return vec![];
     }
 
+    let active_profile = last_used_profile(base);
     let mut items = Vec::new();
 
     // Scan Default and Profile N directories
-    if let Ok(entries) = std::fs::read_dir(&base) {
+    if let Ok(entries) = std::fs::read_dir(base) {
         for entry in entries.flatten() {
             let profile_path = entry.path();
             let profile_name = entry.file_name().to_string_lossy().to_string();
@@ -28,6 +45,11 @@ pub fn scan_indexed_db(threshold_bytes: u64) -> Result<Vec<IndexedDbItem>> {
                 continue;
             }
 
+            let is_active_profile = profile_name == active_profile;
+            if is_active_profile && chrome_running && !include_active {
+                continue;
+            }
+
             let indexed_db_dir = profile_path.join("IndexedDB");
             if !indexed_db_dir.exists() {
                 continue;
@@ -50,6 +72,9 @@ pub fn scan_indexed_db(threshold_bytes: u64) -> Result<Vec<IndexedDbItem>> {
                             path: db_path,
                             size,
                             over_threshold: true,
+                            is_default_profile: profile_name == DEFAULT_PROFILE,
+                            is_active_profile,
+                            browser_running: chrome_running,
                         });
                     }
                 }
@@ -59,11 +84,29 @@ pub fn scan_indexed_db(threshold_bytes: u64) -> Result<Vec<IndexedDbItem>> {
 
     // Sort by size descending so the largest consumers appear first.
     items.sort_by(|a, b| b.size.cmp(&a.size));
-    Ok(items)
+    items
+}
+
+/// Profile directory Chrome opens on launch, from `Local State`
+pub(super) fn last_used_profile(base: &Path) -> String {
+    std::fs::read_to_string(base.join("Local State"))
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+        .and_then(|state| {
+            state
+                .get("profile")?
+                .get("last_used")?
+                .as_str()
+                .map(|s| s.to_string())
+        })
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
 }
 
 /// Clean the given IndexedDB folders. Returns how many bytes would / did get freed.
 pub fn clean_indexed_db_items(paths: Vec<String>, dry_run: bool) -> Result<IndexedDbCleanResult> {
+    if !dry_run {
+        guard::ensure_deletion_enabled()?;
+    }
     let mut total_freed = 0u64;
     let mut items_removed = 0usize;
 
@@ -79,7 +122,7 @@ pub fn clean_indexed_db_items(paths: Vec<String>, dry_run: bool) -> Result<Index
 
         if !dry_run {
             // Best-effort removal – ignore individual errors but continue.
-            if let Err(e) = std::fs::remove_dir_all(&path) {
+            if let Err(e) = filesystem::delete_dir_all(&path) {
                 eprintln!(
                     "[Rust] clean_indexed_db_items: failed to remove {}: {}",
                     path.display(),
@@ -96,4 +139,73 @@ pub fn clean_indexed_db_items(paths: Vec<String>, dry_run: bool) -> Result<Index
     })
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
 
+    /// Chrome data dir with one 100-byte origin in each profile
+    fn create_chrome_fixture(profiles: &[&str], last_used: Option<&str>) -> TempDir {
+        let base = create_test_dir();
+        for profile in profiles {
+            let origin = base.path().join(profile).join("IndexedDB/https_example.com_0.indexeddb.leveldb");
+            fs::create_dir_all(&origin).unwrap();
+            fs::write(origin.join("000003.log"), [0u8; 100]).unwrap();
+        }
+        if let Some(last_used) = last_used {
+            let state = format!(r#"{{"profile": {{"last_used": "{}"}}}}"#, last_used);
+            fs::write(base.path().join("Local State"), state).unwrap();
+        }
+        base
+    }
+
+    #[test]
+    fn test_profile_metadata() {
+        let base = create_chrome_fixture(&["Default", "Profile 1"], Some("Profile 1"));
+        let items = scan_indexed_db_in(base.path(), 0, true, false);
+        assert_eq!(items.len(), 2);
+
+        let default = items.iter().find(|i| i.profile == "Default").unwrap();
+        assert!(default.is_default_profile);
+        assert!(!default.is_active_profile);
+
+        let profile1 = items.iter().find(|i| i.profile == "Profile 1").unwrap();
+        assert!(!profile1.is_default_profile);
+        assert!(profile1.is_active_profile);
+    }
+
+    #[test]
+    fn test_excludes_active_profile_when_running() {
+        let base = create_chrome_fixture(&["Default", "Profile 1"], Some("Profile 1"));
+        let items = scan_indexed_db_in(base.path(), 0, false, true);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].profile, "Default");
+        assert!(items[0].browser_running);
+    }
+
+    #[test]
+    fn test_keeps_active_profile_when_not_running() {
+        let base = create_chrome_fixture(&["Default", "Profile 1"], Some("Profile 1"));
+        let items = scan_indexed_db_in(base.path(), 0, false, false);
+        assert_eq!(items.len(), 2);
+    }
+
+    #[test]
+    fn test_active_profile_defaults_to_default() {
+        let base = create_chrome_fixture(&["Default", "Profile 2"], None);
+        let items = scan_indexed_db_in(base.path(), 0, false, true);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].profile, "Profile 2");
+    }
+
+    #[test]
+    fn test_threshold_filters_small_origins() {
+        let base = create_chrome_fixture(&["Default"], None);
+        assert!(scan_indexed_db_in(base.path(), 1024, true, false).is_empty());
+    }
+}
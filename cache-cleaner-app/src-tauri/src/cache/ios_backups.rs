@@ -0,0 +1,315 @@
+//! Per-backup view of `~/Library/Application Support/MobileSync/Backup`.
+//!
+//! Each subfolder is one device backup with an `Info.plist` describing the
+//! device. Backups are listed individually so old or superseded ones can be
+//! removed without touching the current backup of a device still in use.
+
+use super::paths::MacPaths;
+use super::{guard, CacheError, PathResult};
+use crate::utils::filesystem;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Backups not refreshed for this long are flagged as stale
+pub const DEFAULT_STALE_AFTER_DAYS: u64 = 180;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IosBackupEntry {
+    /// Backup folder name, usually the device UDID
+    pub id: String,
+    pub path: String,
+    pub device_name: String,
+    pub product_type: String,
+    /// ISO 8601 date from `Info.plist`
+    pub last_backup_date: Option<String>,
+    pub age_days: Option<u64>,
+    pub size_bytes: u64,
+    /// Older than the stale threshold
+    pub stale: bool,
+    /// A newer backup of the same device exists
+    pub superseded: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IosBackupsCleanResult {
+    pub total_freed_bytes: u64,
+    pub items_removed: usize,
+    pub success: bool,
+    pub message: String,
+    pub path_results: Vec<PathResult>,
+}
+
+/// Device metadata read from a backup's `Info.plist`
+#[derive(Debug, Clone, PartialEq)]
+struct BackupInfo {
+    device_name: String,
+    product_type: String,
+    last_backup_date: Option<String>,
+}
+
+pub async fn scan_ios_backups(stale_after_days: u64) -> Result<Vec<IosBackupEntry>> {
+    let root = MacPaths::ios_backups()?;
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+    tokio::task::spawn_blocking(move || scan_ios_backups_in(&root, now, stale_after_days)).await?
+}
+
+/// Remove only the selected backups, identified by folder name
+pub async fn remove_ios_backups(ids: Vec<String>) -> Result<IosBackupsCleanResult> {
+    guard::ensure_deletion_enabled()?;
+    let root = MacPaths::ios_backups()?;
+    tokio::task::spawn_blocking(move || remove_ios_backups_in(&root, &ids)).await?
+}
+
+fn scan_ios_backups_in(root: &Path, now_secs: u64, stale_after_days: u64) -> Result<Vec<IosBackupEntry>> {
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for dir in std::fs::read_dir(root)?.flatten() {
+        let path = dir.path();
+        if !path.is_dir() {
+            continue;
+        }
+        // Folders without Info.plist are not complete backups
+        let Ok(plist) = std::fs::read_to_string(path.join("Info.plist")) else {
+            continue;
+        };
+        let info = parse_info_plist(&plist);
+        let age_days = info
+            .last_backup_date
+            .as_deref()
+            .and_then(parse_iso8601)
+            .map(|secs| now_secs.saturating_sub(secs) / SECS_PER_DAY);
+
+        entries.push(IosBackupEntry {
+            id: dir.file_name().to_string_lossy().to_string(),
+            path: path.display().to_string(),
+            device_name: info.device_name,
+            product_type: info.product_type,
+            last_backup_date: info.last_backup_date,
+            age_days,
+            size_bytes: filesystem::calculate_dir_size_sync(&path).unwrap_or(0),
+            stale: age_days.is_some_and(|days| days > stale_after_days),
+            superseded: false,
+        });
+    }
+
+    mark_superseded(&mut entries);
+    entries.sort_by_key(|backup| Reverse(backup.size_bytes));
+    Ok(entries)
+}
+
+/// Flag every backup except the newest one per device
+fn mark_superseded(entries: &mut [IosBackupEntry]) {
+    for i in 0..entries.len() {
+        let newest_age = entries
+            .iter()
+            .filter(|e| e.device_name == entries[i].device_name && e.product_type == entries[i].product_type)
+            .filter_map(|e| e.age_days)
+            .min();
+        entries[i].superseded = matches!(
+            (entries[i].age_days, newest_age),
+            (Some(age), Some(newest)) if age > newest
+        );
+    }
+}
+
+fn remove_ios_backups_in(root: &Path, ids: &[String]) -> Result<IosBackupsCleanResult> {
+    let mut total_freed = 0u64;
+    let mut items_removed = 0usize;
+    let mut path_results = Vec::new();
+
+    for id in ids {
+        // Ids are folder names, never paths
+        if id.is_empty() || id.contains('/') || id == "." || id == ".." {
+            path_results.push(PathResult::failed(id, format!("Invalid backup id: {}", id)));
+            continue;
+        }
+        let path: PathBuf = root.join(id);
+        let path_str = path.display().to_string();
+        if !path.is_dir() {
+            path_results.push(PathResult::failed(&path_str, "Backup not found".to_string()));
+            continue;
+        }
+        if let Err(e) = guard::assert_safe_to_delete(&path) {
+            path_results.push(PathResult::failed(&path_str, e.to_string()));
+            continue;
+        }
+
+        let size = filesystem::calculate_dir_size_sync(&path).unwrap_or(0);
+        match filesystem::delete_dir_all(&path) {
+            Ok(()) => {
+                total_freed += size;
+                items_removed += 1;
+                path_results.push(PathResult::removed(&path_str, size));
+            }
+            Err(e) => path_results.push(PathResult::from_error(&path_str, &CacheError::from_io(&path, e))),
+        }
+    }
+
+    let errors: Vec<String> = path_results.iter().filter_map(|r| r.error.clone()).collect();
+    let message = if errors.is_empty() {
+        format!("Removed {} iOS backup(s)", items_removed)
+    } else {
+        format!(
+            "Removed {} iOS backup(s), {} errors: {}",
+            items_removed,
+            errors.len(),
+            errors.join("; ")
+        )
+    };
+
+    Ok(IosBackupsCleanResult {
+        total_freed_bytes: total_freed,
+        items_removed,
+        success: errors.is_empty(),
+        message,
+        path_results,
+    })
+}
+
+fn parse_info_plist(plist: &str) -> BackupInfo {
+    BackupInfo {
+        device_name: plist_value(plist, "Device Name", "string").unwrap_or_else(|| "Unknown device".to_string()),
+        product_type: plist_value(plist, "Product Type", "string").unwrap_or_default(),
+        last_backup_date: plist_value(plist, "Last Backup Date", "date"),
+    }
+}
+
+/// Value of `<key>name</key><tag>…</tag>` in an XML plist
+pub(super) fn plist_value(plist: &str, key: &str, tag: &str) -> Option<String> {
+    let key_tag = format!("<key>{}</key>", key);
+    let after_key = &plist[plist.find(&key_tag)? + key_tag.len()..];
+    let value = after_key.trim_start().strip_prefix(&format!("<{}>", tag))?;
+    let end = value.find(&format!("</{}>", tag))?;
+    Some(value[..end].trim().to_string())
+}
+
+/// Seconds since the epoch for a plist date such as `2024-01-15T10:30:00Z`
+pub(super) fn parse_iso8601(date: &str) -> Option<u64> {
+    let date = date.strip_suffix('Z')?;
+    let (day, time) = date.split_once('T')?;
+    let mut ymd = day.split('-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (ymd.next()??, ymd.next()??, ymd.next()??);
+    let mut hms = time.split(':').map(|p| p.parse::<i64>().ok());
+    let (hour, minute, second) = (hms.next()??, hms.next()??, hms.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Days from civil date, proleptic Gregorian calendar
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    u64::try_from(secs).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const NOW: u64 = 1_720_000_000; // 2024-07-03
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn info_plist(device_name: &str, product_type: &str, date: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Build Version</key>
+	<string>21F90</string>
+	<key>Device Name</key>
+	<string>{}</string>
+	<key>Last Backup Date</key>
+	<date>{}</date>
+	<key>Product Type</key>
+	<string>{}</string>
+</dict>
+</plist>
+"#,
+            device_name, date, product_type
+        )
+    }
+
+    fn create_backup(root: &Path, id: &str, device_name: &str, date: &str) {
+        let dir = root.join(id);
+        fs::create_dir_all(dir.join("00")).unwrap();
+        fs::write(dir.join("Info.plist"), info_plist(device_name, "iPhone15,2", date)).unwrap();
+        fs::write(dir.join("00/file"), [0u8; 100]).unwrap();
+    }
+
+    #[test]
+    fn test_parse_info_plist() {
+        let info = parse_info_plist(&info_plist("Alex's iPhone", "iPhone15,2", "2024-01-15T10:30:00Z"));
+        assert_eq!(info.device_name, "Alex's iPhone");
+        assert_eq!(info.product_type, "iPhone15,2");
+        assert_eq!(info.last_backup_date.as_deref(), Some("2024-01-15T10:30:00Z"));
+    }
+
+    #[test]
+    fn test_parse_iso8601() {
+        assert_eq!(parse_iso8601("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_iso8601("2024-01-15T10:30:00Z"), Some(1_705_314_600));
+        assert_eq!(parse_iso8601("2024-13-01T00:00:00Z"), None);
+        assert_eq!(parse_iso8601("not a date"), None);
+    }
+
+    #[test]
+    fn test_scan_flags_stale_and_superseded() {
+        let root = create_test_dir();
+        create_backup(root.path(), "old-udid", "Work iPhone", "2023-01-01T00:00:00Z");
+        create_backup(root.path(), "new-udid", "Work iPhone", "2024-07-01T00:00:00Z");
+        create_backup(root.path(), "ipad-udid", "iPad", "2024-06-30T00:00:00Z");
+        fs::create_dir(root.path().join("incomplete")).unwrap();
+
+        let entries = scan_ios_backups_in(root.path(), NOW, DEFAULT_STALE_AFTER_DAYS).unwrap();
+        assert_eq!(entries.len(), 3);
+
+        let find = |id: &str| entries.iter().find(|e| e.id == id).unwrap();
+        assert!(find("old-udid").stale);
+        assert!(find("old-udid").superseded);
+        assert!(!find("new-udid").stale);
+        assert!(!find("new-udid").superseded);
+        assert!(!find("ipad-udid").superseded);
+        assert_eq!(find("new-udid").age_days, Some(2));
+    }
+
+    #[test]
+    fn test_remove_only_selected_backups() {
+        let root = create_test_dir();
+        create_backup(root.path(), "old-udid", "Work iPhone", "2023-01-01T00:00:00Z");
+        create_backup(root.path(), "new-udid", "Work iPhone", "2024-07-01T00:00:00Z");
+
+        let result = remove_ios_backups_in(root.path(), &["old-udid".to_string()]).unwrap();
+        assert!(result.success);
+        assert_eq!(result.items_removed, 1);
+        assert!(!root.path().join("old-udid").exists());
+        assert!(root.path().join("new-udid").exists());
+    }
+
+    #[test]
+    fn test_remove_rejects_path_ids() {
+        let root = create_test_dir();
+        let result = remove_ios_backups_in(root.path(), &["../escape".to_string(), "..".to_string()]).unwrap();
+        assert!(!result.success);
+        assert_eq!(result.items_removed, 0);
+    }
+}
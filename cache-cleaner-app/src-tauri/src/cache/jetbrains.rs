@@ -0,0 +1,298 @@
+//! JetBrains IDE caches and logs.
+//!
+//! Every IntelliJ-based IDE keeps its caches in
+//! `~/Library/Caches/JetBrains/<Product><version>` and its logs in
+//! `~/Library/Logs/JetBrains/<Product><version>`, and never removes the
+//! folders of a version it has upgraded from. Those older versions are
+//! preselected; the current version's folders are rebuilt on the next start
+//! but cost a full reindex, so they are left for the user to pick.
+
+use super::cold_artifacts::LargeCacheEntry;
+use super::paths::MacPaths;
+use super::smart_suggestions::CleanupConfidence;
+use super::{guard, CacheError, PathResult};
+use crate::utils::filesystem;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Roots holding one folder per product version, relative to the home directory
+const JETBRAINS_ROOTS: &[(&str, &str)] = &[
+    ("caches", "Library/Caches/JetBrains"),
+    ("logs", "Library/Logs/JetBrains"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JetBrainsCleanResult {
+    pub total_freed_bytes: u64,
+    pub items_removed: usize,
+    pub success: bool,
+    pub message: String,
+    pub dry_run: bool,
+    pub path_results: Vec<PathResult>,
+}
+
+/// A `<Product><version>` folder, e.g. `WebStorm2024.1`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProductDir {
+    product: String,
+    version: Vec<u32>,
+}
+
+/// Split `IntelliJIdea2023.3` into `IntelliJIdea` and `[2023, 3]`; folders
+/// without a trailing version (e.g. `Toolbox`) are not per-version caches
+fn parse_product_dir(name: &str) -> Option<ProductDir> {
+    let start = name.find(|c: char| c.is_ascii_digit())?;
+    let (product, version) = name.split_at(start);
+    if product.is_empty() {
+        return None;
+    }
+    let version = version
+        .split('.')
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<u32>>>()?;
+    Some(ProductDir {
+        product: product.to_string(),
+        version,
+    })
+}
+
+/// Per-version cache and log folders, largest first
+pub async fn scan_jetbrains_caches() -> Result<Vec<LargeCacheEntry>> {
+    let home = MacPaths::home()?;
+    tokio::task::spawn_blocking(move || Ok(scan_jetbrains_caches_in(&home))).await?
+}
+
+/// Remove the selected version folders; anything outside the JetBrains
+/// caches and logs roots is refused
+pub async fn clean_jetbrains_caches(paths: Vec<String>, dry_run: bool) -> Result<JetBrainsCleanResult> {
+    if !dry_run {
+        guard::ensure_deletion_enabled()?;
+    }
+    let home = MacPaths::home()?;
+    tokio::task::spawn_blocking(move || Ok(clean_jetbrains_caches_in(&home, &paths, dry_run))).await?
+}
+
+fn scan_jetbrains_caches_in(home: &Path) -> Vec<LargeCacheEntry> {
+    let mut found = Vec::new();
+    for (kind, relative) in JETBRAINS_ROOTS {
+        let Ok(entries) = std::fs::read_dir(home.join(relative)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(dir) = parse_product_dir(&name) {
+                found.push((*kind, name, dir, path));
+            }
+        }
+    }
+
+    // Newest version of each product across both roots, so an old log
+    // folder is flagged even when its caches are already gone
+    let mut newest: HashMap<&str, &[u32]> = HashMap::new();
+    for (_, _, dir, _) in &found {
+        let current = newest.entry(dir.product.as_str()).or_insert(dir.version.as_slice());
+        if dir.version.as_slice() > *current {
+            *current = dir.version.as_slice();
+        }
+    }
+
+    let mut entries: Vec<LargeCacheEntry> = found
+        .iter()
+        .map(|(kind, name, dir, path)| {
+            let outdated = newest.get(dir.product.as_str()).is_some_and(|v| dir.version.as_slice() < *v);
+            LargeCacheEntry {
+                name: format!("{} {}", name, kind),
+                path: path.display().to_string(),
+                size_bytes: filesystem::calculate_dir_size_sync(path).unwrap_or(0),
+                last_used_days: None,
+                confidence: if outdated { CleanupConfidence::Safe } else { CleanupConfidence::Caution },
+                auto_select: outdated,
+            }
+        })
+        .collect();
+    entries.sort_by_key(|entry| Reverse(entry.size_bytes));
+    entries
+}
+
+/// Strictly below one of the roots, without `..` tricks
+fn is_under_jetbrains_root(home: &Path, path: &Path) -> bool {
+    if path.components().any(|c| matches!(c, Component::ParentDir)) {
+        return false;
+    }
+    JETBRAINS_ROOTS.iter().any(|(_, relative)| {
+        let root = home.join(relative);
+        path.starts_with(&root) && path != root
+    })
+}
+
+fn clean_jetbrains_caches_in(home: &Path, paths: &[String], dry_run: bool) -> JetBrainsCleanResult {
+    let mut total_freed = 0u64;
+    let mut items_removed = 0usize;
+    let mut path_results = Vec::new();
+
+    for path_str in paths {
+        let path = PathBuf::from(path_str);
+        if !is_under_jetbrains_root(home, &path) {
+            path_results.push(PathResult::failed(
+                path_str,
+                "Not inside the JetBrains caches or logs folder".to_string(),
+            ));
+            continue;
+        }
+        if !path.is_dir() {
+            path_results.push(PathResult::failed(path_str, "Cache not found".to_string()));
+            continue;
+        }
+        if let Err(e) = guard::assert_safe_to_delete(&path) {
+            path_results.push(PathResult::failed(path_str, e.to_string()));
+            continue;
+        }
+
+        let size = filesystem::calculate_dir_size_sync(&path).unwrap_or(0);
+        if dry_run || filesystem::skip_deletion() {
+            total_freed += size;
+            items_removed += 1;
+            path_results.push(PathResult::removed(path_str, size));
+            continue;
+        }
+        match filesystem::delete_dir_all(&path) {
+            Ok(()) => {
+                total_freed += size;
+                items_removed += 1;
+                path_results.push(PathResult::removed(path_str, size));
+            }
+            Err(e) => path_results.push(PathResult::from_error(path_str, &CacheError::from_io(&path, e))),
+        }
+    }
+
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    let errors: Vec<String> = path_results.iter().filter_map(|r| r.error.clone()).collect();
+    let message = if errors.is_empty() {
+        format!("{} {} JetBrains folder(s)", verb, items_removed)
+    } else {
+        format!(
+            "{} {} JetBrains folder(s), {} errors: {}",
+            verb,
+            items_removed,
+            errors.len(),
+            errors.join("; ")
+        )
+    };
+
+    JetBrainsCleanResult {
+        total_freed_bytes: total_freed,
+        items_removed,
+        success: errors.is_empty(),
+        message,
+        dry_run,
+        path_results,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn create_folder(dir: &Path, bytes: usize) -> String {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("blob"), vec![0u8; bytes]).unwrap();
+        dir.display().to_string()
+    }
+
+    /// WebStorm 2023.3 (caches and logs) upgraded to 2024.1, plus one PyCharm
+    fn create_home(home: &Path) {
+        create_folder(&home.join("Library/Caches/JetBrains/WebStorm2023.3"), 300);
+        create_folder(&home.join("Library/Caches/JetBrains/WebStorm2024.1"), 200);
+        create_folder(&home.join("Library/Logs/JetBrains/WebStorm2023.3"), 50);
+        create_folder(&home.join("Library/Caches/JetBrains/PyCharmCE2023.2"), 100);
+        create_folder(&home.join("Library/Caches/JetBrains/Toolbox"), 10);
+    }
+
+    #[test]
+    fn test_parse_product_dir() {
+        assert_eq!(
+            parse_product_dir("IntelliJIdea2023.3"),
+            Some(ProductDir {
+                product: "IntelliJIdea".to_string(),
+                version: vec![2023, 3]
+            })
+        );
+        assert_eq!(parse_product_dir("PyCharmCE2024.1").unwrap().product, "PyCharmCE");
+        assert_eq!(parse_product_dir("Toolbox"), None);
+        assert_eq!(parse_product_dir("2024.1"), None);
+        assert_eq!(parse_product_dir("WebStorm2024.1-backup"), None);
+    }
+
+    #[test]
+    fn test_older_version_flagged() {
+        let home = create_test_dir();
+        create_home(home.path());
+
+        let entries = scan_jetbrains_caches_in(home.path());
+        assert_eq!(entries.len(), 4);
+        let entry = |name: &str| entries.iter().find(|e| e.name == name).unwrap();
+
+        let old = entry("WebStorm2023.3 caches");
+        assert_eq!(old.size_bytes, 300);
+        assert!(old.auto_select);
+        assert_eq!(old.confidence, CleanupConfidence::Safe);
+        assert!(entry("WebStorm2023.3 logs").auto_select);
+
+        let current = entry("WebStorm2024.1 caches");
+        assert!(!current.auto_select);
+        assert_eq!(current.confidence, CleanupConfidence::Caution);
+        // The only version of a product is the current one
+        assert!(!entry("PyCharmCE2023.2 caches").auto_select);
+    }
+
+    #[test]
+    fn test_clean_refuses_paths_outside_roots() {
+        let home = create_test_dir();
+        create_home(home.path());
+        let outside = create_folder(&home.path().join("Library/Caches/Other"), 10);
+        let root = home.path().join("Library/Caches/JetBrains").display().to_string();
+        let escaping = home
+            .path()
+            .join("Library/Caches/JetBrains/../Other")
+            .display()
+            .to_string();
+
+        let result = clean_jetbrains_caches_in(home.path(), &[outside.clone(), root.clone(), escaping], false);
+        assert!(!result.success);
+        assert_eq!(result.items_removed, 0);
+        assert!(Path::new(&outside).exists());
+        assert!(Path::new(&root).join("WebStorm2024.1").exists());
+    }
+
+    #[test]
+    fn test_clean_removes_selected_versions() {
+        let home = create_test_dir();
+        create_home(home.path());
+        let old = home.path().join("Library/Caches/JetBrains/WebStorm2023.3");
+        let paths = vec![old.display().to_string()];
+
+        let preview = clean_jetbrains_caches_in(home.path(), &paths, true);
+        assert!(preview.dry_run);
+        assert_eq!(preview.total_freed_bytes, 300);
+        assert!(old.exists());
+
+        let result = clean_jetbrains_caches_in(home.path(), &paths, false);
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.total_freed_bytes, 300);
+        assert!(!old.exists());
+        assert!(home.path().join("Library/Caches/JetBrains/WebStorm2024.1").exists());
+    }
+}
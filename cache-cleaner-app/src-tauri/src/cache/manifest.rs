@@ -0,0 +1,303 @@
+//! Cache definitions loaded from a TOML manifest.
+//!
+//! The bundled `cache_manifest.toml` is merged with an optional user override
+//! at `~/.cache-cleaner/cache_manifest.toml`. Each entry becomes a
+//! `CacheType::Manifest(id)` that can be scanned and cleaned like a native type.
+
+use super::{guard, CacheCategory, CacheInfo, CacheType, CleanResult};
+use crate::utils::command::split_command_line;
+use crate::utils::filesystem;
+use crate::utils::format::format_size;
+use crate::utils::home::{app_data_dir, require_home};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+const BUNDLED_MANIFEST: &str = include_str!("cache_manifest.toml");
+
+static MANIFEST: OnceLock<Vec<ManifestEntry>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub id: String,
+    pub display_name: String,
+    pub path_template: String,
+    #[serde(default)]
+    pub owning_process: Option<String>,
+    #[serde(default)]
+    pub cleaner_command: Option<String>,
+    /// Category for grouped scans and cleans, `System` when unset
+    #[serde(default)]
+    pub category: Option<CacheCategory>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CacheManifest {
+    #[serde(default, rename = "cache")]
+    caches: Vec<ManifestEntry>,
+}
+
+impl ManifestEntry {
+    /// Cache directory with `~/` expanded
+    pub fn resolved_path(&self) -> Result<PathBuf> {
+        if self.path_template.starts_with("~/") {
+            let home = require_home()?;
+            Ok(home.join(&self.path_template[2..]))
+        } else {
+            Ok(PathBuf::from(&self.path_template))
+        }
+    }
+}
+
+/// Parse manifest TOML into its entries
+pub fn parse_manifest(content: &str) -> Result<Vec<ManifestEntry>> {
+    let manifest: CacheManifest = toml::from_str(content)?;
+    for entry in &manifest.caches {
+        if entry.id.trim().is_empty() || entry.path_template.trim().is_empty() {
+            return Err(anyhow!("Manifest entry with empty id or path_template"));
+        }
+    }
+    Ok(manifest.caches)
+}
+
+/// All manifest entries, loaded once per process
+pub fn manifest_entries() -> &'static [ManifestEntry] {
+    MANIFEST.get_or_init(load_manifest)
+}
+
+pub fn find_entry(id: &str) -> Option<&'static ManifestEntry> {
+    manifest_entries().iter().find(|e| e.id.eq_ignore_ascii_case(id))
+}
+
+fn load_manifest() -> Vec<ManifestEntry> {
+    let mut entries = parse_manifest(BUNDLED_MANIFEST).unwrap_or_else(|e| {
+        eprintln!("[Rust] Invalid bundled cache manifest: {}", e);
+        Vec::new()
+    });
+
+    let override_path = app_data_dir().ok().map(|dir| dir.join("cache_manifest.toml"));
+    if let Some(path) = override_path.filter(|p| p.exists()) {
+        match std::fs::read_to_string(&path).map_err(anyhow::Error::from).and_then(|c| parse_manifest(&c)) {
+            Ok(user_entries) => merge_entries(&mut entries, user_entries),
+            Err(e) => eprintln!("[Rust] Ignoring invalid cache manifest {}: {}", path.display(), e),
+        }
+    }
+
+    entries
+}
+
+/// User entries replace bundled ones with the same id
+fn merge_entries(entries: &mut Vec<ManifestEntry>, overrides: Vec<ManifestEntry>) {
+    for entry in overrides {
+        entries.retain(|e| e.id != entry.id);
+        entries.push(entry);
+    }
+}
+
+pub async fn scan_entry(entry: &ManifestEntry) -> Result<CacheInfo> {
+    let path = entry.resolved_path()?;
+    let exists = path.exists();
+    let (size, item_count) = if exists {
+        let size = filesystem::calculate_dir_size(&path).await?;
+        let count = filesystem::count_items(&path)?;
+        (size, count)
+    } else {
+        (0, 0)
+    };
+
+    Ok(CacheInfo {
+        cache_type: CacheType::Manifest(entry.id.clone()),
+        path: path.display().to_string(),
+        size,
+        exists,
+        item_count,
+        size_margin: None,
+        note: None,
+    })
+}
+
+pub async fn clean_entry(entry: &ManifestEntry, dry_run: bool) -> Result<CleanResult> {
+    let cache_type = CacheType::Manifest(entry.id.clone());
+    let path = entry.resolved_path()?;
+
+    if !path.exists() {
+        return Ok(CleanResult {
+            cache_type,
+            freed_bytes: 0,
+            items_removed: 0,
+            success: true,
+            message: "Cache directory does not exist".to_string(),
+            dry_run,
+            ..Default::default()
+        });
+    }
+
+    let size_before = filesystem::calculate_dir_size(&path).await?;
+    let item_count = filesystem::count_items(&path)?;
+
+    // Read-only mode reports the same estimate, since neither a cleanup
+    // command nor a skipped removal would free anything to measure
+    if dry_run || filesystem::skip_deletion() {
+        return Ok(CleanResult {
+            cache_type,
+            freed_bytes: size_before,
+            items_removed: item_count,
+            success: true,
+            message: format!("Would free {} bytes ({} items)", size_before, item_count),
+            dry_run,
+            ..Default::default()
+        });
+    }
+
+    if let Some(process) = &entry.owning_process {
+        if is_process_running(process) {
+            return Ok(CleanResult {
+                cache_type,
+                freed_bytes: 0,
+                items_removed: 0,
+                success: false,
+                message: format!("{} is running, close it before cleaning", process),
+                dry_run,
+                ..Default::default()
+            });
+        }
+    }
+
+    if let Some(command) = &entry.cleaner_command {
+        run_cleaner_command(command)?;
+    } else {
+        guard::assert_safe_to_delete(&path)?;
+        filesystem::remove_dir_contents(&path)?;
+    }
+
+    let size_after = filesystem::calculate_dir_size(&path).await.unwrap_or(0);
+    let freed_bytes = size_before.saturating_sub(size_after);
+
+    Ok(CleanResult {
+        cache_type,
+        freed_bytes,
+        items_removed: item_count,
+        success: true,
+        message: format!("Freed {}", format_size(freed_bytes)),
+        dry_run: false,
+        ..Default::default()
+    })
+}
+
+/// Run a cleaner command directly, without a shell
+pub fn run_cleaner_command(command: &str) -> Result<()> {
+    guard::ensure_commands_enabled()?;
+    let argv = split_command_line(command)?;
+    let output = Command::new(&argv[0]).args(&argv[1..]).output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "`{}` failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn is_process_running(name: &str) -> bool {
+    Command::new("pgrep")
+        .args(["-x", name])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheError;
+
+    const SAMPLE: &str = r#"
+[[cache]]
+id = "homebrew"
+display_name = "Homebrew Cache"
+path_template = "~/Library/Caches/Homebrew"
+owning_process = "brew"
+cleaner_command = "brew cleanup --prune=all"
+
+[[cache]]
+id = "custom"
+display_name = "Custom"
+path_template = "/tmp/custom-cache"
+"#;
+
+    #[test]
+    fn test_parse_sample_manifest() {
+        let entries = parse_manifest(SAMPLE).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "homebrew");
+        assert_eq!(entries[0].owning_process.as_deref(), Some("brew"));
+        assert_eq!(entries[0].cleaner_command.as_deref(), Some("brew cleanup --prune=all"));
+        assert!(entries[1].owning_process.is_none());
+        assert!(entries[1].cleaner_command.is_none());
+    }
+
+    #[test]
+    fn test_parse_rejects_empty_id() {
+        let content = "[[cache]]\nid = \"\"\ndisplay_name = \"x\"\npath_template = \"~/x\"\n";
+        assert!(parse_manifest(content).is_err());
+    }
+
+    #[test]
+    fn test_bundled_manifest_is_valid() {
+        let entries = parse_manifest(BUNDLED_MANIFEST).unwrap();
+        assert!(entries.iter().any(|e| e.id == "homebrew"));
+    }
+
+    #[test]
+    fn test_resolved_path_expands_home() {
+        let entries = parse_manifest(SAMPLE).unwrap();
+        let path = entries[0].resolved_path().unwrap();
+        assert!(path.is_absolute());
+        assert!(path.ends_with("Library/Caches/Homebrew"));
+        assert_eq!(entries[1].resolved_path().unwrap(), PathBuf::from("/tmp/custom-cache"));
+    }
+
+    #[test]
+    fn test_merge_overrides_by_id() {
+        let mut entries = parse_manifest(SAMPLE).unwrap();
+        let overrides = parse_manifest(
+            "[[cache]]\nid = \"homebrew\"\ndisplay_name = \"Brew\"\npath_template = \"/opt/brew-cache\"\n",
+        )
+        .unwrap();
+        merge_entries(&mut entries, overrides);
+        assert_eq!(entries.len(), 2);
+        let brew = entries.iter().find(|e| e.id == "homebrew").unwrap();
+        assert_eq!(brew.path_template, "/opt/brew-cache");
+    }
+
+    #[test]
+    fn test_find_entry_case_insensitive() {
+        assert!(find_entry("homebrew").is_some());
+        assert!(find_entry("HomeBrew").is_some());
+        assert!(find_entry("not-in-manifest").is_none());
+    }
+
+    #[test]
+    fn test_run_cleaner_command_empty() {
+        assert!(run_cleaner_command("   ").is_err());
+    }
+
+    #[test]
+    fn test_run_cleaner_command_refused_read_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("ran");
+        let command = format!("touch {}", marker.display());
+
+        guard::set_read_only_for_thread(true);
+        let result = run_cleaner_command(&command);
+        guard::set_read_only_for_thread(false);
+
+        let error = result.unwrap_err();
+        assert!(matches!(error.downcast_ref::<CacheError>(), Some(CacheError::DeletionDisabled)));
+        assert!(!marker.exists());
+    }
+}
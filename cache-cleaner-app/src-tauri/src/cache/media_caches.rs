@@ -0,0 +1,279 @@
+//! Messages and Photos caches.
+//!
+//! Photos and its analysis daemon keep large regenerable caches, and Photos
+//! libraries hold derivatives (thumbnails, previews) inside the
+//! `.photoslibrary` bundle. Everything is reported so the user can see where
+//! the space goes, but only the standalone cache folders can be cleaned.
+//! Nothing inside a `.photoslibrary` bundle is ever selected or removed:
+//! Photos expects to manage that bundle itself. Messages attachments are
+//! conversation history, not cache, and are reported as risky only.
+
+use super::cold_artifacts::LargeCacheEntry;
+use super::paths::MacPaths;
+use super::smart_suggestions::CleanupConfidence;
+use super::{guard, PathResult};
+use crate::utils::filesystem;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+/// Regenerable cache folders, relative to the home directory
+const CLEANABLE_CACHES: &[(&str, &str)] = &[
+    ("Photos cache", "Library/Caches/com.apple.Photos"),
+    ("Messages cache", "Library/Caches/com.apple.MobileSMS"),
+    (
+        "Photos analysis cache",
+        "Library/Containers/com.apple.photoanalysisd/Data/Library/Caches",
+    ),
+    (
+        "Media analysis cache",
+        "Library/Containers/com.apple.mediaanalysisd/Data/Library/Caches",
+    ),
+];
+
+/// Reported but never cleaned: Messages attachments are user data
+const MESSAGES_ATTACHMENTS: &str = "Library/Messages/Attachments";
+
+/// Regenerable folders inside a `.photoslibrary` bundle, reported only
+const PHOTOS_LIBRARY_CACHES: &[(&str, &str)] = &[
+    ("derivatives", "resources/derivatives"),
+    ("caches", "resources/caches"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaCachesCleanResult {
+    pub total_freed_bytes: u64,
+    pub items_removed: usize,
+    pub success: bool,
+    pub message: String,
+    pub path_results: Vec<PathResult>,
+}
+
+/// Media app caches, largest first
+pub async fn scan_media_app_caches() -> Result<Vec<LargeCacheEntry>> {
+    let home = MacPaths::home()?;
+    tokio::task::spawn_blocking(move || Ok(scan_media_app_caches_in(&home))).await?
+}
+
+/// Empty the selected caches; only the standalone cache folders are accepted
+pub async fn clean_media_app_caches(paths: Vec<String>) -> Result<MediaCachesCleanResult> {
+    guard::ensure_deletion_enabled()?;
+    let home = MacPaths::home()?;
+    tokio::task::spawn_blocking(move || Ok(clean_media_app_caches_in(&home, &paths))).await?
+}
+
+fn scan_media_app_caches_in(home: &Path) -> Vec<LargeCacheEntry> {
+    let mut entries = Vec::new();
+    for (name, relative) in CLEANABLE_CACHES {
+        push_if_dir(&mut entries, name, home.join(relative), CleanupConfidence::Caution, true);
+    }
+    push_if_dir(
+        &mut entries,
+        "Messages attachments",
+        home.join(MESSAGES_ATTACHMENTS),
+        CleanupConfidence::Risky,
+        false,
+    );
+    for library in photos_libraries(home) {
+        let library_name = library
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        for (name, relative) in PHOTOS_LIBRARY_CACHES {
+            let label = format!("{} ({})", library_name, name);
+            push_if_dir(&mut entries, &label, library.join(relative), CleanupConfidence::Risky, false);
+        }
+    }
+
+    entries.sort_by_key(|entry| Reverse(entry.size_bytes));
+    entries
+}
+
+fn push_if_dir(
+    entries: &mut Vec<LargeCacheEntry>,
+    name: &str,
+    path: PathBuf,
+    confidence: CleanupConfidence,
+    auto_select: bool,
+) {
+    if !path.is_dir() {
+        return;
+    }
+    entries.push(LargeCacheEntry {
+        name: name.to_string(),
+        size_bytes: filesystem::calculate_dir_size_sync(&path).unwrap_or(0),
+        path: path.display().to_string(),
+        last_used_days: None,
+        // A bundle's contents are never preselected, whatever the caller asked
+        auto_select: auto_select && !in_photos_library(&path),
+        confidence,
+    });
+}
+
+/// `.photoslibrary` bundles directly in `~/Pictures`
+fn photos_libraries(home: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(home.join("Pictures")) else {
+        return Vec::new();
+    };
+    let mut libraries: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && in_photos_library(path))
+        .collect();
+    libraries.sort();
+    libraries
+}
+
+fn in_photos_library(path: &Path) -> bool {
+    path.components().any(|c| {
+        Path::new(c.as_os_str())
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("photoslibrary"))
+    })
+}
+
+fn clean_media_app_caches_in(home: &Path, paths: &[String]) -> MediaCachesCleanResult {
+    let cleanable: Vec<PathBuf> = CLEANABLE_CACHES.iter().map(|(_, relative)| home.join(relative)).collect();
+
+    let mut total_freed = 0u64;
+    let mut items_removed = 0usize;
+    let mut path_results = Vec::new();
+
+    for path_str in paths {
+        let path = PathBuf::from(path_str);
+        if in_photos_library(&path) {
+            path_results.push(PathResult::failed(
+                path_str,
+                "Photos library contents are managed by Photos and never cleaned".to_string(),
+            ));
+            continue;
+        }
+        // Exact cache roots only, so nothing else under the home folder can slip in
+        if !cleanable.contains(&path) {
+            path_results.push(PathResult::failed(path_str, "Not a cleanable media cache".to_string()));
+            continue;
+        }
+        if !path.is_dir() {
+            path_results.push(PathResult::failed(path_str, "Cache not found".to_string()));
+            continue;
+        }
+        if let Err(e) = guard::assert_safe_to_delete(&path) {
+            path_results.push(PathResult::failed(path_str, e.to_string()));
+            continue;
+        }
+
+        let size = filesystem::calculate_dir_size_sync(&path).unwrap_or(0);
+        let items = filesystem::count_items(&path).unwrap_or(0);
+        let outcome = filesystem::empty_dir_measured(&path, size, items, None);
+        total_freed += outcome.freed_bytes;
+        items_removed += outcome.items_removed;
+        if outcome.errors.is_empty() {
+            path_results.push(PathResult::removed(path_str, outcome.freed_bytes));
+        } else {
+            path_results.push(PathResult::failed(path_str, outcome.errors.join("; ")));
+        }
+    }
+
+    let errors: Vec<String> = path_results.iter().filter_map(|r| r.error.clone()).collect();
+    let message = if errors.is_empty() {
+        format!("Removed {} item(s) from media caches", items_removed)
+    } else {
+        format!(
+            "Removed {} item(s) from media caches, {} errors: {}",
+            items_removed,
+            errors.len(),
+            errors.join("; ")
+        )
+    };
+
+    MediaCachesCleanResult {
+        total_freed_bytes: total_freed,
+        items_removed,
+        success: errors.is_empty(),
+        message,
+        path_results,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn create_cache(dir: &Path, bytes: usize) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("blob"), vec![0u8; bytes]).unwrap();
+    }
+
+    /// Photos cache, Messages attachments and a library with derivatives
+    fn create_home(home: &Path) -> PathBuf {
+        create_cache(&home.join("Library/Caches/com.apple.Photos"), 100);
+        create_cache(&home.join(MESSAGES_ATTACHMENTS), 300);
+        let library = home.join("Pictures/Photos Library.photoslibrary");
+        create_cache(&library.join("resources/derivatives"), 500);
+        fs::create_dir_all(library.join("originals")).unwrap();
+        library
+    }
+
+    #[test]
+    fn test_photos_library_reported_but_not_selected() {
+        let home = create_test_dir();
+        create_home(home.path());
+
+        let entries = scan_media_app_caches_in(home.path());
+        assert_eq!(entries.len(), 3);
+
+        let derivatives = &entries[0];
+        assert_eq!(derivatives.name, "Photos Library (derivatives)");
+        assert_eq!(derivatives.size_bytes, 500);
+        assert_eq!(derivatives.confidence, CleanupConfidence::Risky);
+        assert!(!derivatives.auto_select);
+
+        let attachments = entries.iter().find(|e| e.name == "Messages attachments").unwrap();
+        assert_eq!(attachments.confidence, CleanupConfidence::Risky);
+        assert!(!attachments.auto_select);
+
+        let photos_cache = entries.iter().find(|e| e.name == "Photos cache").unwrap();
+        assert_eq!(photos_cache.confidence, CleanupConfidence::Caution);
+        assert!(photos_cache.auto_select);
+    }
+
+    #[test]
+    fn test_photos_library_never_cleaned() {
+        let home = create_test_dir();
+        let library = create_home(home.path());
+        let derivatives = library.join("resources/derivatives");
+
+        let result = clean_media_app_caches_in(
+            home.path(),
+            &[
+                derivatives.display().to_string(),
+                library.display().to_string(),
+                home.path().join(MESSAGES_ATTACHMENTS).display().to_string(),
+            ],
+        );
+        assert!(!result.success);
+        assert_eq!(result.items_removed, 0);
+        assert!(derivatives.join("blob").exists());
+        assert!(home.path().join(MESSAGES_ATTACHMENTS).join("blob").exists());
+    }
+
+    #[test]
+    fn test_clean_empties_safe_cache() {
+        let home = create_test_dir();
+        create_home(home.path());
+        let cache = home.path().join("Library/Caches/com.apple.Photos");
+
+        let result = clean_media_app_caches_in(home.path(), &[cache.display().to_string()]);
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.total_freed_bytes, 100);
+        assert!(cache.exists());
+        assert!(!cache.join("blob").exists());
+    }
+}
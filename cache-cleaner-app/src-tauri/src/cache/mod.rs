@@ -20,10 +20,112 @@ pub mod parallel_scanner;
 // Docker cleanup module
 pub mod docker;
 
+// Trash storage for cleaned folders
+pub mod trash;
+
+// Health checks for support diagnostics
+pub mod self_test;
+
+// Deletion safety checks
+pub mod guard;
+
+// Duplicate file report across caches
+pub mod duplicates;
+
+// Cache definitions loaded from cache_manifest.toml
+pub mod manifest;
+
+// Errors with a specific meaning for the UI
+pub mod error;
+pub use error::CacheError;
+
+// Deletion permission checks before a clean
+pub mod preflight;
+
+// Short-lived cache of the last full scan
+pub mod scan_results;
+
+// QuickLook thumbnail cache
+pub mod quicklook;
+
+// Machine-readable suggestion reasons
+pub mod reasons;
+
+// Ordered clean of every safe cache
+pub mod deep_clean;
+
+// Suggestions needed to reach a free-space target
+pub mod cleanup_plan;
+
+// Persistent SQLite index of cache sizes
+pub mod index;
+
+// Filesystem events that keep the index fresh
+pub mod index_watcher;
+
+// Individual iOS device backups
+pub mod ios_backups;
+
+// Serializes destructive operations
+pub mod operation_lock;
+
+// Xcode simulator runtimes via simctl
+pub mod simulator_runtimes;
+
+// git gc and stale worktrees in developer repositories
+pub mod git_maintenance;
+
+// The app's own footprint in ~/.cache-cleaner
+pub mod app_data;
+
+// Unused artifacts inside Gradle, Cargo and CocoaPods caches
+pub mod cold_artifacts;
+
+// Go build and module caches
+pub mod go_caches;
+
+// Poetry and uv caches
+pub mod python_caches;
+
+// conda / mamba package caches
+pub mod conda;
+
+// Append-only record of destructive operations
+pub mod audit;
+
+// Health checks for corrupt or half-written caches
+pub mod diagnosis;
+
+// Individual Xcode archives, kept per app
+pub mod xcode_archives;
+
+// Saved scans for growth comparisons
+pub mod baselines;
+
+// Messages and Photos caches
+pub mod media_caches;
+
+// Chrome caches per profile
+pub mod chrome_profiles;
+
+// Plain-language descriptions of paths for tooltips
+pub mod explain;
+
+// JetBrains IDE caches and logs per product version
+pub mod jetbrains;
+
+// Log files past a given age, across log folders
+pub mod old_logs;
+
+// Cleanup time estimates from past deletion throughput
+pub mod cleanup_eta;
+
+use crate::utils::filesystem::RemovalOutcome;
+use crate::utils::format::format_size;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CacheType {
     Npm,
     Chrome,
@@ -37,9 +139,14 @@ pub enum CacheType {
     Yarn,
     Pnpm,
     Pip,
+    Poetry,
+    Uv,
+    Conda,
     CocoaPods,
     Gradle,
     Cargo,
+    GoBuild,
+    GoModCache,
     XcodeDerivedData,
     XcodeArchives,
     XcodeSimulators,
@@ -47,12 +154,80 @@ pub enum CacheType {
     UserLogs,
     TempFiles,
     IosBackups,
+    QuickLook,
     
     // Docker cache types
     DockerContainers,
     DockerImages,
     DockerVolumes,
     DockerBuildCache,
+
+    // Cache defined in cache_manifest.toml, by id
+    Manifest(String),
+}
+
+/// How a cache type is cleaned, relative to its root directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanStrategy {
+    /// Empty the root directory but keep it
+    RemoveContents,
+    /// Empty only this subdirectory, leaving the rest of the root (e.g. config) alone
+    RemoveSubpath(&'static str),
+    /// Let the owning tool clean up instead of deleting files
+    RunCommand(&'static str),
+}
+
+/// Whether a clean command deletes, passed by the frontend as `"preview"` or
+/// `"commit"`. Anything else, including a missing or mistyped mode, is a
+/// preview: deleting has to be asked for by name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteMode {
+    /// Report what would be removed
+    #[default]
+    Preview,
+    /// Actually remove it
+    Commit,
+}
+
+impl DeleteMode {
+    /// The `dry_run` flag the cleaners take
+    pub fn is_preview(self) -> bool {
+        self != DeleteMode::Commit
+    }
+}
+
+impl<'de> Deserialize<'de> for DeleteMode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Ok(match value.as_str() {
+            Some("commit") => DeleteMode::Commit,
+            _ => DeleteMode::Preview,
+        })
+    }
+}
+
+/// Owning application category, used to scan or clean a whole group at once
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CacheCategory {
+    Browser,
+    PackageManager,
+    DevTool,
+    System,
+}
+
+impl std::str::FromStr for CacheCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "browser" => Ok(CacheCategory::Browser),
+            "package_manager" | "packagemanager" => Ok(CacheCategory::PackageManager),
+            "dev_tool" | "devtool" => Ok(CacheCategory::DevTool),
+            "system" => Ok(CacheCategory::System),
+            _ => Err(format!("Unknown cache category: {}", s)),
+        }
+    }
 }
 
 impl CacheType {
@@ -70,9 +245,14 @@ impl CacheType {
             "yarn" => Ok(CacheType::Yarn),
             "pnpm" => Ok(CacheType::Pnpm),
             "pip" => Ok(CacheType::Pip),
+            "poetry" => Ok(CacheType::Poetry),
+            "uv" => Ok(CacheType::Uv),
+            "conda" | "mamba" => Ok(CacheType::Conda),
             "cocoapods" => Ok(CacheType::CocoaPods),
             "gradle" => Ok(CacheType::Gradle),
             "cargo" => Ok(CacheType::Cargo),
+            "go_build" | "gobuild" => Ok(CacheType::GoBuild),
+            "go_mod" | "gomod" => Ok(CacheType::GoModCache),
             "xcode_derived_data" | "xcodederiveddata" => Ok(CacheType::XcodeDerivedData),
             "xcode_archives" | "xcodearchives" => Ok(CacheType::XcodeArchives),
             "xcode_simulators" | "xcodesimulators" => Ok(CacheType::XcodeSimulators),
@@ -80,12 +260,93 @@ impl CacheType {
             "user_logs" | "userlogs" => Ok(CacheType::UserLogs),
             "temp_files" | "tempfiles" | "tmp" => Ok(CacheType::TempFiles),
             "ios_backups" | "iosbackups" => Ok(CacheType::IosBackups),
+            "quicklook" | "ql" => Ok(CacheType::QuickLook),
             // Docker cache types
             "docker_containers" | "dockercontainers" => Ok(CacheType::DockerContainers),
             "docker_images" | "dockerimages" => Ok(CacheType::DockerImages),
             "docker_volumes" | "dockervolumes" => Ok(CacheType::DockerVolumes),
             "docker_build_cache" | "dockerbuildcache" | "docker_buildcache" => Ok(CacheType::DockerBuildCache),
-            _ => Err(format!("Unknown cache type: {}", s)),
+            _ => manifest::find_entry(s)
+                .map(|entry| CacheType::Manifest(entry.id.clone()))
+                .ok_or_else(|| format!("Unknown cache type: {}", s)),
+        }
+    }
+
+    pub fn clean_strategy(&self) -> CleanStrategy {
+        match self {
+            // ~/.npm also holds npmrc-related state; only the content cache is safe
+            CacheType::Npm => CleanStrategy::RemoveSubpath("_cacache"),
+            // ~/.cargo also holds bin/ and config; only the registry is a cache
+            CacheType::Cargo => CleanStrategy::RemoveSubpath("registry"),
+            CacheType::QuickLook => CleanStrategy::RunCommand("qlmanage -r cache"),
+            CacheType::GoBuild => CleanStrategy::RunCommand("go clean -cache"),
+            CacheType::GoModCache => CleanStrategy::RunCommand("go clean -modcache"),
+            CacheType::Uv => CleanStrategy::RunCommand("uv cache clean"),
+            CacheType::Poetry => CleanStrategy::RunCommand("poetry cache clear --all ."),
+            CacheType::Manifest(id) => match manifest::find_entry(id)
+                .and_then(|entry| entry.cleaner_command.as_deref())
+            {
+                Some(command) => CleanStrategy::RunCommand(command),
+                None => CleanStrategy::RemoveContents,
+            },
+            _ => CleanStrategy::RemoveContents,
+        }
+    }
+
+    /// Command that downloads a cleaned package cache again, for caches that
+    /// are otherwise refilled one package at a time by the next build
+    pub fn repopulation_hint(&self) -> Option<&'static str> {
+        match self {
+            CacheType::Npm => Some("Run `npm install` in a project to restore the npm cache"),
+            CacheType::Yarn => Some("Run `yarn install` in a project to restore the Yarn cache"),
+            CacheType::Pnpm => Some("Run `pnpm install` in a project to restore the pnpm store"),
+            CacheType::Pip => Some("Run `pip install -r requirements.txt` in a project to restore the pip cache"),
+            CacheType::Poetry => Some("Run `poetry install` in a project to restore the Poetry cache"),
+            CacheType::Uv => Some("Run `uv sync` in a project to restore the uv cache"),
+            CacheType::Conda => Some("Run `conda env update` in a project to restore the conda package cache"),
+            CacheType::CocoaPods => Some("Run `pod install` to restore CocoaPods cache"),
+            CacheType::Gradle => Some("Run `./gradlew dependencies` in a project to restore the Gradle cache"),
+            CacheType::Cargo => Some("Run `cargo fetch` in a project to restore the Cargo registry"),
+            CacheType::GoModCache => Some("Run `go mod download` in a module to restore the Go module cache"),
+            _ => None,
+        }
+    }
+
+    pub fn category(&self) -> CacheCategory {
+        match self {
+            CacheType::Chrome | CacheType::Safari | CacheType::Firefox | CacheType::Arc => {
+                CacheCategory::Browser
+            }
+            CacheType::Npm
+            | CacheType::Yarn
+            | CacheType::Pnpm
+            | CacheType::Pip
+            | CacheType::Poetry
+            | CacheType::Uv
+            | CacheType::Conda
+            | CacheType::CocoaPods
+            | CacheType::Gradle
+            | CacheType::Cargo
+            | CacheType::GoModCache => CacheCategory::PackageManager,
+            CacheType::VSCode
+            | CacheType::GoBuild
+            | CacheType::Cursor
+            | CacheType::XcodeDerivedData
+            | CacheType::XcodeArchives
+            | CacheType::XcodeSimulators
+            | CacheType::DockerContainers
+            | CacheType::DockerImages
+            | CacheType::DockerVolumes
+            | CacheType::DockerBuildCache => CacheCategory::DevTool,
+            CacheType::CacheDir
+            | CacheType::SystemCaches
+            | CacheType::UserLogs
+            | CacheType::TempFiles
+            | CacheType::IosBackups
+            | CacheType::QuickLook => CacheCategory::System,
+            CacheType::Manifest(id) => manifest::find_entry(id)
+                .and_then(|entry| entry.category)
+                .unwrap_or(CacheCategory::System),
         }
     }
 }
@@ -97,6 +358,20 @@ pub struct CacheInfo {
     pub size: u64,
     pub exists: bool,
     pub item_count: usize,
+    /// Relative margin of error when `size` was estimated from a sample
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_margin: Option<f64>,
+    /// Why a cache that should be there came up empty, for diagnostics
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Result of a full scan. Problems are reported in `errors` instead of
+/// failing the whole scan, so the UI can still show whatever was found.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScanReport {
+    pub caches: Vec<CacheInfo>,
+    pub errors: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -107,6 +382,182 @@ pub struct CleanResult {
     pub success: bool,
     pub message: String,
     pub dry_run: bool,
+    /// Breakdown of what was removed, only kept in verbose mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<CleanDetails>,
+    /// What the user can run to fill the cache again, e.g. `pod install`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repopulation_hint: Option<String>,
+    /// Processes that had files in the cache open when it was cleaned
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files_in_use: Vec<String>,
+    /// Growth of the volume's free space over the clean, when `verify_disk_space`
+    /// is on. It need not match `freed_bytes`: removing an APFS clone frees
+    /// only blocks it did not share, local snapshots hold on to deleted data
+    /// until macOS purges them, and other apps write to the disk meanwhile.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual_disk_delta: Option<u64>,
+    /// Set when `actual_disk_delta` is far from `freed_bytes`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk_delta_warning: Option<String>,
+}
+
+/// How much a `CleanResult` reports beyond its counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verbosity {
+    /// `message` is a one-word status for machine consumers
+    Quiet,
+    #[default]
+    Normal,
+    /// `details` is filled in where the cleaner measured it
+    Verbose,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanDetails {
+    /// Directories that were emptied
+    pub paths_touched: Vec<String>,
+    /// Bytes freed per top-level entry of those directories
+    pub entries: Vec<EntryFreed>,
+    pub errors: Vec<String>,
+    /// Files kept because they match a preserve glob
+    #[serde(default)]
+    pub preserved: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryFreed {
+    pub path: String,
+    pub freed_bytes: u64,
+}
+
+/// Disagreement between freed bytes and free-space growth that is never
+/// worth a warning, since other apps write to the disk during a clean
+const DISK_DELTA_MIN_TOLERANCE: u64 = 64 * 1024 * 1024;
+
+impl Default for CleanResult {
+    /// No bytes freed and none of the optional reports; literals set
+    /// `cache_type` and the counters and take the rest from here
+    fn default() -> Self {
+        Self {
+            cache_type: CacheType::CacheDir,
+            freed_bytes: 0,
+            items_removed: 0,
+            success: false,
+            message: String::new(),
+            dry_run: false,
+            details: None,
+            repopulation_hint: None,
+            files_in_use: Vec::new(),
+            actual_disk_delta: None,
+            disk_delta_warning: None,
+        }
+    }
+}
+
+impl CleanResult {
+    /// Result of emptying a cache directory, based on what was really removed.
+    /// Anything left behind makes the clean unsuccessful.
+    pub fn from_removal(cache_type: CacheType, outcome: &RemovalOutcome, success_message: String) -> Self {
+        let (success, mut message) = if outcome.is_complete() {
+            (true, success_message)
+        } else if outcome.errors.is_empty() {
+            (
+                false,
+                format!(
+                    "Freed {}, {} remain",
+                    format_size(outcome.freed_bytes),
+                    format_size(outcome.remaining_bytes)
+                ),
+            )
+        } else {
+            (
+                false,
+                format!(
+                    "Freed {}, {} could not be removed: {}",
+                    format_size(outcome.freed_bytes),
+                    format_size(outcome.remaining_bytes),
+                    outcome.errors.join("; ")
+                ),
+            )
+        };
+        if outcome.protected_items > 0 {
+            message.push_str(&format!(
+                " ({} recently modified files protected)",
+                outcome.protected_items
+            ));
+        }
+        if !outcome.preserved.is_empty() {
+            message.push_str(&format!(" ({} preserved files kept)", outcome.preserved.len()));
+        }
+
+        Self {
+            cache_type,
+            freed_bytes: outcome.freed_bytes,
+            items_removed: outcome.items_removed,
+            success,
+            message,
+            dry_run: false,
+            details: Some(CleanDetails {
+                paths_touched: outcome.roots.clone(),
+                entries: outcome
+                    .freed_by_entry
+                    .iter()
+                    .map(|(path, freed_bytes)| EntryFreed { path: path.clone(), freed_bytes: *freed_bytes })
+                    .collect(),
+                errors: outcome.errors.clone(),
+                preserved: outcome.preserved.clone(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Tell the user how to refill the cache, once something was really removed
+    pub fn with_repopulation_hint(mut self) -> Self {
+        if !self.dry_run && self.items_removed > 0 {
+            self.repopulation_hint = self.cache_type.repopulation_hint().map(str::to_string);
+        }
+        self
+    }
+
+    /// Warn that these processes may misbehave now their open files are gone
+    pub fn with_files_in_use(mut self, processes: Vec<String>) -> Self {
+        self.files_in_use = processes;
+        self
+    }
+
+    /// Record how much free space actually grew, warning when it is far
+    /// from `freed_bytes`
+    pub fn with_disk_delta(mut self, free_before: u64, free_after: u64) -> Self {
+        let delta = free_after.saturating_sub(free_before);
+        let tolerance = (self.freed_bytes / 4).max(DISK_DELTA_MIN_TOLERANCE);
+        if delta.abs_diff(self.freed_bytes) > tolerance {
+            self.disk_delta_warning = Some(format!(
+                "Free space grew by {} but {} was removed; APFS clones, local snapshots or other apps' writes can account for the difference",
+                format_size(delta),
+                format_size(self.freed_bytes)
+            ));
+        }
+        self.actual_disk_delta = Some(delta);
+        self
+    }
+
+    /// Trim the result down to what `verbosity` asks for
+    pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+        if verbosity != Verbosity::Verbose {
+            self.details = None;
+        }
+        if verbosity == Verbosity::Quiet {
+            self.message = match (self.dry_run, self.success) {
+                (true, _) => "dry_run",
+                (false, true) => "ok",
+                (false, false) => "failed",
+            }
+            .to_string();
+        }
+        self
+    }
 }
 
 
@@ -118,6 +569,15 @@ pub struct IndexedDbItem {
     pub path: PathBuf,
     pub size: u64,
     pub over_threshold: bool,
+    /// Chrome's "Default" profile
+    #[serde(default)]
+    pub is_default_profile: bool,
+    /// Last used profile, the one a running Chrome has open
+    #[serde(default)]
+    pub is_active_profile: bool,
+    /// Chrome was running when the scan happened
+    #[serde(default)]
+    pub browser_running: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,6 +601,50 @@ pub struct NpmCachesCleanResult {
     pub items_removed: usize,
     pub success: bool,
     pub message: String,
+    #[serde(default)]
+    pub path_results: Vec<PathResult>,
+}
+
+/// Outcome of removing a single selected path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathResult {
+    pub path: String,
+    pub removed: bool,
+    pub freed_bytes: u64,
+    pub error: Option<String>,
+    /// Removal was blocked by macOS privacy protection, not file permissions
+    #[serde(default)]
+    pub tcc_denied: bool,
+}
+
+impl PathResult {
+    pub fn removed(path: &str, freed_bytes: u64) -> Self {
+        Self {
+            path: path.to_string(),
+            removed: true,
+            freed_bytes,
+            error: None,
+            tcc_denied: false,
+        }
+    }
+
+    pub fn failed(path: &str, error: String) -> Self {
+        Self {
+            path: path.to_string(),
+            removed: false,
+            freed_bytes: 0,
+            error: Some(error),
+            tcc_denied: false,
+        }
+    }
+
+    /// Failed removal, flagged when it was a TCC denial
+    pub fn from_error(path: &str, error: &CacheError) -> Self {
+        Self {
+            tcc_denied: error.is_tcc_denied(),
+            ..Self::failed(path, error.to_string())
+        }
+    }
 }
 
 #[cfg(test)]
@@ -174,9 +678,16 @@ mod tests {
         assert!(matches!(CacheType::from_str("yarn"), Ok(CacheType::Yarn)));
         assert!(matches!(CacheType::from_str("pnpm"), Ok(CacheType::Pnpm)));
         assert!(matches!(CacheType::from_str("pip"), Ok(CacheType::Pip)));
+        assert!(matches!(CacheType::from_str("poetry"), Ok(CacheType::Poetry)));
+        assert!(matches!(CacheType::from_str("uv"), Ok(CacheType::Uv)));
+        assert!(matches!(CacheType::from_str("conda"), Ok(CacheType::Conda)));
         assert!(matches!(CacheType::from_str("cocoapods"), Ok(CacheType::CocoaPods)));
         assert!(matches!(CacheType::from_str("gradle"), Ok(CacheType::Gradle)));
         assert!(matches!(CacheType::from_str("cargo"), Ok(CacheType::Cargo)));
+        assert!(matches!(CacheType::from_str("go_build"), Ok(CacheType::GoBuild)));
+        assert!(matches!(CacheType::from_str("gobuild"), Ok(CacheType::GoBuild)));
+        assert!(matches!(CacheType::from_str("go_mod"), Ok(CacheType::GoModCache)));
+        assert!(matches!(CacheType::from_str("gomod"), Ok(CacheType::GoModCache)));
     }
 
     #[test]
@@ -194,6 +705,8 @@ mod tests {
         assert!(matches!(CacheType::from_str("tempfiles"), Ok(CacheType::TempFiles)));
         assert!(matches!(CacheType::from_str("tmp"), Ok(CacheType::TempFiles)));
         assert!(matches!(CacheType::from_str("iosbackups"), Ok(CacheType::IosBackups)));
+        assert!(matches!(CacheType::from_str("quicklook"), Ok(CacheType::QuickLook)));
+        assert!(matches!(CacheType::from_str("ql"), Ok(CacheType::QuickLook)));
     }
 
     #[test]
@@ -208,6 +721,11 @@ mod tests {
         assert!(matches!(CacheType::from_str("dockerbuildcache"), Ok(CacheType::DockerBuildCache)));
     }
 
+    #[test]
+    fn test_cache_type_from_str_manifest() {
+        assert!(matches!(CacheType::from_str("homebrew"), Ok(CacheType::Manifest(id)) if id == "homebrew"));
+    }
+
     #[test]
     fn test_cache_type_from_str_case_insensitive() {
         assert!(matches!(CacheType::from_str("NPM"), Ok(CacheType::Npm)));
@@ -230,6 +748,8 @@ mod tests {
             size: 1024,
             exists: true,
             item_count: 10,
+            size_margin: None,
+            note: None,
         };
         assert!(info.exists);
         assert_eq!(info.size, 1024);
@@ -245,6 +765,7 @@ mod tests {
             success: true,
             message: "Cleaned successfully".to_string(),
             dry_run: false,
+            ..Default::default()
         };
         assert!(result.success);
         assert_eq!(result.freed_bytes, 2048);
@@ -259,11 +780,27 @@ mod tests {
             path: std::path::PathBuf::from("/test/indexeddb"),
             size: 5000,
             over_threshold: true,
+            is_default_profile: true,
+            is_active_profile: false,
+            browser_running: false,
         };
         assert!(item.over_threshold);
         assert_eq!(item.profile, "Default");
     }
 
+    #[test]
+    fn test_path_result_constructors() {
+        let ok = PathResult::removed("/test/a", 100);
+        assert!(ok.removed);
+        assert_eq!(ok.freed_bytes, 100);
+        assert!(ok.error.is_none());
+
+        let failed = PathResult::failed("/test/b", "Permission denied".to_string());
+        assert!(!failed.removed);
+        assert_eq!(failed.freed_bytes, 0);
+        assert_eq!(failed.error.as_deref(), Some("Permission denied"));
+    }
+
     #[test]
     fn test_cache_type_serialization() {
         let cache_type = CacheType::Npm;
@@ -282,9 +819,290 @@ mod tests {
             size: 100,
             exists: true,
             item_count: 5,
+            size_margin: None,
+            note: None,
         };
         let serialized = serde_json::to_string(&info).unwrap();
         assert!(serialized.contains("Chrome"));
         assert!(serialized.contains("100"));
     }
+
+    #[test]
+    fn test_cache_category_mapping() {
+        use CacheCategory::*;
+        let expected = [
+            (CacheType::Chrome, Browser),
+            (CacheType::Safari, Browser),
+            (CacheType::Firefox, Browser),
+            (CacheType::Arc, Browser),
+            (CacheType::Npm, PackageManager),
+            (CacheType::Yarn, PackageManager),
+            (CacheType::Pnpm, PackageManager),
+            (CacheType::Pip, PackageManager),
+            (CacheType::Poetry, PackageManager),
+            (CacheType::Uv, PackageManager),
+            (CacheType::Conda, PackageManager),
+            (CacheType::CocoaPods, PackageManager),
+            (CacheType::Gradle, PackageManager),
+            (CacheType::Cargo, PackageManager),
+            (CacheType::GoModCache, PackageManager),
+            (CacheType::GoBuild, DevTool),
+            (CacheType::VSCode, DevTool),
+            (CacheType::Cursor, DevTool),
+            (CacheType::XcodeDerivedData, DevTool),
+            (CacheType::XcodeArchives, DevTool),
+            (CacheType::XcodeSimulators, DevTool),
+            (CacheType::DockerContainers, DevTool),
+            (CacheType::DockerImages, DevTool),
+            (CacheType::DockerVolumes, DevTool),
+            (CacheType::DockerBuildCache, DevTool),
+            (CacheType::CacheDir, System),
+            (CacheType::SystemCaches, System),
+            (CacheType::UserLogs, System),
+            (CacheType::TempFiles, System),
+            (CacheType::IosBackups, System),
+            (CacheType::QuickLook, System),
+        ];
+        for (cache_type, category) in expected {
+            assert_eq!(cache_type.category(), category, "{:?}", cache_type);
+        }
+    }
+
+    #[test]
+    fn test_manifest_cache_category() {
+        assert_eq!(
+            CacheType::Manifest("homebrew".to_string()).category(),
+            CacheCategory::PackageManager
+        );
+        assert_eq!(
+            CacheType::Manifest("missing".to_string()).category(),
+            CacheCategory::System
+        );
+    }
+
+    #[test]
+    fn test_cache_category_from_str() {
+        assert_eq!("browser".parse(), Ok(CacheCategory::Browser));
+        assert_eq!("package_manager".parse(), Ok(CacheCategory::PackageManager));
+        assert_eq!("DevTool".parse(), Ok(CacheCategory::DevTool));
+        assert!("games".parse::<CacheCategory>().is_err());
+    }
+
+    #[test]
+    fn test_clean_strategy_npm_targets_cacache() {
+        assert_eq!(CacheType::Npm.clean_strategy(), CleanStrategy::RemoveSubpath("_cacache"));
+    }
+
+    #[test]
+    fn test_clean_strategy_cargo_targets_registry() {
+        assert_eq!(CacheType::Cargo.clean_strategy(), CleanStrategy::RemoveSubpath("registry"));
+    }
+
+    #[test]
+    fn test_clean_strategy_commands() {
+        assert_eq!(
+            CacheType::QuickLook.clean_strategy(),
+            CleanStrategy::RunCommand("qlmanage -r cache")
+        );
+        assert_eq!(
+            CacheType::Manifest("homebrew".to_string()).clean_strategy(),
+            CleanStrategy::RunCommand("brew cleanup --prune=all")
+        );
+        assert_eq!(CacheType::Chrome.clean_strategy(), CleanStrategy::RemoveContents);
+    }
+
+    #[test]
+    fn test_clean_result_from_partial_removal() {
+        let outcome = RemovalOutcome {
+            freed_bytes: 100,
+            remaining_bytes: 50,
+            items_removed: 1,
+            errors: vec!["/cache/locked/b.bin: Permission denied".to_string()],
+            ..Default::default()
+        };
+        let result = CleanResult::from_removal(CacheType::Npm, &outcome, "Freed 150 bytes".to_string());
+        assert!(!result.success);
+        assert_eq!(result.freed_bytes, 100);
+        assert!(result.message.contains("50 B could not be removed"));
+    }
+
+    #[test]
+    fn test_path_result_flags_tcc_denial() {
+        let path = std::path::Path::new("/Users/test/Library/Caches/com.apple.Safari");
+        let error = CacheError::from_io(path, std::io::Error::from_raw_os_error(1));
+        let result = PathResult::from_error(&path.display().to_string(), &error);
+
+        assert!(!result.removed);
+        assert!(result.tcc_denied);
+        assert!(result.error.unwrap().contains("Privacy & Security"));
+    }
+
+    #[test]
+    fn test_clean_result_from_complete_removal() {
+        let outcome = RemovalOutcome {
+            freed_bytes: 150,
+            ..Default::default()
+        };
+        let result = CleanResult::from_removal(CacheType::Npm, &outcome, "Freed 150 bytes".to_string());
+        assert!(result.success);
+        assert_eq!(result.message, "Freed 150 bytes");
+        assert!(!result.dry_run);
+    }
+
+    #[test]
+    fn test_repopulation_hints() {
+        assert_eq!(
+            CacheType::CocoaPods.repopulation_hint(),
+            Some("Run `pod install` to restore CocoaPods cache")
+        );
+        assert_eq!(
+            CacheType::Cargo.repopulation_hint(),
+            Some("Run `cargo fetch` in a project to restore the Cargo registry")
+        );
+        assert_eq!(
+            CacheType::Npm.repopulation_hint(),
+            Some("Run `npm install` in a project to restore the npm cache")
+        );
+        assert_eq!(
+            CacheType::Gradle.repopulation_hint(),
+            Some("Run `./gradlew dependencies` in a project to restore the Gradle cache")
+        );
+        assert_eq!(CacheType::Chrome.repopulation_hint(), None);
+        assert_eq!(CacheType::XcodeDerivedData.repopulation_hint(), None);
+    }
+
+    #[test]
+    fn test_hint_only_after_real_removal() {
+        let outcome = RemovalOutcome {
+            freed_bytes: 150,
+            items_removed: 3,
+            ..Default::default()
+        };
+        let cleaned = CleanResult::from_removal(CacheType::CocoaPods, &outcome, "Freed".to_string());
+        assert!(cleaned.with_repopulation_hint().repopulation_hint.is_some());
+
+        let mut dry_run = CleanResult::from_removal(CacheType::CocoaPods, &outcome, "Freed".to_string());
+        dry_run.dry_run = true;
+        assert!(dry_run.with_repopulation_hint().repopulation_hint.is_none());
+
+        let nothing = CleanResult::from_removal(CacheType::CocoaPods, &RemovalOutcome::default(), "Freed".to_string());
+        assert!(nothing.with_repopulation_hint().repopulation_hint.is_none());
+    }
+
+    fn clean_fixture(verbosity: Verbosity) -> CleanResult {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub/a.bin"), [0u8; 100]).unwrap();
+        std::fs::write(dir.path().join("b.bin"), [0u8; 50]).unwrap();
+        let outcome = crate::utils::filesystem::empty_dir_measured(dir.path(), 150, 3, None);
+        CleanResult::from_removal(CacheType::Npm, &outcome, "Freed 150 bytes".to_string()).with_verbosity(verbosity)
+    }
+
+    #[test]
+    fn test_verbose_result_has_details() {
+        let verbose = clean_fixture(Verbosity::Verbose);
+        let quiet = clean_fixture(Verbosity::Quiet);
+        assert_eq!(verbose.freed_bytes, quiet.freed_bytes);
+
+        let details = verbose.details.unwrap();
+        assert_eq!(details.paths_touched.len(), 1);
+        let mut freed: Vec<u64> = details.entries.iter().map(|e| e.freed_bytes).collect();
+        freed.sort();
+        assert_eq!(freed, vec![50, 100]);
+        assert_eq!(verbose.message, "Freed 150 bytes");
+
+        assert!(quiet.details.is_none());
+        assert_eq!(quiet.message, "ok");
+    }
+
+    #[test]
+    fn test_normal_result_drops_details() {
+        let result = clean_fixture(Verbosity::Normal);
+        assert!(result.details.is_none());
+        assert_eq!(result.message, "Freed 150 bytes");
+    }
+
+    #[test]
+    fn test_results_tolerate_missing_optional_fields() {
+        let npm = serde_json::to_value(CacheType::Npm).unwrap();
+
+        let clean: CleanResult = serde_json::from_value(serde_json::json!({
+            "cache_type": npm,
+            "freed_bytes": 10,
+            "items_removed": 1,
+            "success": true,
+            "message": "ok",
+            "dry_run": false,
+        }))
+        .unwrap();
+        assert!(clean.details.is_none());
+        assert!(clean.repopulation_hint.is_none());
+        assert!(clean.files_in_use.is_empty());
+
+        let info: CacheInfo = serde_json::from_value(serde_json::json!({
+            "cache_type": npm,
+            "path": "/Users/test/.npm",
+            "size": 10,
+            "exists": true,
+            "item_count": 1,
+        }))
+        .unwrap();
+        assert_eq!(info.size, 10);
+        assert!(info.size_margin.is_none());
+
+        let npm_clean: NpmCachesCleanResult = serde_json::from_value(serde_json::json!({
+            "total_freed_bytes": 10,
+            "items_removed": 1,
+            "success": true,
+            "message": "ok",
+        }))
+        .unwrap();
+        assert!(npm_clean.path_results.is_empty());
+
+        let path: PathResult = serde_json::from_value(serde_json::json!({
+            "path": "/Users/test/.npm/_cacache",
+            "removed": false,
+            "freed_bytes": 0,
+            "error": "denied",
+        }))
+        .unwrap();
+        assert!(!path.tcc_denied);
+
+        let item: IndexedDbItem = serde_json::from_value(serde_json::json!({
+            "profile": "Default",
+            "origin": "https_example.com_0",
+            "path": "/tmp/idb",
+            "size": 10,
+            "over_threshold": false,
+        }))
+        .unwrap();
+        assert!(!item.is_default_profile && !item.is_active_profile && !item.browser_running);
+    }
+
+    #[test]
+    fn test_delete_mode_defaults_to_preview() {
+        #[derive(Deserialize)]
+        struct Request {
+            #[serde(default)]
+            mode: DeleteMode,
+        }
+
+        let mode = |value: serde_json::Value| serde_json::from_value::<DeleteMode>(value).unwrap();
+        assert_eq!(mode(serde_json::json!("commit")), DeleteMode::Commit);
+        assert_eq!(mode(serde_json::json!("preview")), DeleteMode::Preview);
+        assert_eq!(mode(serde_json::json!("Commit")), DeleteMode::Preview);
+        assert_eq!(mode(serde_json::json!("delete")), DeleteMode::Preview);
+        assert_eq!(mode(serde_json::json!(false)), DeleteMode::Preview);
+        assert_eq!(mode(serde_json::json!(null)), DeleteMode::Preview);
+        assert_eq!(serde_json::from_value::<Option<DeleteMode>>(serde_json::json!(null)).unwrap(), None);
+
+        let missing: Request = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert_eq!(missing.mode, DeleteMode::Preview);
+        let legacy: Request = serde_json::from_value(serde_json::json!({ "dry_run": false })).unwrap();
+        assert_eq!(legacy.mode, DeleteMode::Preview);
+
+        assert!(DeleteMode::Preview.is_preview());
+        assert!(!DeleteMode::Commit.is_preview());
+        assert_eq!(serde_json::to_value(DeleteMode::Commit).unwrap(), serde_json::json!("commit"));
+    }
 }
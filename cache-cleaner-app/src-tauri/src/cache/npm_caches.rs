@@ -1,5 +1,6 @@
-use super::NpmCacheEntry;
+use super::{guard, CacheError, NpmCacheEntry, PathResult};
 use crate::utils::filesystem;
+use crate::utils::home::require_home;
 use anyhow::Result;
 use std::path::{Path, PathBuf};
 
@@ -14,8 +15,7 @@ const NPM_CACHE_PATHS: &[&str] = &[
 
 /// Scans ~/.npm for specific cache subdirectories
 pub async fn scan_npm_caches() -> Result<Vec<NpmCacheEntry>> {
-    let home = dirs::home_dir()
-        .ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+    let home = require_home()?;
     let npm_dir = home.join(".npm");
 
     if !npm_dir.exists() {
@@ -78,8 +78,8 @@ fn scan_npm_caches_sync(npm_dir: &Path) -> Result<Vec<NpmCacheEntry>> {
 
 /// Removes the specified NPM cache directories
 pub async fn remove_npm_caches(paths: Vec<String>) -> Result<super::NpmCachesCleanResult> {
-    let home = dirs::home_dir()
-        .ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+    guard::ensure_deletion_enabled()?;
+    let home = require_home()?;
     let npm_dir = home.join(".npm");
     let npm_dir_str = npm_dir.to_string_lossy().to_string();
 
@@ -106,10 +106,12 @@ pub async fn remove_npm_caches(paths: Vec<String>) -> Result<super::NpmCachesCle
 fn remove_npm_caches_sync(paths: &[PathBuf]) -> Result<super::NpmCachesCleanResult> {
     let mut total_freed = 0u64;
     let mut items_removed = 0usize;
-    let mut errors = Vec::new();
+    let mut path_results = Vec::new();
 
     for path in paths {
+        let path_str = path.to_string_lossy().to_string();
         if !path.exists() {
+            path_results.push(PathResult::failed(&path_str, format!("Path not found: {}", path_str)));
             continue;
         }
 
@@ -117,17 +119,20 @@ fn remove_npm_caches_sync(paths: &[PathBuf]) -> Result<super::NpmCachesCleanResu
         let size = filesystem::calculate_dir_size_sync(path).unwrap_or(0);
 
         // Xóa thư mục
-        match std::fs::remove_dir_all(path) {
+        match filesystem::delete_dir_all(path) {
             Ok(_) => {
                 total_freed += size;
                 items_removed += 1;
+                path_results.push(PathResult::removed(&path_str, size));
             }
             Err(e) => {
-                errors.push(format!("{}: {}", path.display(), e));
+                path_results.push(PathResult::from_error(&path_str, &CacheError::from_io(path, e)));
             }
         }
     }
 
+    let errors: Vec<String> = path_results.iter().filter_map(|r| r.error.clone()).collect();
+
     let message = if errors.is_empty() {
         format!("Successfully removed {} NPM cache directory(ies)", items_removed)
     } else {
@@ -144,6 +149,46 @@ fn remove_npm_caches_sync(paths: &[PathBuf]) -> Result<super::NpmCachesCleanResu
         items_removed,
         success: errors.is_empty(),
         message,
+        path_results,
     })
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::guard;
+    use std::fs;
+
+    #[test]
+    fn test_remove_npm_caches_read_only_reports_sizes() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("_cacache");
+        fs::create_dir_all(cache.join("content-v2")).unwrap();
+        fs::write(cache.join("content-v2/blob"), [0u8; 100]).unwrap();
+
+        guard::set_read_only_for_thread(true);
+        let result = remove_npm_caches_sync(&[cache.clone()]).unwrap();
+        guard::set_read_only_for_thread(false);
+
+        assert!(cache.join("content-v2/blob").exists());
+        assert_eq!(result.total_freed_bytes, 100);
+        assert_eq!(result.items_removed, 1);
+        assert!(result.success);
+    }
+
+    #[test]
+    fn test_remove_npm_caches_counts_missing_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("_cacache");
+        fs::create_dir_all(&cache).unwrap();
+        let missing = dir.path().join("_npx");
+
+        let result = remove_npm_caches_sync(&[cache.clone(), missing]).unwrap();
+
+        assert_eq!(result.items_removed, 1);
+        assert!(!result.success);
+        assert_eq!(result.path_results.iter().filter(|r| r.error.is_some()).count(), 1);
+        assert!(result.message.contains("1 errors"), "{}", result.message);
+    }
+}
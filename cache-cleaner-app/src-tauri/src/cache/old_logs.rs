@@ -0,0 +1,247 @@
+//! Old log files across the log folders.
+//!
+//! A narrower alternative to emptying `~/Library/Logs` as the `UserLogs`
+//! cache type does: only files that look like logs (`.log`, rotated
+//! `.log.N`, compressed `.gz`) and have not been written for the given
+//! number of days are removed. Recent logs, crash reports and anything else
+//! in the same folders stay. `/var/log` is scanned where readable; files
+//! there that need administrator rights are skipped rather than failed.
+
+use super::cold_artifacts::LargeCacheEntry;
+use super::paths::MacPaths;
+use super::smart_suggestions::CleanupConfidence;
+use super::{guard, CacheError, PathResult};
+use crate::utils::filesystem;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OldLogsCleanResult {
+    pub total_freed_bytes: u64,
+    pub items_removed: usize,
+    /// Old logs we lack the rights to delete, e.g. root-owned files in `/var/log`
+    #[serde(default)]
+    pub items_skipped: usize,
+    pub success: bool,
+    pub message: String,
+    pub dry_run: bool,
+    pub path_results: Vec<PathResult>,
+}
+
+/// `~/Library/Logs`, `/var/log` and the `Logs` folder of every sandboxed app
+fn log_roots(home: &Path) -> Vec<PathBuf> {
+    let mut roots = vec![home.join("Library/Logs"), MacPaths::system_logs()];
+    if let Ok(containers) = std::fs::read_dir(home.join("Library/Containers")) {
+        let mut app_logs: Vec<PathBuf> = containers
+            .flatten()
+            .map(|entry| entry.path().join("Data/Library/Logs"))
+            .filter(|path| path.is_dir())
+            .collect();
+        app_logs.sort();
+        roots.extend(app_logs);
+    }
+    roots
+}
+
+/// `.log`, rotated `.log.N`, or compressed `.gz`
+fn is_log_file(name: &str) -> bool {
+    let name = name.to_lowercase();
+    if name.ends_with(".log") || name.ends_with(".gz") {
+        return true;
+    }
+    name.rsplit_once(".log.")
+        .is_some_and(|(_, n)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Log files not written for `older_than_days`, largest first
+pub async fn scan_old_logs(older_than_days: u64) -> Result<Vec<LargeCacheEntry>> {
+    let roots = log_roots(&MacPaths::home()?);
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+    tokio::task::spawn_blocking(move || Ok(scan_old_logs_in(&roots, now, older_than_days))).await?
+}
+
+/// Delete the log files `scan_old_logs` reports; nothing else is touched
+pub async fn clean_old_logs(older_than_days: u64, dry_run: bool) -> Result<OldLogsCleanResult> {
+    if !dry_run {
+        guard::ensure_deletion_enabled()?;
+    }
+    let roots = log_roots(&MacPaths::home()?);
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?.as_secs();
+    tokio::task::spawn_blocking(move || Ok(clean_old_logs_in(&roots, now, older_than_days, dry_run))).await?
+}
+
+fn scan_old_logs_in(roots: &[PathBuf], now_secs: u64, older_than_days: u64) -> Vec<LargeCacheEntry> {
+    let mut entries = Vec::new();
+    for root in roots {
+        // Unreadable folders (much of /var/log) are skipped silently
+        for entry in WalkDir::new(root).into_iter().flatten() {
+            if !entry.file_type().is_file() || !is_log_file(&entry.file_name().to_string_lossy()) {
+                continue;
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let Some(modified) = metadata
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            else {
+                continue;
+            };
+            let age_days = now_secs.saturating_sub(modified.as_secs()) / SECS_PER_DAY;
+            if age_days < older_than_days {
+                continue;
+            }
+            entries.push(LargeCacheEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: entry.path().display().to_string(),
+                size_bytes: metadata.len(),
+                last_used_days: Some(age_days),
+                confidence: CleanupConfidence::Safe,
+                auto_select: true,
+            });
+        }
+    }
+
+    entries.sort_by_key(|entry| Reverse(entry.size_bytes));
+    entries
+}
+
+fn clean_old_logs_in(roots: &[PathBuf], now_secs: u64, older_than_days: u64, dry_run: bool) -> OldLogsCleanResult {
+    let mut total_freed = 0u64;
+    let mut items_removed = 0usize;
+    let mut items_skipped = 0usize;
+    let mut path_results = Vec::new();
+
+    for entry in scan_old_logs_in(roots, now_secs, older_than_days) {
+        let path = PathBuf::from(&entry.path);
+        if let Err(e) = guard::assert_safe_to_delete(&path) {
+            path_results.push(PathResult::failed(&entry.path, e.to_string()));
+            continue;
+        }
+        if dry_run || filesystem::skip_deletion() {
+            total_freed += entry.size_bytes;
+            items_removed += 1;
+            path_results.push(PathResult::removed(&entry.path, entry.size_bytes));
+            continue;
+        }
+        match filesystem::delete_file(&path) {
+            Ok(()) => {
+                total_freed += entry.size_bytes;
+                items_removed += 1;
+                path_results.push(PathResult::removed(&entry.path, entry.size_bytes));
+            }
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => items_skipped += 1,
+            Err(e) => path_results.push(PathResult::from_error(&entry.path, &CacheError::from_io(&path, e))),
+        }
+    }
+
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    let errors: Vec<String> = path_results.iter().filter_map(|r| r.error.clone()).collect();
+    let mut message = format!(
+        "{} {} log file(s) older than {} day(s)",
+        verb, items_removed, older_than_days
+    );
+    if items_skipped > 0 {
+        message.push_str(&format!(", skipped {} needing administrator rights", items_skipped));
+    }
+    if !errors.is_empty() {
+        message.push_str(&format!(", {} errors: {}", errors.len(), errors.join("; ")));
+    }
+
+    OldLogsCleanResult {
+        total_freed_bytes: total_freed,
+        items_removed,
+        items_skipped,
+        success: errors.is_empty(),
+        message,
+        dry_run,
+        path_results,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn create_file(path: &Path, bytes: usize, days_old: u64) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, vec![b'x'; bytes]).unwrap();
+        let when = SystemTime::now() - Duration::from_secs(days_old * SECS_PER_DAY);
+        File::options().write(true).open(path).unwrap().set_modified(when).unwrap();
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs()
+    }
+
+    /// Old and new logs, plus old files that are not logs
+    fn create_logs(root: &Path) {
+        create_file(&root.join("app/old.log"), 100, 40);
+        create_file(&root.join("app/old.log.1"), 50, 40);
+        create_file(&root.join("app/archive.log.2.gz"), 25, 40);
+        create_file(&root.join("app/current.log"), 10, 1);
+        create_file(&root.join("app/settings.plist"), 70, 40);
+        create_file(&root.join("DiagnosticReports/crash.ips"), 80, 40);
+        create_file(&root.join("app/notes.log.bak"), 5, 40);
+    }
+
+    #[test]
+    fn test_is_log_file() {
+        assert!(is_log_file("system.log"));
+        assert!(is_log_file("install.LOG"));
+        assert!(is_log_file("wifi.log.3"));
+        assert!(is_log_file("system.log.0.gz"));
+        assert!(!is_log_file("crash.ips"));
+        assert!(!is_log_file("notes.log.bak"));
+        assert!(!is_log_file("catalog"));
+    }
+
+    #[test]
+    fn test_scan_reports_only_old_logs() {
+        let root = create_test_dir();
+        create_logs(root.path());
+
+        let entries = scan_old_logs_in(&[root.path().to_path_buf()], now(), 30);
+        let mut names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["archive.log.2.gz", "old.log", "old.log.1"]);
+        assert!(entries.iter().all(|e| e.last_used_days.unwrap() >= 30 && e.auto_select));
+    }
+
+    #[test]
+    fn test_clean_removes_only_old_logs() {
+        let root = create_test_dir();
+        create_logs(root.path());
+        let roots = [root.path().to_path_buf()];
+
+        let preview = clean_old_logs_in(&roots, now(), 30, true);
+        assert_eq!(preview.items_removed, 3);
+        assert!(root.path().join("app/old.log").exists());
+
+        let result = clean_old_logs_in(&roots, now(), 30, false);
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.items_removed, 3);
+        assert_eq!(result.total_freed_bytes, 175);
+        for removed in ["app/old.log", "app/old.log.1", "app/archive.log.2.gz"] {
+            assert!(!root.path().join(removed).exists(), "{} should be removed", removed);
+        }
+        for kept in ["app/current.log", "app/settings.plist", "DiagnosticReports/crash.ips", "app/notes.log.bak"] {
+            assert!(root.path().join(kept).exists(), "{} should be kept", kept);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! One destructive operation at a time.
+//!
+//! Every command that deletes, moves or restores files holds this lock for its
+//! whole duration. A second request does not fail, it queues: it runs once the
+//! first finishes and then measures what is actually left, so two overlapping
+//! cleans of the same tree never race or count the same bytes twice. Dry runs
+//! queue too, so their numbers are not taken from a half-cleaned tree.
+
+use tokio::sync::{Mutex, MutexGuard};
+
+static OPERATION_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Wait until no other destructive operation is running
+pub async fn exclusive() -> MutexGuard<'static, ()> {
+    OPERATION_LOCK.lock().await
+}
+
@@ -1,9 +1,11 @@
 use crate::cache::{CacheInfo, CacheType, CleanResult};
 use crate::cache::paths::MacPaths;
+use crate::utils::filesystem;
 use std::fs;
+use std::time::Duration;
 
 pub fn get_yarn_cache_info() -> CacheInfo {
-    let path = MacPaths::yarn_cache();
+    let path = MacPaths::yarn_cache().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = get_cache_stats(&path);
     
@@ -13,11 +15,13 @@ pub fn get_yarn_cache_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
 pub fn get_pnpm_cache_info() -> CacheInfo {
-    let path = MacPaths::pnpm_cache();
+    let path = MacPaths::pnpm_cache().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = get_cache_stats(&path);
     
@@ -27,11 +31,13 @@ pub fn get_pnpm_cache_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
 pub fn get_pip_cache_info() -> CacheInfo {
-    let path = MacPaths::pip_cache();
+    let path = MacPaths::pip_cache().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = get_cache_stats(&path);
     
@@ -41,11 +47,13 @@ pub fn get_pip_cache_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
 pub fn get_cocoapods_cache_info() -> CacheInfo {
-    let path = MacPaths::cocoapods_cache();
+    let path = MacPaths::cocoapods_cache().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = get_cache_stats(&path);
     
@@ -55,11 +63,13 @@ pub fn get_cocoapods_cache_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
 pub fn get_gradle_cache_info() -> CacheInfo {
-    let path = MacPaths::gradle_cache();
+    let path = MacPaths::gradle_cache().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = get_cache_stats(&path);
     
@@ -69,11 +79,13 @@ pub fn get_gradle_cache_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
 pub fn get_cargo_cache_info() -> CacheInfo {
-    let path = MacPaths::cargo_cache();
+    let path = MacPaths::cargo_cache().unwrap_or_default();
     let path_str = path.display().to_string();
     let (size, item_count, exists) = get_cache_stats(&path);
     
@@ -83,10 +95,12 @@ pub fn get_cargo_cache_info() -> CacheInfo {
         size,
         exists,
         item_count,
+        size_margin: None,
+        note: None,
     }
 }
 
-pub fn clean_package_cache(cache_type: CacheType, dry_run: bool) -> CleanResult {
+pub fn clean_package_cache(cache_type: CacheType, dry_run: bool, protect_recent: Option<Duration>) -> CleanResult {
     let path = match cache_type {
         CacheType::Yarn => MacPaths::yarn_cache(),
         CacheType::Pnpm => MacPaths::pnpm_cache(),
@@ -101,6 +115,19 @@ pub fn clean_package_cache(cache_type: CacheType, dry_run: bool) -> CleanResult
             success: false,
             message: "Invalid package manager cache type".to_string(),
             dry_run,
+            ..Default::default()
+        },
+    };
+    let path = match path {
+        Ok(p) => p,
+        Err(e) => return CleanResult {
+            cache_type,
+            freed_bytes: 0,
+            items_removed: 0,
+            success: false,
+            message: e.to_string(),
+            dry_run,
+            ..Default::default()
         },
     };
 
@@ -112,6 +139,7 @@ pub fn clean_package_cache(cache_type: CacheType, dry_run: bool) -> CleanResult
             success: true,
             message: "Cache directory does not exist".to_string(),
             dry_run,
+            ..Default::default()
         };
     }
 
@@ -124,6 +152,7 @@ pub fn clean_package_cache(cache_type: CacheType, dry_run: bool) -> CleanResult
             success: false,
             message: format!("Failed to calculate size: {}", e),
             dry_run,
+            ..Default::default()
         },
     };
 
@@ -135,27 +164,12 @@ pub fn clean_package_cache(cache_type: CacheType, dry_run: bool) -> CleanResult
             success: true,
             message: format!("Would clean {} items ({} bytes)", items_before, size_before),
             dry_run,
+            ..Default::default()
         };
     }
 
-    match fs::remove_dir_all(&path) {
-        Ok(_) => CleanResult {
-            cache_type,
-            freed_bytes: size_before,
-            items_removed: items_before,
-            success: true,
-            message: format!("Successfully cleaned {} items", items_before),
-            dry_run,
-        },
-        Err(e) => CleanResult {
-            cache_type,
-            freed_bytes: 0,
-            items_removed: 0,
-            success: false,
-            message: format!("Failed to clean cache: {}", e),
-            dry_run,
-        },
-    }
+    let outcome = filesystem::empty_dir_measured(&path, size_before, items_before, protect_recent);
+    CleanResult::from_removal(cache_type, &outcome, format!("Successfully cleaned {} items", items_before))
 }
 
 fn get_cache_stats(path: &std::path::Path) -> (u64, usize, bool) {
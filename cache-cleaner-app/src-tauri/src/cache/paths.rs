@@ -1,87 +1,106 @@
+use crate::cache::{go_caches, python_caches, CacheError};
+use crate::utils::home::require_home;
 use std::path::PathBuf;
-use dirs::home_dir;
 
 pub struct MacPaths;
 
 impl MacPaths {
-    pub fn home() -> PathBuf {
-        home_dir().unwrap_or_else(|| PathBuf::from("/"))
+    /// Home directory; there is deliberately no fallback to `/`
+    pub fn home() -> Result<PathBuf, CacheError> {
+        require_home()
     }
 
     // Browser Caches
-    pub fn chrome_cache() -> PathBuf {
-        Self::home().join("Library/Caches/Google/Chrome")
+    pub fn chrome_cache() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Caches/Google/Chrome"))
     }
 
-    pub fn chrome_default_cache() -> PathBuf {
-        Self::home().join("Library/Caches/Google/Chrome/Default/Cache")
+    pub fn chrome_default_cache() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Caches/Google/Chrome/Default/Cache"))
     }
 
-    pub fn safari_cache() -> PathBuf {
-        Self::home().join("Library/Caches/com.apple.Safari")
+    pub fn safari_cache() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Caches/com.apple.Safari"))
     }
 
-    pub fn firefox_profiles() -> PathBuf {
-        Self::home().join("Library/Caches/Firefox/Profiles")
+    pub fn firefox_profiles() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Caches/Firefox/Profiles"))
     }
 
-    pub fn arc_cache() -> PathBuf {
-        Self::home().join("Library/Caches/company.thebrowser.Browser")
+    pub fn arc_cache() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Caches/company.thebrowser.Browser"))
     }
 
     // Package Manager Caches
-    pub fn npm_cache() -> PathBuf {
-        Self::home().join(".npm/_cacache")
+    pub fn npm_cache() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join(".npm/_cacache"))
     }
 
-    pub fn yarn_cache() -> PathBuf {
-        Self::home().join("Library/Caches/Yarn")
+    pub fn yarn_cache() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Caches/Yarn"))
     }
 
-    pub fn pnpm_cache() -> PathBuf {
-        Self::home().join("Library/pnpm/store")
+    pub fn pnpm_cache() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/pnpm/store"))
     }
 
-    pub fn pip_cache() -> PathBuf {
-        Self::home().join(".cache/pip")
+    pub fn pip_cache() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join(".cache/pip"))
     }
 
-    pub fn cocoapods_cache() -> PathBuf {
-        Self::home().join("Library/Caches/CocoaPods")
+    pub fn cocoapods_cache() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Caches/CocoaPods"))
     }
 
-    pub fn gradle_cache() -> PathBuf {
-        Self::home().join(".gradle/caches")
+    pub fn gradle_cache() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join(".gradle/caches"))
     }
 
-    pub fn cargo_cache() -> PathBuf {
-        Self::home().join(".cargo/registry")
+    pub fn cargo_cache() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join(".cargo/registry"))
+    }
+
+    // Only sees GOCACHE/GOMODCACHE/GOPATH when the app was launched with them
+    pub fn go_build_cache() -> Result<PathBuf, CacheError> {
+        Ok(go_caches::go_build_cache_in(&Self::home()?, |key| std::env::var(key).ok()))
+    }
+
+    pub fn go_mod_cache() -> Result<PathBuf, CacheError> {
+        Ok(go_caches::go_mod_cache_in(&Self::home()?, |key| std::env::var(key).ok()))
+    }
+
+    pub fn poetry_cache() -> Result<PathBuf, CacheError> {
+        Ok(python_caches::poetry_cache_in(&Self::home()?, |key| std::env::var(key).ok()))
+    }
+
+    pub fn uv_cache() -> Result<PathBuf, CacheError> {
+        Ok(python_caches::uv_cache_in(&Self::home()?, |key| std::env::var(key).ok()))
     }
 
     // Development Tools
-    pub fn xcode_derived_data() -> PathBuf {
-        Self::home().join("Library/Developer/Xcode/DerivedData")
+    pub fn xcode_derived_data() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Developer/Xcode/DerivedData"))
     }
 
-    pub fn xcode_archives() -> PathBuf {
-        Self::home().join("Library/Developer/Xcode/Archives")
+    pub fn xcode_archives() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Developer/Xcode/Archives"))
     }
 
-    pub fn xcode_simulators() -> PathBuf {
-        Self::home().join("Library/Developer/CoreSimulator/Devices")
+    pub fn xcode_simulators() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Developer/CoreSimulator/Devices"))
     }
 
     // System Caches
-    pub fn user_caches() -> PathBuf {
-        Self::home().join("Library/Caches")
+    pub fn user_caches() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Caches"))
     }
 
     pub fn system_caches() -> PathBuf {
         PathBuf::from("/Library/Caches")
     }
 
-    pub fn user_logs() -> PathBuf {
-        Self::home().join("Library/Logs")
+    pub fn user_logs() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Logs"))
     }
 
     pub fn system_logs() -> PathBuf {
@@ -97,25 +116,25 @@ impl MacPaths {
     }
 
     // User Directories
-    pub fn trash() -> PathBuf {
-        Self::home().join(".Trash")
+    pub fn trash() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join(".Trash"))
     }
 
-    pub fn downloads() -> PathBuf {
-        Self::home().join("Downloads")
+    pub fn downloads() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Downloads"))
     }
 
-    pub fn documents() -> PathBuf {
-        Self::home().join("Documents")
+    pub fn documents() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Documents"))
     }
 
     // iOS/Mobile
-    pub fn ios_backups() -> PathBuf {
-        Self::home().join("Library/Application Support/MobileSync/Backup")
+    pub fn ios_backups() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Application Support/MobileSync/Backup"))
     }
 
-    pub fn mail_downloads() -> PathBuf {
-        Self::home().join("Library/Containers/com.apple.mail/Data/Library/Mail Downloads")
+    pub fn mail_downloads() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join("Library/Containers/com.apple.mail/Data/Library/Mail Downloads"))
     }
 
     // Applications
@@ -124,8 +143,8 @@ impl MacPaths {
     }
 
     // Generic cache directory
-    pub fn cache_dir() -> PathBuf {
-        Self::home().join(".cache")
+    pub fn cache_dir() -> Result<PathBuf, CacheError> {
+        Ok(Self::home()?.join(".cache"))
     }
 
     // Check if path is system-protected
@@ -144,25 +163,29 @@ impl MacPaths {
     }
 
     // Get all cache paths as a vector
-    pub fn all_cache_paths() -> Vec<(String, PathBuf)> {
-        vec![
-            ("Chrome Cache".to_string(), Self::chrome_cache()),
-            ("Safari Cache".to_string(), Self::safari_cache()),
-            ("Firefox Profiles".to_string(), Self::firefox_profiles()),
-            ("Arc Cache".to_string(), Self::arc_cache()),
-            ("npm Cache".to_string(), Self::npm_cache()),
-            ("Yarn Cache".to_string(), Self::yarn_cache()),
-            ("pnpm Cache".to_string(), Self::pnpm_cache()),
-            ("pip Cache".to_string(), Self::pip_cache()),
-            ("CocoaPods Cache".to_string(), Self::cocoapods_cache()),
-            ("Gradle Cache".to_string(), Self::gradle_cache()),
-            ("Cargo Cache".to_string(), Self::cargo_cache()),
-            ("Xcode DerivedData".to_string(), Self::xcode_derived_data()),
-            ("Xcode Archives".to_string(), Self::xcode_archives()),
-            ("Xcode Simulators".to_string(), Self::xcode_simulators()),
-            ("User Caches".to_string(), Self::user_caches()),
-            ("Cache Directory".to_string(), Self::cache_dir()),
-        ]
+    pub fn all_cache_paths() -> Result<Vec<(String, PathBuf)>, CacheError> {
+        Ok(vec![
+            ("Chrome Cache".to_string(), Self::chrome_cache()?),
+            ("Safari Cache".to_string(), Self::safari_cache()?),
+            ("Firefox Profiles".to_string(), Self::firefox_profiles()?),
+            ("Arc Cache".to_string(), Self::arc_cache()?),
+            ("npm Cache".to_string(), Self::npm_cache()?),
+            ("Yarn Cache".to_string(), Self::yarn_cache()?),
+            ("pnpm Cache".to_string(), Self::pnpm_cache()?),
+            ("pip Cache".to_string(), Self::pip_cache()?),
+            ("Poetry Cache".to_string(), Self::poetry_cache()?),
+            ("uv Cache".to_string(), Self::uv_cache()?),
+            ("CocoaPods Cache".to_string(), Self::cocoapods_cache()?),
+            ("Gradle Cache".to_string(), Self::gradle_cache()?),
+            ("Cargo Cache".to_string(), Self::cargo_cache()?),
+            ("Go Build Cache".to_string(), Self::go_build_cache()?),
+            ("Go Module Cache".to_string(), Self::go_mod_cache()?),
+            ("Xcode DerivedData".to_string(), Self::xcode_derived_data()?),
+            ("Xcode Archives".to_string(), Self::xcode_archives()?),
+            ("Xcode Simulators".to_string(), Self::xcode_simulators()?),
+            ("User Caches".to_string(), Self::user_caches()?),
+            ("Cache Directory".to_string(), Self::cache_dir()?),
+        ])
     }
 }
 
@@ -172,49 +195,49 @@ mod tests {
 
     #[test]
     fn test_home_returns_valid_path() {
-        let home = MacPaths::home();
+        let home = MacPaths::home().unwrap();
         assert!(!home.as_os_str().is_empty());
     }
 
     #[test]
     fn test_browser_cache_paths() {
-        let chrome = MacPaths::chrome_cache();
+        let chrome = MacPaths::chrome_cache().unwrap();
         assert!(chrome.to_string_lossy().contains("Library/Caches/Google/Chrome"));
 
-        let safari = MacPaths::safari_cache();
+        let safari = MacPaths::safari_cache().unwrap();
         assert!(safari.to_string_lossy().contains("com.apple.Safari"));
 
-        let firefox = MacPaths::firefox_profiles();
+        let firefox = MacPaths::firefox_profiles().unwrap();
         assert!(firefox.to_string_lossy().contains("Firefox"));
 
-        let arc = MacPaths::arc_cache();
+        let arc = MacPaths::arc_cache().unwrap();
         assert!(arc.to_string_lossy().contains("company.thebrowser.Browser"));
     }
 
     #[test]
     fn test_package_manager_paths() {
-        let npm = MacPaths::npm_cache();
+        let npm = MacPaths::npm_cache().unwrap();
         assert!(npm.to_string_lossy().contains(".npm"));
 
-        let yarn = MacPaths::yarn_cache();
+        let yarn = MacPaths::yarn_cache().unwrap();
         assert!(yarn.to_string_lossy().contains("Yarn"));
 
-        let pip = MacPaths::pip_cache();
+        let pip = MacPaths::pip_cache().unwrap();
         assert!(pip.to_string_lossy().contains("pip"));
 
-        let cargo = MacPaths::cargo_cache();
+        let cargo = MacPaths::cargo_cache().unwrap();
         assert!(cargo.to_string_lossy().contains(".cargo"));
     }
 
     #[test]
     fn test_xcode_paths() {
-        let derived = MacPaths::xcode_derived_data();
+        let derived = MacPaths::xcode_derived_data().unwrap();
         assert!(derived.to_string_lossy().contains("DerivedData"));
 
-        let archives = MacPaths::xcode_archives();
+        let archives = MacPaths::xcode_archives().unwrap();
         assert!(archives.to_string_lossy().contains("Archives"));
 
-        let simulators = MacPaths::xcode_simulators();
+        let simulators = MacPaths::xcode_simulators().unwrap();
         assert!(simulators.to_string_lossy().contains("CoreSimulator"));
     }
 
@@ -244,7 +267,7 @@ mod tests {
 
     #[test]
     fn test_all_cache_paths_not_empty() {
-        let paths = MacPaths::all_cache_paths();
+        let paths = MacPaths::all_cache_paths().unwrap();
         assert!(!paths.is_empty());
         assert!(paths.len() >= 10);
         
@@ -256,19 +279,29 @@ mod tests {
 
     #[test]
     fn test_user_directories() {
-        let trash = MacPaths::trash();
+        let trash = MacPaths::trash().unwrap();
         assert!(trash.to_string_lossy().contains(".Trash"));
 
-        let downloads = MacPaths::downloads();
+        let downloads = MacPaths::downloads().unwrap();
         assert!(downloads.to_string_lossy().contains("Downloads"));
 
-        let documents = MacPaths::documents();
+        let documents = MacPaths::documents().unwrap();
         assert!(documents.to_string_lossy().contains("Documents"));
     }
 
+    #[test]
+    fn test_no_home_relative_path_resolves_to_root() {
+        let root = PathBuf::from("/");
+        assert_ne!(MacPaths::home().unwrap(), root);
+        for (name, path) in MacPaths::all_cache_paths().unwrap() {
+            assert_ne!(path, root, "{} resolved to /", name);
+            assert!(path.is_absolute());
+        }
+    }
+
     #[test]
     fn test_ios_paths() {
-        let backups = MacPaths::ios_backups();
+        let backups = MacPaths::ios_backups().unwrap();
         assert!(backups.to_string_lossy().contains("MobileSync/Backup"));
     }
 }
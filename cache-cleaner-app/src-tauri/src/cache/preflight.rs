@@ -0,0 +1,148 @@
+//! Pre-flight check for a clean.
+//!
+//! A dry run only reports sizes. This goes one step further and verifies that
+//! each target could actually be deleted, so the UI can warn about missing
+//! permissions (usually Full Disk Access) before anything is touched.
+
+use super::{error, guard, CacheError};
+use serde::{Deserialize, Serialize};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+const PROBE_FILE_NAME: &str = ".cache-cleaner-preflight";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightResult {
+    pub path: String,
+    pub deletable: bool,
+    pub reason: Option<String>,
+}
+
+impl PreflightResult {
+    fn ok(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            deletable: true,
+            reason: None,
+        }
+    }
+
+    fn blocked(path: &str, reason: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            deletable: false,
+            reason: Some(reason.into()),
+        }
+    }
+}
+
+/// Check every path without deleting anything
+pub fn preflight_paths(paths: &[String]) -> Vec<PreflightResult> {
+    paths.iter().map(|p| preflight_path(p)).collect()
+}
+
+fn preflight_path(path_str: &str) -> PreflightResult {
+    let path = PathBuf::from(path_str);
+
+    if let Err(e) = guard::assert_safe_to_delete(&path) {
+        return PreflightResult::blocked(path_str, e.to_string());
+    }
+    if !path.exists() {
+        return PreflightResult::blocked(path_str, "Path does not exist");
+    }
+
+    // Directory contents are removed from inside, a file through its parent
+    let probe_dir = if path.is_dir() {
+        path.as_path()
+    } else {
+        match path.parent() {
+            Some(parent) => parent,
+            None => return PreflightResult::blocked(path_str, "Path has no parent directory"),
+        }
+    };
+
+    match probe_writable(probe_dir) {
+        Ok(()) => PreflightResult::ok(path_str),
+        Err(e) if error::is_operation_not_permitted(&e) => {
+            PreflightResult::blocked(path_str, CacheError::from_io(probe_dir, e).to_string())
+        }
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => PreflightResult::blocked(
+            path_str,
+            "Permission denied, Full Disk Access may be required",
+        ),
+        Err(e) => PreflightResult::blocked(path_str, e.to_string()),
+    }
+}
+
+/// Create and remove a probe file inside `dir`
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(PROBE_FILE_NAME);
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    #[test]
+    fn test_writable_dir_is_deletable() {
+        let dir = create_test_dir();
+        let target = dir.path().join("cache");
+        fs::create_dir(&target).unwrap();
+        fs::write(target.join("entry"), b"data").unwrap();
+
+        let results = preflight_paths(&[target.to_string_lossy().to_string()]);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].deletable, "{:?}", results[0].reason);
+        assert!(results[0].reason.is_none());
+        // The probe does not leave anything behind
+        assert!(!target.join(PROBE_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn test_writable_file_is_deletable() {
+        let dir = create_test_dir();
+        let file = dir.path().join("cache.bin");
+        fs::write(&file, b"data").unwrap();
+
+        let results = preflight_paths(&[file.to_string_lossy().to_string()]);
+        assert!(results[0].deletable);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_only_dir_is_not_deletable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = create_test_dir();
+        let target = dir.path().join("locked");
+        fs::create_dir(&target).unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o555)).unwrap();
+
+        let results = preflight_paths(&[target.to_string_lossy().to_string()]);
+
+        // Restore permissions so the temp dir can be cleaned up
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(!results[0].deletable);
+        assert!(results[0].reason.as_deref().unwrap().contains("Permission denied"));
+    }
+
+    #[test]
+    fn test_missing_and_protected_paths() {
+        let results = preflight_paths(&[
+            "/nonexistent/cache-cleaner-preflight".to_string(),
+            "/System".to_string(),
+        ]);
+        assert!(!results[0].deletable);
+        assert_eq!(results[0].reason.as_deref(), Some("Path does not exist"));
+        assert!(!results[1].deletable);
+        assert!(results[1].reason.as_deref().unwrap().contains("Refusing to delete"));
+    }
+}
@@ -0,0 +1,249 @@
+//! Poetry and uv caches.
+//!
+//! Poetry keeps downloaded wheels and virtualenv artifacts in
+//! `~/Library/Caches/pypoetry`, moved with `POETRY_CACHE_DIR`; uv follows the
+//! XDG layout even on macOS, `~/.cache/uv`, moved with `UV_CACHE_DIR` or
+//! `XDG_CACHE_HOME`. Cleaning goes through the tool when it is installed so
+//! its own bookkeeping stays consistent, and empties the directory otherwise.
+
+use super::paths::MacPaths;
+use super::{manifest, CacheInfo, CacheType, CleanResult, CleanStrategy};
+use crate::utils::filesystem;
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// `POETRY_CACHE_DIR`, or the default `~/Library/Caches/pypoetry` on macOS
+pub fn poetry_cache_in(home: &Path, env: impl Fn(&str) -> Option<String>) -> PathBuf {
+    match env_path(&env, "POETRY_CACHE_DIR") {
+        Some(path) => path,
+        None => home.join("Library/Caches/pypoetry"),
+    }
+}
+
+/// `UV_CACHE_DIR`, else `uv` in `XDG_CACHE_HOME`, else `~/.cache/uv`
+pub fn uv_cache_in(home: &Path, env: impl Fn(&str) -> Option<String>) -> PathBuf {
+    if let Some(path) = env_path(&env, "UV_CACHE_DIR") {
+        return path;
+    }
+    env_path(&env, "XDG_CACHE_HOME")
+        .unwrap_or_else(|| home.join(".cache"))
+        .join("uv")
+}
+
+/// Absolute path from `key`; a relative value would depend on our working directory
+fn env_path(env: &impl Fn(&str) -> Option<String>, key: &str) -> Option<PathBuf> {
+    env(key).map(PathBuf::from).filter(|p| p.is_absolute())
+}
+
+/// Binary that owns the cache
+fn tool_for(cache_type: &CacheType) -> Option<&'static str> {
+    match cache_type {
+        CacheType::Poetry => Some("poetry"),
+        CacheType::Uv => Some("uv"),
+        _ => None,
+    }
+}
+
+/// Check if the tool owning `cache_type` is installed
+pub fn is_tool_available(cache_type: &CacheType) -> bool {
+    let Some(tool) = tool_for(cache_type) else {
+        return false;
+    };
+    Command::new(tool)
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// The tool's own clean command when it is installed, emptying the directory otherwise
+pub fn clean_method(cache_type: &CacheType, tool_available: bool) -> CleanStrategy {
+    match cache_type.clean_strategy() {
+        CleanStrategy::RunCommand(command) if tool_available => CleanStrategy::RunCommand(command),
+        _ => CleanStrategy::RemoveContents,
+    }
+}
+
+fn cache_path(cache_type: &CacheType) -> Result<PathBuf> {
+    match cache_type {
+        CacheType::Poetry => Ok(MacPaths::poetry_cache()?),
+        CacheType::Uv => Ok(MacPaths::uv_cache()?),
+        _ => Err(anyhow!("Not a Python tool cache type: {:?}", cache_type)),
+    }
+}
+
+pub fn get_python_cache_info(cache_type: CacheType) -> Result<CacheInfo> {
+    let path = cache_path(&cache_type)?;
+    let exists = path.exists();
+    let (size, item_count) = if exists {
+        (
+            filesystem::calculate_dir_size_sync(&path).unwrap_or(0),
+            filesystem::count_items(&path).unwrap_or(0),
+        )
+    } else {
+        (0, 0)
+    };
+
+    Ok(CacheInfo {
+        cache_type,
+        path: path.display().to_string(),
+        size,
+        exists,
+        item_count,
+        size_margin: None,
+        note: None,
+    })
+}
+
+pub fn clean_python_cache(cache_type: CacheType, dry_run: bool, protect_recent: Option<Duration>) -> Result<CleanResult> {
+    let path = cache_path(&cache_type)?;
+    let method = clean_method(&cache_type, is_tool_available(&cache_type));
+    clean_python_cache_at(cache_type, &path, method, dry_run, protect_recent)
+}
+
+fn clean_python_cache_at(
+    cache_type: CacheType,
+    path: &Path,
+    method: CleanStrategy,
+    dry_run: bool,
+    protect_recent: Option<Duration>,
+) -> Result<CleanResult> {
+    if !path.exists() {
+        return Ok(CleanResult {
+            cache_type,
+            freed_bytes: 0,
+            items_removed: 0,
+            success: true,
+            message: "Cache directory does not exist".to_string(),
+            dry_run,
+            ..Default::default()
+        });
+    }
+
+    let size_before = filesystem::calculate_dir_size_sync(path)?;
+    let items_before = filesystem::count_items(path)?;
+
+    if dry_run || filesystem::skip_deletion() {
+        return Ok(CleanResult {
+            cache_type,
+            freed_bytes: size_before,
+            items_removed: items_before,
+            success: true,
+            message: format!("Would clean {} items ({} bytes)", items_before, size_before),
+            dry_run,
+            ..Default::default()
+        });
+    }
+
+    if let CleanStrategy::RunCommand(command) = method {
+        manifest::run_cleaner_command(command)?;
+        let size_after = filesystem::calculate_dir_size_sync(path).unwrap_or(0);
+        let freed_bytes = size_before.saturating_sub(size_after);
+        return Ok(CleanResult {
+            cache_type,
+            freed_bytes,
+            items_removed: items_before,
+            success: true,
+            message: format!("Ran `{}`, freed {} bytes", command, freed_bytes),
+            dry_run: false,
+            ..Default::default()
+        });
+    }
+
+    let outcome = filesystem::empty_dir_measured(path, size_before, items_before, protect_recent);
+    Ok(CleanResult::from_removal(cache_type, &outcome, format!("Successfully cleaned {} items", items_before)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::DeleteMode;
+    use std::collections::HashMap;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn test_poetry_cache_resolution() {
+        let home = Path::new("/Users/test");
+        assert_eq!(poetry_cache_in(home, env(&[])), home.join("Library/Caches/pypoetry"));
+        assert_eq!(
+            poetry_cache_in(home, env(&[("POETRY_CACHE_DIR", "/Volumes/fast/poetry")])),
+            PathBuf::from("/Volumes/fast/poetry")
+        );
+        assert_eq!(
+            poetry_cache_in(home, env(&[("POETRY_CACHE_DIR", "poetry")])),
+            home.join("Library/Caches/pypoetry")
+        );
+    }
+
+    #[test]
+    fn test_uv_cache_resolution() {
+        let home = Path::new("/Users/test");
+        assert_eq!(uv_cache_in(home, env(&[])), home.join(".cache/uv"));
+        assert_eq!(
+            uv_cache_in(home, env(&[("XDG_CACHE_HOME", "/data/cache")])),
+            PathBuf::from("/data/cache/uv")
+        );
+        assert_eq!(
+            uv_cache_in(home, env(&[("XDG_CACHE_HOME", "/data/cache"), ("UV_CACHE_DIR", "/data/uv")])),
+            PathBuf::from("/data/uv")
+        );
+    }
+
+    #[test]
+    fn test_clean_method_prefers_tool() {
+        assert_eq!(
+            clean_method(&CacheType::Uv, true),
+            CleanStrategy::RunCommand("uv cache clean")
+        );
+        assert_eq!(
+            clean_method(&CacheType::Poetry, true),
+            CleanStrategy::RunCommand("poetry cache clear --all .")
+        );
+        assert_eq!(clean_method(&CacheType::Uv, false), CleanStrategy::RemoveContents);
+        assert_eq!(clean_method(&CacheType::Poetry, false), CleanStrategy::RemoveContents);
+    }
+
+    #[test]
+    fn test_fallback_empties_cache() {
+        let root = create_test_dir();
+        let wheels = root.path().join("wheels-v1/pypi/requests");
+        fs::create_dir_all(&wheels).unwrap();
+        fs::write(wheels.join("requests-2.31.0-py3-none-any.whl"), [0u8; 100]).unwrap();
+
+        let result =
+            clean_python_cache_at(CacheType::Uv, root.path(), CleanStrategy::RemoveContents, false, None).unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.freed_bytes, 100);
+        assert!(root.path().exists());
+        assert_eq!(fs::read_dir(root.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_preview_mode_never_deletes() {
+        let root = create_test_dir();
+        let wheel = root.path().join("wheels-v1/requests-2.31.0-py3-none-any.whl");
+        fs::create_dir_all(wheel.parent().unwrap()).unwrap();
+        fs::write(&wheel, [0u8; 100]).unwrap();
+
+        // Neither the fallback nor the tool command runs in preview
+        for method in [CleanStrategy::RemoveContents, CleanStrategy::RunCommand("false")] {
+            let dry_run = DeleteMode::Preview.is_preview();
+            let result = clean_python_cache_at(CacheType::Uv, root.path(), method, dry_run, None).unwrap();
+            assert!(result.dry_run);
+            assert_eq!(result.freed_bytes, 100);
+            assert!(wheel.exists());
+        }
+    }
+}
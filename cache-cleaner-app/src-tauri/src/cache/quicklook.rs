@@ -0,0 +1,165 @@
+//! QuickLook thumbnail cache.
+//!
+//! The cache lives in the per-user temp area,
+//! `/private/var/folders/<xx>/<id>/C/com.apple.QuickLook.thumbnailcache`, so its
+//! path has to be discovered. Cleaning goes through `qlmanage -r cache` rather
+//! than deleting files out from under the QuickLook daemon.
+
+use super::paths::MacPaths;
+use super::{guard, CacheInfo, CacheType, CleanResult};
+use crate::utils::filesystem;
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const THUMBNAIL_CACHE_DIR: &str = "C/com.apple.QuickLook.thumbnailcache";
+
+/// QuickLook thumbnail cache of the current user, if it exists and is readable
+pub fn quicklook_cache_path() -> Option<PathBuf> {
+    find_quicklook_cache_in(&MacPaths::var_folders())
+}
+
+/// Look for `<xx>/<id>/C/com.apple.QuickLook.thumbnailcache` below `var_folders`.
+///
+/// Other users' folders are not readable, so the first readable match is ours.
+pub fn find_quicklook_cache_in(var_folders: &Path) -> Option<PathBuf> {
+    let buckets = std::fs::read_dir(var_folders).ok()?;
+    for bucket in buckets.flatten() {
+        let Ok(user_dirs) = std::fs::read_dir(bucket.path()) else {
+            continue;
+        };
+        for user_dir in user_dirs.flatten() {
+            let candidate = user_dir.path().join(THUMBNAIL_CACHE_DIR);
+            if candidate.is_dir() && std::fs::read_dir(&candidate).is_ok() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+pub async fn get_quicklook_info() -> Result<CacheInfo> {
+    let Some(path) = quicklook_cache_path() else {
+        return Ok(CacheInfo {
+            cache_type: CacheType::QuickLook,
+            path: MacPaths::var_folders().display().to_string(),
+            size: 0,
+            exists: false,
+            item_count: 0,
+            size_margin: None,
+            note: None,
+        });
+    };
+
+    let size = filesystem::calculate_dir_size(&path).await?;
+    let item_count = filesystem::count_items(&path)?;
+    Ok(CacheInfo {
+        cache_type: CacheType::QuickLook,
+        path: path.display().to_string(),
+        size,
+        exists: true,
+        item_count,
+        size_margin: None,
+        note: None,
+    })
+}
+
+pub async fn clean_quicklook_cache(dry_run: bool) -> Result<CleanResult> {
+    let path = quicklook_cache_path();
+    let size_before = match &path {
+        Some(p) => filesystem::calculate_dir_size(p).await.unwrap_or(0),
+        None => 0,
+    };
+
+    if dry_run || filesystem::skip_deletion() {
+        return Ok(CleanResult {
+            cache_type: CacheType::QuickLook,
+            freed_bytes: size_before,
+            items_removed: 0,
+            success: true,
+            message: format!("Would reset QuickLook cache ({} bytes)", size_before),
+            dry_run,
+            ..Default::default()
+        });
+    }
+
+    guard::ensure_commands_enabled()?;
+    let output = Command::new("qlmanage").args(["-r", "cache"]).output()?;
+    let message = handle_qlmanage_output(
+        output.status.success(),
+        &String::from_utf8_lossy(&output.stdout),
+        &String::from_utf8_lossy(&output.stderr),
+    )?;
+
+    let size_after = match &path {
+        Some(p) => filesystem::calculate_dir_size(p).await.unwrap_or(0),
+        None => 0,
+    };
+
+    Ok(CleanResult {
+        cache_type: CacheType::QuickLook,
+        freed_bytes: size_before.saturating_sub(size_after),
+        items_removed: 0,
+        success: true,
+        message,
+        dry_run: false,
+        ..Default::default()
+    })
+}
+
+/// Turn `qlmanage -r cache` output into a result message
+fn handle_qlmanage_output(success: bool, stdout: &str, stderr: &str) -> Result<String> {
+    if !success {
+        let detail = if stderr.trim().is_empty() { stdout } else { stderr };
+        return Err(anyhow!("qlmanage failed: {}", detail.trim()));
+    }
+
+    let stdout = stdout.trim();
+    if stdout.is_empty() {
+        Ok("QuickLook cache reset".to_string())
+    } else {
+        Ok(stdout.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    #[test]
+    fn test_find_quicklook_cache_in_fixture() {
+        let root = create_test_dir();
+        fs::create_dir_all(root.path().join("ab/xyz123/T")).unwrap();
+        let cache = root.path().join("zz/user456").join(THUMBNAIL_CACHE_DIR);
+        fs::create_dir_all(&cache).unwrap();
+        fs::write(cache.join("index.sqlite"), b"thumbs").unwrap();
+
+        assert_eq!(find_quicklook_cache_in(root.path()), Some(cache));
+    }
+
+    #[test]
+    fn test_find_quicklook_cache_missing() {
+        let root = create_test_dir();
+        fs::create_dir_all(root.path().join("ab/xyz123/C")).unwrap();
+        assert_eq!(find_quicklook_cache_in(root.path()), None);
+        assert_eq!(find_quicklook_cache_in(Path::new("/nonexistent/var/folders")), None);
+    }
+
+    #[test]
+    fn test_handle_qlmanage_output() {
+        assert_eq!(
+            handle_qlmanage_output(true, "qlmanage: resetting quicklookd cache\n", "").unwrap(),
+            "qlmanage: resetting quicklookd cache"
+        );
+        assert_eq!(handle_qlmanage_output(true, "", "").unwrap(), "QuickLook cache reset");
+
+        let err = handle_qlmanage_output(false, "", "permission denied\n").unwrap_err();
+        assert_eq!(err.to_string(), "qlmanage failed: permission denied");
+    }
+}
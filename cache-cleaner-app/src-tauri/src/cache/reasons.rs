@@ -0,0 +1,109 @@
+//! Machine-readable reasons behind cleanup suggestions.
+//!
+//! Each code renders to the English string previously shown in the UI, so
+//! `reasons` stays backward compatible while the frontend can localize from
+//! `reason_codes` and their structured payloads.
+
+use crate::utils::format::format_size;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum ReasonCode {
+    LargeSize { bytes: u64 },
+    Size { bytes: u64 },
+    NotAccessedLong { days: u64 },
+    CacheLocation,
+    LogLocation,
+    DevCacheLocation,
+    ContainerExited,
+    ContainerDead,
+    ContainerNeverStarted,
+    NotUsedLong,
+    Dangling,
+    Unused,
+    Orphan,
+    BuildCache { bytes: u64 },
+    SafeToRemove,
+    MostlyLogs,
+    MostlyImages,
+    NodeModulesLike,
+    GitRepository,
+}
+
+impl ReasonCode {
+    /// English description, kept identical to the historical reason strings
+    pub fn message(&self) -> String {
+        match self {
+            ReasonCode::LargeSize { bytes } => format!("Large size: {}", format_size(*bytes)),
+            ReasonCode::Size { bytes } => format!("Size: {}", format_size(*bytes)),
+            ReasonCode::NotAccessedLong { days } => format!("Not accessed for {} days", days),
+            ReasonCode::CacheLocation => "Cache directory".to_string(),
+            ReasonCode::LogLocation => "Log directory".to_string(),
+            ReasonCode::DevCacheLocation => "Development cache".to_string(),
+            ReasonCode::ContainerExited => "Container has exited".to_string(),
+            ReasonCode::ContainerDead => "Container is dead".to_string(),
+            ReasonCode::ContainerNeverStarted => "Container was created but never started".to_string(),
+            ReasonCode::NotUsedLong => "Not used for a long time".to_string(),
+            ReasonCode::Dangling => "Dangling image (untagged)".to_string(),
+            ReasonCode::Unused => "Not used by any container".to_string(),
+            ReasonCode::Orphan => "Orphan volume (not used by any container)".to_string(),
+            ReasonCode::BuildCache { bytes } => format!("Build cache: {}", format_size(*bytes)),
+            ReasonCode::SafeToRemove => "Can be safely removed".to_string(),
+            ReasonCode::MostlyLogs => "Mostly log files".to_string(),
+            ReasonCode::MostlyImages => "Mostly images".to_string(),
+            ReasonCode::NodeModulesLike => "Looks like node_modules".to_string(),
+            ReasonCode::GitRepository => "Git repository, avoid deleting".to_string(),
+        }
+    }
+}
+
+/// Render codes to their display strings, in order
+pub fn reason_messages(codes: &[ReasonCode]) -> Vec<String> {
+    codes.iter().map(ReasonCode::message).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn test_every_code_matches_historical_string() {
+        let cases = [
+            (ReasonCode::LargeSize { bytes: 2 * GIB }, "Large size: 2.0 GiB"),
+            (ReasonCode::Size { bytes: 500 * 1024 * 1024 }, "Size: 500.0 MiB"),
+            (ReasonCode::NotAccessedLong { days: 90 }, "Not accessed for 90 days"),
+            (ReasonCode::CacheLocation, "Cache directory"),
+            (ReasonCode::LogLocation, "Log directory"),
+            (ReasonCode::DevCacheLocation, "Development cache"),
+            (ReasonCode::ContainerExited, "Container has exited"),
+            (ReasonCode::ContainerDead, "Container is dead"),
+            (ReasonCode::ContainerNeverStarted, "Container was created but never started"),
+            (ReasonCode::NotUsedLong, "Not used for a long time"),
+            (ReasonCode::Dangling, "Dangling image (untagged)"),
+            (ReasonCode::Unused, "Not used by any container"),
+            (ReasonCode::Orphan, "Orphan volume (not used by any container)"),
+            (ReasonCode::BuildCache { bytes: GIB }, "Build cache: 1.0 GiB"),
+            (ReasonCode::SafeToRemove, "Can be safely removed"),
+            (ReasonCode::MostlyLogs, "Mostly log files"),
+            (ReasonCode::MostlyImages, "Mostly images"),
+            (ReasonCode::NodeModulesLike, "Looks like node_modules"),
+            (ReasonCode::GitRepository, "Git repository, avoid deleting"),
+        ];
+        for (code, expected) in cases {
+            assert_eq!(code.message(), expected);
+        }
+    }
+
+    #[test]
+    fn test_payload_serialization() {
+        let json = serde_json::to_value(ReasonCode::NotAccessedLong { days: 45 }).unwrap();
+        assert_eq!(json["code"], "not_accessed_long");
+        assert_eq!(json["days"], 45);
+
+        let json = serde_json::to_value(ReasonCode::Dangling).unwrap();
+        assert_eq!(json["code"], "dangling");
+    }
+}
@@ -0,0 +1,136 @@
+//! Short-lived cache of the last full scan.
+//!
+//! The UI asks for a scan on every tab switch; walking the disk each time is
+//! slow, so results younger than the TTL are served from memory. Any clean
+//! operation invalidates the cache.
+
+use super::config::AppConfig;
+use super::{scanner, ScanReport};
+use crate::utils::metrics;
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+pub const DEFAULT_SCAN_CACHE_TTL_SECS: u64 = 30;
+
+static SCAN_RESULTS: OnceLock<ScanResultCache> = OnceLock::new();
+
+pub struct ScanResultCache {
+    ttl: Duration,
+    last: RwLock<Option<(Instant, ScanReport)>>,
+}
+
+impl ScanResultCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            last: RwLock::new(None),
+        }
+    }
+
+    /// Return the cached report if it is younger than the TTL, otherwise run `scan`
+    pub async fn get_or_scan<F, Fut>(&self, force: bool, scan: F) -> ScanReport
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = ScanReport>,
+    {
+        if !force {
+            if let Some((scanned_at, report)) = self.last.read().await.as_ref() {
+                if scanned_at.elapsed() < self.ttl {
+                    metrics::SCAN_CACHE_HITS.incr();
+                    return report.clone();
+                }
+            }
+        }
+
+        metrics::SCAN_CACHE_MISSES.incr();
+        let report = scan().await;
+        *self.last.write().await = Some((Instant::now(), report.clone()));
+        report
+    }
+
+    pub async fn invalidate(&self) {
+        *self.last.write().await = None;
+    }
+}
+
+fn scan_result_cache() -> &'static ScanResultCache {
+    SCAN_RESULTS.get_or_init(|| {
+        let ttl_secs = AppConfig::load()
+            .map(|c| c.scan_cache_ttl_secs)
+            .unwrap_or(DEFAULT_SCAN_CACHE_TTL_SECS);
+        ScanResultCache::new(Duration::from_secs(ttl_secs))
+    })
+}
+
+/// Full scan, served from the cache unless `force` is set or it has expired
+pub async fn cached_scan_all(force: bool) -> ScanReport {
+    scan_result_cache().get_or_scan(force, scanner::scan_all).await
+}
+
+/// Drop the cached scan so the next request re-walks the disk
+pub async fn invalidate_scan_cache() {
+    scan_result_cache().invalidate().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn counting_scan(calls: &AtomicUsize) -> ScanReport {
+        calls.fetch_add(1, Ordering::SeqCst);
+        ScanReport {
+            caches: Vec::new(),
+            errors: vec![format!("scan {}", calls.load(Ordering::SeqCst))],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_second_call_within_ttl_is_cached() {
+        let cache = ScanResultCache::new(Duration::from_secs(60));
+        let calls = AtomicUsize::new(0);
+
+        let first = cache.get_or_scan(false, || counting_scan(&calls)).await;
+        let second = cache.get_or_scan(false, || counting_scan(&calls)).await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first.errors, second.errors);
+    }
+
+    #[tokio::test]
+    async fn test_force_bypasses_cache() {
+        let cache = ScanResultCache::new(Duration::from_secs(60));
+        let calls = AtomicUsize::new(0);
+
+        cache.get_or_scan(false, || counting_scan(&calls)).await;
+        let forced = cache.get_or_scan(true, || counting_scan(&calls)).await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(forced.errors, vec!["scan 2".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entry_rescans() {
+        let cache = ScanResultCache::new(Duration::ZERO);
+        let calls = AtomicUsize::new(0);
+
+        cache.get_or_scan(false, || counting_scan(&calls)).await;
+        cache.get_or_scan(false, || counting_scan(&calls)).await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_forces_rescan() {
+        let cache = ScanResultCache::new(Duration::from_secs(60));
+        let calls = AtomicUsize::new(0);
+
+        cache.get_or_scan(false, || counting_scan(&calls)).await;
+        cache.invalidate().await;
+        cache.get_or_scan(false, || counting_scan(&calls)).await;
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
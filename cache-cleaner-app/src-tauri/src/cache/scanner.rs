@@ -1,12 +1,102 @@
-use super::{CacheInfo, CacheType};
-use crate::cache::{browser_caches, dev_tools, package_managers, paths::MacPaths};
-use crate::utils::filesystem;
+use super::{CacheCategory, CacheInfo, CacheType, ScanReport};
+use crate::cache::{browser_caches, conda, dev_tools, go_caches, manifest, package_managers, paths::MacPaths, python_caches, quicklook};
+use crate::events::{self, AppEvent};
+use crate::utils::home::require_home;
+use crate::utils::{filesystem, size_estimate};
 use anyhow::Result;
+use std::future::Future;
 
-pub async fn scan_all() -> Result<Vec<CacheInfo>> {
-    let mut caches = Vec::new();
-    
-    let cache_types = [
+pub async fn scan_all() -> ScanReport {
+    scan_types(candidate_cache_types()).await
+}
+
+/// Confidence of the size estimates in a fast scan
+const FAST_SCAN_CONFIDENCE: f64 = 0.95;
+
+/// Like `scan_all`, but sizes large caches from a sample of their subtrees.
+/// Estimated entries carry `size_margin`; `scan_all` still walks everything.
+pub async fn scan_all_fast() -> ScanReport {
+    scan_types_with(candidate_cache_types(), |cache_type| async move { scan_cache_fast(&cache_type).await }).await
+}
+
+/// Scan every candidate cache type in `category`
+pub async fn scan_by_category(category: CacheCategory) -> ScanReport {
+    scan_types(candidate_types_in(category)).await
+}
+
+/// Candidate cache types belonging to `category`
+pub fn candidate_types_in(category: CacheCategory) -> Vec<CacheType> {
+    candidate_cache_types()
+        .into_iter()
+        .filter(|t| t.category() == category)
+        .collect()
+}
+
+async fn scan_types(cache_types: Vec<CacheType>) -> ScanReport {
+    scan_types_with(cache_types, |cache_type| async move { scan_cache(&cache_type).await }).await
+}
+
+/// `scan_types` with the per-type scan supplied by the caller. Each scan runs
+/// in its own task, so a panic in one is reported as that type's error while
+/// the remaining types are still scanned.
+async fn scan_types_with<F, Fut>(cache_types: Vec<CacheType>, scan: F) -> ScanReport
+where
+    F: Fn(CacheType) -> Fut,
+    Fut: Future<Output = Result<CacheInfo>> + Send + 'static,
+{
+    let mut report = ScanReport::default();
+
+    // Without a home directory almost nothing can be resolved; say so once
+    if let Err(e) = require_home() {
+        report.errors.push(e.to_string());
+        return report;
+    }
+
+    let total = cache_types.len();
+    for (index, cache_type) in cache_types.into_iter().enumerate() {
+        events::bus().publish(AppEvent::ScanProgress {
+            scanned: index,
+            total,
+            current: format!("{:?}", cache_type),
+        });
+        match tokio::spawn(scan(cache_type.clone())).await {
+            Ok(Ok(info)) => {
+                if info.exists && info.size > 0 {
+                    report.caches.push(info);
+                }
+            }
+            Ok(Err(e)) => report.errors.push(format!("{:?}: {}", cache_type, e)),
+            Err(e) => {
+                let reason = panic_reason(e);
+                eprintln!("[Rust] scan of {:?} failed: {}", cache_type, reason);
+                report.errors.push(format!("{:?}: {}", cache_type, reason));
+            }
+        }
+    }
+
+    events::bus().publish(AppEvent::ScanCompleted {
+        caches_found: report.caches.len(),
+        errors: report.errors.len(),
+    });
+    report
+}
+
+fn panic_reason(error: tokio::task::JoinError) -> String {
+    if !error.is_panic() {
+        return format!("scan was cancelled: {}", error);
+    }
+    let payload = error.into_panic();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown cause".to_string());
+    format!("scan panicked: {}", message)
+}
+
+/// Built-in cache types followed by the ones defined in the cache manifest
+pub fn candidate_cache_types() -> Vec<CacheType> {
+    let mut cache_types = vec![
         CacheType::Npm,
         CacheType::Chrome,
         CacheType::CacheDir,
@@ -18,23 +108,29 @@ pub async fn scan_all() -> Result<Vec<CacheInfo>> {
         CacheType::Yarn,
         CacheType::Pnpm,
         CacheType::Pip,
+        CacheType::Poetry,
+        CacheType::Uv,
         CacheType::CocoaPods,
         CacheType::Gradle,
         CacheType::Cargo,
+        CacheType::GoBuild,
+        CacheType::GoModCache,
         CacheType::XcodeDerivedData,
         CacheType::XcodeArchives,
         CacheType::XcodeSimulators,
+        CacheType::QuickLook,
     ];
-    
-    for cache_type in cache_types {
-        if let Ok(info) = scan_cache(&cache_type).await {
-            if info.exists && info.size > 0 {
-                caches.push(info);
-            }
-        }
+    // Only offered on machines with a conda install, whose prefix varies
+    if conda::find_install().is_some() {
+        cache_types.push(CacheType::Conda);
     }
-    
-    Ok(caches)
+
+    cache_types.extend(
+        manifest::manifest_entries()
+            .iter()
+            .map(|entry| CacheType::Manifest(entry.id.clone())),
+    );
+    cache_types
 }
 
 pub async fn scan_cache(cache_type: &CacheType) -> Result<CacheInfo> {
@@ -51,76 +147,32 @@ pub async fn scan_cache(cache_type: &CacheType) -> Result<CacheInfo> {
         CacheType::CocoaPods => Ok(package_managers::get_cocoapods_cache_info()),
         CacheType::Gradle => Ok(package_managers::get_gradle_cache_info()),
         CacheType::Cargo => Ok(package_managers::get_cargo_cache_info()),
+        CacheType::GoBuild | CacheType::GoModCache => go_caches::get_go_cache_info(cache_type.clone()),
+        CacheType::Poetry | CacheType::Uv => python_caches::get_python_cache_info(cache_type.clone()),
+        CacheType::Conda => conda::get_conda_cache_info(),
         
         // Development tools
         CacheType::XcodeDerivedData => Ok(dev_tools::get_xcode_derived_data_info()),
         CacheType::XcodeArchives => Ok(dev_tools::get_xcode_archives_info()),
         CacheType::XcodeSimulators => Ok(dev_tools::get_xcode_simulators_info()),
         
+        // Manifest-defined caches
+        CacheType::Manifest(id) => {
+            let entry = manifest::find_entry(id)
+                .ok_or_else(|| anyhow::anyhow!("Unknown manifest cache: {}", id))?;
+            manifest::scan_entry(entry).await
+        }
+
+        CacheType::QuickLook => quicklook::get_quicklook_info().await,
+        
         // Existing cache types
         CacheType::Cursor => {
-            let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
-            let cursor_cache_paths = get_cursor_cache_paths(&home);
-            
-            let mut total_size = 0u64;
-            let mut item_count = 0usize;
-            let mut exists = false;
-            let mut existing_paths = Vec::new();
-            
-            for path in &cursor_cache_paths {
-                if path.exists() {
-                    exists = true;
-                    existing_paths.push(path.display().to_string());
-                    total_size += filesystem::calculate_dir_size(path).await?;
-                    item_count += filesystem::count_items(path)?;
-                }
-            }
-            
-            let display_path = if existing_paths.is_empty() {
-                home.join("Library/Caches/Cursor").display().to_string()
-            } else {
-                existing_paths.join("\n")
-            };
-            
-            Ok(CacheInfo {
-                cache_type: cache_type.clone(),
-                path: display_path,
-                size: total_size,
-                exists,
-                item_count,
-            })
+            let home = require_home()?;
+            scan_cursor_in(&home, is_cursor_installed(&home)).await
         }
         CacheType::VSCode => {
-            let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
-            let vscode_cache_paths = get_vscode_cache_paths(&home);
-            
-            let mut total_size = 0u64;
-            let mut item_count = 0usize;
-            let mut exists = false;
-            let mut existing_paths = Vec::new();
-            
-            for path in &vscode_cache_paths {
-                if path.exists() {
-                    exists = true;
-                    existing_paths.push(path.display().to_string());
-                    total_size += filesystem::calculate_dir_size(path).await?;
-                    item_count += filesystem::count_items(path)?;
-                }
-            }
-            
-            let display_path = if existing_paths.is_empty() {
-                home.join("Library/Application Support/Code/Cache/Cache_Data").display().to_string()
-            } else {
-                existing_paths.join("\n")
-            };
-            
-            Ok(CacheInfo {
-                cache_type: cache_type.clone(),
-                path: display_path,
-                size: total_size,
-                exists,
-                item_count,
-            })
+            let home = require_home()?;
+            scan_vscode_in(&home).await
         }
         _ => {
             let path = get_cache_path(cache_type)?;
@@ -140,15 +192,48 @@ pub async fn scan_cache(cache_type: &CacheType) -> Result<CacheInfo> {
                 size,
                 exists,
                 item_count,
+                size_margin: None,
+                note: None,
             })
         }
     }
 }
 
+/// `scan_cache`, estimating caches that live in one large directory
+async fn scan_cache_fast(cache_type: &CacheType) -> Result<CacheInfo> {
+    match cache_type {
+        CacheType::Npm | CacheType::CacheDir |
+        CacheType::Yarn | CacheType::Pnpm | CacheType::Pip | CacheType::Poetry | CacheType::Uv |
+        CacheType::CocoaPods | CacheType::Gradle | CacheType::Cargo |
+        CacheType::GoBuild | CacheType::GoModCache | CacheType::XcodeDerivedData => {
+            let path = get_cache_path(cache_type)?;
+            if !path.exists() {
+                return scan_cache(cache_type).await;
+            }
+            let path_str = path.display().to_string();
+            let estimate = tokio::task::spawn_blocking(move || {
+                size_estimate::estimate_dir(&path, FAST_SCAN_CONFIDENCE)
+            })
+            .await??;
+
+            Ok(CacheInfo {
+                cache_type: cache_type.clone(),
+                path: path_str,
+                size: estimate.bytes,
+                exists: true,
+                item_count: estimate.items,
+                size_margin: (!estimate.is_exact()).then_some(estimate.margin),
+                note: None,
+            })
+        }
+        _ => scan_cache(cache_type).await,
+    }
+}
+
 pub async fn get_size(cache_type: &CacheType) -> Result<u64> {
     match cache_type {
         CacheType::Cursor => {
-            let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+            let home = require_home()?;
             let cursor_cache_paths = get_cursor_cache_paths(&home);
             
             let mut total_size = 0u64;
@@ -160,7 +245,7 @@ pub async fn get_size(cache_type: &CacheType) -> Result<u64> {
             Ok(total_size)
         }
         CacheType::VSCode => {
-            let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+            let home = require_home()?;
             let vscode_cache_paths = get_vscode_cache_paths(&home);
             
             let mut total_size = 0u64;
@@ -186,26 +271,32 @@ pub async fn get_size(cache_type: &CacheType) -> Result<u64> {
     }
 }
 
+/// Name prefixes of Cursor's folders in `~/Library/Caches`. The app ships
+/// under a ToDesktop bundle ID today; the others cover a rebrand.
+const CURSOR_BUNDLE_PREFIXES: &[&str] = &["com.todesktop.", "com.cursor.", "Cursor"];
+
+const CURSOR_APP: &str = "Applications/Cursor.app";
+
+/// Whether Cursor is in `/Applications` or `~/Applications`
+fn is_cursor_installed(home: &std::path::Path) -> bool {
+    std::path::Path::new("/").join(CURSOR_APP).exists() || home.join(CURSOR_APP).exists()
+}
+
 /// Get all safe Cursor cache directories
-fn get_cursor_cache_paths(home: &std::path::Path) -> Vec<std::path::PathBuf> {
+pub(crate) fn get_cursor_cache_paths(home: &std::path::Path) -> Vec<std::path::PathBuf> {
     let mut paths = Vec::new();
     
-    // Main cache in Library/Caches (todesktop bundle ID)
+    // Main caches in Library/Caches, matched by bundle prefix
     let caches_dir = home.join("Library/Caches");
     if let Ok(entries) = std::fs::read_dir(&caches_dir) {
         for entry in entries.filter_map(|e| e.ok()) {
             let name = entry.file_name().to_string_lossy().to_string();
-            if name.starts_with("com.todesktop.") && entry.path().is_dir() {
+            if CURSOR_BUNDLE_PREFIXES.iter().any(|prefix| name.starts_with(prefix)) && entry.path().is_dir() {
                 paths.push(entry.path());
             }
         }
     }
-    
-    // Secondary cache directory
-    let cursor_cache = caches_dir.join("Cursor");
-    if cursor_cache.exists() {
-        paths.push(cursor_cache);
-    }
+    paths.sort();
     
     // Safe directories in Application Support
     let app_support = home.join("Library/Application Support/Cursor");
@@ -219,27 +310,98 @@ fn get_cursor_cache_paths(home: &std::path::Path) -> Vec<std::path::PathBuf> {
     paths
 }
 
-/// Get all VSCode cache directories
-fn get_vscode_cache_paths(home: &std::path::Path) -> Vec<std::path::PathBuf> {
-    let mut paths = Vec::new();
-    
-    // Cache/Cache_Data directory
-    let cache_data = home.join("Library/Application Support/Code/Cache/Cache_Data");
-    if cache_data.exists() {
-        paths.push(cache_data);
+/// Cursor caches summed across every cache directory under `home`. When
+/// Cursor is `installed` but nothing matched, the result carries a note
+/// rather than passing for an empty cache.
+async fn scan_cursor_in(home: &std::path::Path, installed: bool) -> Result<CacheInfo> {
+    let mut total_size = 0u64;
+    let mut item_count = 0usize;
+    let mut existing_paths = Vec::new();
+
+    for path in get_cursor_cache_paths(home) {
+        total_size += filesystem::calculate_dir_size(&path).await?;
+        item_count += filesystem::count_items(&path)?;
+        existing_paths.push(path.display().to_string());
     }
-    
-    // User/workspaceStorage directory
-    let workspace_storage = home.join("Library/Application Support/Code/User/workspaceStorage");
-    if workspace_storage.exists() {
-        paths.push(workspace_storage);
+
+    let note = (existing_paths.is_empty() && installed).then(|| {
+        let patterns: Vec<String> = CURSOR_BUNDLE_PREFIXES.iter().map(|prefix| format!("{}*", prefix)).collect();
+        format!(
+            "Cursor is installed but no cache directory matching {} was found in ~/Library/Caches; its bundle ID may have changed",
+            patterns.join(", ")
+        )
+    });
+    let display_path = if existing_paths.is_empty() {
+        home.join("Library/Caches/Cursor").display().to_string()
+    } else {
+        existing_paths.join("\n")
+    };
+
+    Ok(CacheInfo {
+        cache_type: CacheType::Cursor,
+        path: display_path,
+        size: total_size,
+        exists: !existing_paths.is_empty(),
+        item_count,
+        size_margin: None,
+        note,
+    })
+}
+
+/// VS Code caches summed across every cache directory under `home`
+async fn scan_vscode_in(home: &std::path::Path) -> Result<CacheInfo> {
+    let mut total_size = 0u64;
+    let mut item_count = 0usize;
+    let mut existing_paths = Vec::new();
+
+    for path in get_vscode_cache_paths(home) {
+        total_size += filesystem::calculate_dir_size(&path).await?;
+        item_count += filesystem::count_items(&path)?;
+        existing_paths.push(path.display().to_string());
     }
-    
-    paths
+
+    let display_path = if existing_paths.is_empty() {
+        home.join(VSCODE_SUPPORT_DIR).display().to_string()
+    } else {
+        existing_paths.join("\n")
+    };
+
+    Ok(CacheInfo {
+        cache_type: CacheType::VSCode,
+        path: display_path,
+        size: total_size,
+        exists: !existing_paths.is_empty(),
+        item_count,
+        size_margin: None,
+        note: None,
+    })
 }
 
-fn get_cache_path(cache_type: &CacheType) -> Result<std::path::PathBuf> {
-    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+const VSCODE_SUPPORT_DIR: &str = "Library/Application Support/Code";
+
+/// Regenerable subdirectories of VS Code's support folder
+const VSCODE_CACHE_SUBDIRS: &[&str] = &[
+    "Cache/Cache_Data",
+    "CachedData",
+    "CachedExtensions",
+    "CachedExtensionVSIXs",
+    "GPUCache",
+    "logs",
+    "User/workspaceStorage",
+];
+
+/// Get all existing VSCode cache directories
+pub(crate) fn get_vscode_cache_paths(home: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let support = home.join(VSCODE_SUPPORT_DIR);
+    VSCODE_CACHE_SUBDIRS
+        .iter()
+        .map(|subdir| support.join(subdir))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+pub fn get_cache_path(cache_type: &CacheType) -> Result<std::path::PathBuf> {
+    let home = require_home()?;
     
     Ok(match cache_type {
         CacheType::Npm => home.join(".npm"),
@@ -248,22 +410,29 @@ fn get_cache_path(cache_type: &CacheType) -> Result<std::path::PathBuf> {
 
         CacheType::VSCode => home.join("Library/Application Support/Code/Cache/Cache_Data"),
         CacheType::Cursor => home.join("Library/Application Support/Cursor/User/globalStorage"),
-        CacheType::Safari => MacPaths::safari_cache(),
-        CacheType::Firefox => MacPaths::firefox_profiles(),
-        CacheType::Arc => MacPaths::arc_cache(),
-        CacheType::Yarn => MacPaths::yarn_cache(),
-        CacheType::Pnpm => MacPaths::pnpm_cache(),
-        CacheType::Pip => MacPaths::pip_cache(),
-        CacheType::CocoaPods => MacPaths::cocoapods_cache(),
-        CacheType::Gradle => MacPaths::gradle_cache(),
-        CacheType::Cargo => MacPaths::cargo_cache(),
-        CacheType::XcodeDerivedData => MacPaths::xcode_derived_data(),
-        CacheType::XcodeArchives => MacPaths::xcode_archives(),
-        CacheType::XcodeSimulators => MacPaths::xcode_simulators(),
+        CacheType::Safari => MacPaths::safari_cache()?,
+        CacheType::Firefox => MacPaths::firefox_profiles()?,
+        CacheType::Arc => MacPaths::arc_cache()?,
+        CacheType::Yarn => MacPaths::yarn_cache()?,
+        CacheType::Pnpm => MacPaths::pnpm_cache()?,
+        CacheType::Pip => MacPaths::pip_cache()?,
+        CacheType::Poetry => MacPaths::poetry_cache()?,
+        CacheType::Uv => MacPaths::uv_cache()?,
+        CacheType::Conda => conda::conda_pkgs_path()?,
+        CacheType::CocoaPods => MacPaths::cocoapods_cache()?,
+        CacheType::Gradle => MacPaths::gradle_cache()?,
+        CacheType::Cargo => MacPaths::cargo_cache()?,
+        CacheType::GoBuild => MacPaths::go_build_cache()?,
+        CacheType::GoModCache => MacPaths::go_mod_cache()?,
+        CacheType::XcodeDerivedData => MacPaths::xcode_derived_data()?,
+        CacheType::XcodeArchives => MacPaths::xcode_archives()?,
+        CacheType::XcodeSimulators => MacPaths::xcode_simulators()?,
         CacheType::SystemCaches => MacPaths::system_caches(),
-        CacheType::UserLogs => MacPaths::user_logs(),
+        CacheType::UserLogs => MacPaths::user_logs()?,
         CacheType::TempFiles => MacPaths::tmp(),
-        CacheType::IosBackups => MacPaths::ios_backups(),
+        CacheType::IosBackups => MacPaths::ios_backups()?,
+        CacheType::QuickLook => quicklook::quicklook_cache_path()
+            .ok_or_else(|| anyhow::anyhow!("QuickLook cache not found"))?,
         // Docker cache types don't have file paths - they're managed by Docker CLI
         CacheType::DockerContainers | 
         CacheType::DockerImages | 
@@ -271,5 +440,152 @@ fn get_cache_path(cache_type: &CacheType) -> Result<std::path::PathBuf> {
         CacheType::DockerBuildCache => {
             return Err(anyhow::anyhow!("Docker cache types should be managed through docker module"))
         }
+        CacheType::Manifest(id) => {
+            return manifest::find_entry(id)
+                .ok_or_else(|| anyhow::anyhow!("Unknown manifest cache: {}", id))?
+                .resolved_path();
+        }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_cache_types_include_builtin() {
+        let candidates = candidate_cache_types();
+        assert!(candidates.iter().any(|c| matches!(c, CacheType::Npm)));
+        assert!(candidates.iter().any(|c| matches!(c, CacheType::XcodeSimulators)));
+    }
+
+    #[test]
+    fn test_candidate_cache_types_include_manifest() {
+        let candidates = candidate_cache_types();
+        assert!(candidates
+            .iter()
+            .any(|c| matches!(c, CacheType::Manifest(id) if id == "homebrew")));
+    }
+
+    #[tokio::test]
+    async fn test_scan_cache_manifest_entry() {
+        let info = scan_cache(&CacheType::Manifest("homebrew".to_string())).await.unwrap();
+        assert!(matches!(info.cache_type, CacheType::Manifest(ref id) if id == "homebrew"));
+        assert!(info.path.ends_with("Library/Caches/Homebrew"));
+    }
+
+    #[tokio::test]
+    async fn test_scan_cache_unknown_manifest_entry() {
+        assert!(scan_cache(&CacheType::Manifest("missing".to_string())).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scan_all_reports_instead_of_failing() {
+        let report = scan_all().await;
+        for info in &report.caches {
+            assert!(info.exists);
+            assert_ne!(info.path, "/");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panicking_scan_reported_as_error() {
+        let report = scan_types_with(
+            vec![CacheType::Npm, CacheType::Yarn, CacheType::Pip],
+            |cache_type| async move {
+                if cache_type == CacheType::Yarn {
+                    panic!("unreadable filesystem");
+                }
+                Ok(CacheInfo {
+                    path: format!("/cache/{:?}", cache_type),
+                    cache_type,
+                    size: 100,
+                    exists: true,
+                    item_count: 1,
+                    size_margin: None,
+                    note: None,
+                })
+            },
+        )
+        .await;
+
+        let scanned: Vec<&CacheType> = report.caches.iter().map(|info| &info.cache_type).collect();
+        assert_eq!(scanned, vec![&CacheType::Npm, &CacheType::Pip]);
+        assert_eq!(report.errors, vec!["Yarn: scan panicked: unreadable filesystem"]);
+    }
+
+    fn write_file(path: &std::path::Path, len: usize) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, vec![0u8; len]).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_scan_vscode_sums_cache_subdirs() {
+        let home = tempfile::tempdir().unwrap();
+        let support = home.path().join(VSCODE_SUPPORT_DIR);
+        write_file(&support.join("CachedData/abc/chrome/js/index"), 4000);
+        write_file(&support.join("CachedExtensions/ext"), 2000);
+        write_file(&support.join("GPUCache/data_0"), 1000);
+        write_file(&support.join("logs/20240101/main.log"), 500);
+        write_file(&support.join("WebStorage/1/CacheStorage/index"), 10);
+        // Settings are not cache
+        write_file(&support.join("User/settings.json"), 300);
+
+        let info = scan_vscode_in(home.path()).await.unwrap();
+        assert!(info.exists);
+        assert_eq!(info.size, 4000 + 2000 + 1000 + 500);
+        assert_eq!(info.path.lines().count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_scan_vscode_missing() {
+        let home = tempfile::tempdir().unwrap();
+        let info = scan_vscode_in(home.path()).await.unwrap();
+        assert!(!info.exists);
+        assert_eq!(info.size, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cursor_bundle_prefixes() {
+        for bundle in ["com.todesktop.230313mzl4w4u92", "com.cursor.editor", "Cursor"] {
+            let home = tempfile::tempdir().unwrap();
+            let caches = home.path().join("Library/Caches");
+            write_file(&caches.join(bundle).join("Cache_Data/f_000001"), 3000);
+            write_file(&caches.join("com.apple.Safari/Cache.db"), 100);
+
+            let paths = get_cursor_cache_paths(home.path());
+            assert_eq!(paths, vec![caches.join(bundle)], "{}", bundle);
+
+            let info = scan_cursor_in(home.path(), true).await.unwrap();
+            assert!(info.exists);
+            assert_eq!(info.size, 3000);
+            assert!(info.note.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cursor_installed_without_caches_has_note() {
+        let home = tempfile::tempdir().unwrap();
+        write_file(&home.path().join("Library/Caches/com.unknown.cursor/data"), 100);
+
+        let info = scan_cursor_in(home.path(), true).await.unwrap();
+        assert!(!info.exists);
+        assert!(info.note.unwrap().contains("com.todesktop.*"));
+        assert!(scan_cursor_in(home.path(), false).await.unwrap().note.is_none());
+    }
+
+    #[test]
+    fn test_candidate_types_in_category() {
+        let browsers = candidate_types_in(CacheCategory::Browser);
+        assert_eq!(browsers.len(), 4);
+        assert!(browsers.iter().all(|t| t.category() == CacheCategory::Browser));
+    }
+
+    #[tokio::test]
+    async fn test_scan_by_category_only_returns_category() {
+        let report = scan_by_category(CacheCategory::PackageManager).await;
+        for info in &report.caches {
+            assert_eq!(info.cache_type.category(), CacheCategory::PackageManager);
+        }
+    }
+}
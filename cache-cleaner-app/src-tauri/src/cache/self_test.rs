@@ -0,0 +1,226 @@
+//! Self-test / health check used for support diagnostics.
+//!
+//! Consolidates the scattered environment checks (home access, config,
+//! Docker, Full Disk Access) into a single report.
+
+use super::config::AppConfig;
+use super::docker;
+use crate::utils::home::app_data_dir;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestCheck {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelfTestReport {
+    pub checks: Vec<SelfTestCheck>,
+    pub all_ok: bool,
+}
+
+impl SelfTestCheck {
+    fn new(name: &str, ok: bool, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            ok,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Run all health checks against the current user's home directory
+pub async fn run_self_test() -> Result<SelfTestReport> {
+    let home = match dirs::home_dir() {
+        Some(h) => h,
+        None => {
+            return Ok(SelfTestReport {
+                checks: vec![SelfTestCheck::new("home_accessible", false, "Cannot find home directory")],
+                all_ok: false,
+            });
+        }
+    };
+
+    let data_dir = app_data_dir().unwrap_or_else(|_| home.join(".cache-cleaner"));
+    let mut checks = filesystem_checks(&home, &data_dir);
+    checks.push(check_docker().await);
+    checks.push(check_full_disk_access(&home));
+
+    let all_ok = checks.iter().all(|c| c.ok);
+    Ok(SelfTestReport { checks, all_ok })
+}
+
+/// Checks that only touch the filesystem under `home` and `data_dir`
+fn filesystem_checks(home: &Path, data_dir: &Path) -> Vec<SelfTestCheck> {
+    vec![
+        check_home(home),
+        check_config_readable(&data_dir.join("config.json")),
+        check_config_writable(&data_dir.join("config.json")),
+        check_data_dir_writable(data_dir),
+    ]
+}
+
+fn check_home(home: &Path) -> SelfTestCheck {
+    match std::fs::read_dir(home) {
+        Ok(_) => SelfTestCheck::new("home_accessible", true, home.display().to_string()),
+        Err(e) => SelfTestCheck::new("home_accessible", false, format!("{}: {}", home.display(), e)),
+    }
+}
+
+fn check_config_readable(config_path: &Path) -> SelfTestCheck {
+    if !config_path.exists() {
+        return SelfTestCheck::new("config_readable", true, "No config file yet, defaults will be used");
+    }
+
+    match std::fs::read_to_string(config_path) {
+        Ok(content) => match serde_json::from_str::<AppConfig>(&content) {
+            Ok(config) => SelfTestCheck::new(
+                "config_readable",
+                true,
+                format!("{} custom scanner(s) configured", config.custom_scanners.len()),
+            ),
+            Err(e) => SelfTestCheck::new("config_readable", false, format!("Invalid config: {}", e)),
+        },
+        Err(e) => SelfTestCheck::new("config_readable", false, format!("{}: {}", config_path.display(), e)),
+    }
+}
+
+fn check_config_writable(config_path: &Path) -> SelfTestCheck {
+    if !config_path.exists() {
+        return SelfTestCheck::new("config_writable", true, "Config file will be created on first save");
+    }
+
+    match std::fs::OpenOptions::new().append(true).open(config_path) {
+        Ok(_) => SelfTestCheck::new("config_writable", true, config_path.display().to_string()),
+        Err(e) => SelfTestCheck::new("config_writable", false, format!("{}: {}", config_path.display(), e)),
+    }
+}
+
+fn check_data_dir_writable(data_dir: &Path) -> SelfTestCheck {
+    if let Err(e) = std::fs::create_dir_all(data_dir) {
+        return SelfTestCheck::new("data_dir_writable", false, format!("{}: {}", data_dir.display(), e));
+    }
+
+    // Probe by writing and removing a temporary file
+    let probe = data_dir.join(".self-test-probe");
+    match std::fs::write(&probe, b"ok") {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            SelfTestCheck::new("data_dir_writable", true, data_dir.display().to_string())
+        }
+        Err(e) => SelfTestCheck::new("data_dir_writable", false, format!("{}: {}", data_dir.display(), e)),
+    }
+}
+
+async fn check_docker() -> SelfTestCheck {
+    if !docker::is_docker_installed() {
+        return SelfTestCheck::new("docker_available", false, "Docker is not installed");
+    }
+    if docker::is_docker_running().await {
+        SelfTestCheck::new("docker_available", true, "Docker daemon is running")
+    } else {
+        SelfTestCheck::new("docker_available", false, "Docker is installed but the daemon is not running")
+    }
+}
+
+fn check_full_disk_access(home: &Path) -> SelfTestCheck {
+    // Same probe as permissions::has_full_disk_access, relative to `home`
+    if std::fs::read_dir(home.join("Library/Safari")).is_ok() {
+        SelfTestCheck::new("full_disk_access", true, "Full Disk Access granted")
+    } else {
+        SelfTestCheck::new(
+            "full_disk_access",
+            false,
+            "Grant Full Disk Access in System Settings > Privacy & Security",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::home::set_data_dir_for_thread;
+    use std::fs;
+
+    fn create_test_dir() -> tempfile::TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    #[test]
+    fn test_filesystem_checks_pass_with_temp_home() {
+        let home = create_test_dir();
+        let checks = filesystem_checks(home.path(), &home.path().join(".cache-cleaner"));
+        assert_eq!(checks.len(), 4);
+        for check in &checks {
+            assert!(check.ok, "{} failed: {}", check.name, check.detail);
+        }
+        // The probe file is cleaned up again
+        assert!(!home.path().join(".cache-cleaner/.self-test-probe").exists());
+    }
+
+    #[test]
+    fn test_filesystem_checks_with_existing_config() {
+        let home = create_test_dir();
+        let data_dir = home.path().join(".cache-cleaner");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(data_dir.join("config.json"), r#"{"custom_scanners": []}"#).unwrap();
+
+        let checks = filesystem_checks(home.path(), &data_dir);
+        assert!(checks.iter().all(|c| c.ok));
+    }
+
+    #[test]
+    fn test_invalid_config_is_reported() {
+        let home = create_test_dir();
+        let data_dir = home.path().join(".cache-cleaner");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::write(data_dir.join("config.json"), "not json").unwrap();
+
+        let checks = filesystem_checks(home.path(), &data_dir);
+        let readable = checks.iter().find(|c| c.name == "config_readable").unwrap();
+        assert!(!readable.ok);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_only_config_dir_fails() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let home = create_test_dir();
+        let data_dir = home.path().join(".cache-cleaner");
+        fs::create_dir_all(&data_dir).unwrap();
+        fs::set_permissions(&data_dir, fs::Permissions::from_mode(0o555)).unwrap();
+
+        let checks = filesystem_checks(home.path(), &data_dir);
+        let writable = checks.iter().find(|c| c.name == "data_dir_writable").unwrap();
+
+        // Restore permissions so the temp dir can be cleaned up
+        fs::set_permissions(&data_dir, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(!writable.ok);
+    }
+
+    #[test]
+    fn test_check_home_missing() {
+        let check = check_home(Path::new("/nonexistent/home"));
+        assert!(!check.ok);
+    }
+
+    #[tokio::test]
+    async fn test_run_self_test_reports_all_checks() {
+        // Keep the probe writes out of the real ~/.cache-cleaner
+        let data_dir = create_test_dir();
+        set_data_dir_for_thread(Some(data_dir.path().to_path_buf()));
+        let report = run_self_test().await.unwrap();
+        set_data_dir_for_thread(None);
+
+        let names: Vec<&str> = report.checks.iter().map(|c| c.name.as_str()).collect();
+        assert!(names.contains(&"home_accessible"));
+        assert!(names.contains(&"docker_available"));
+        assert!(names.contains(&"full_disk_access"));
+        assert_eq!(report.all_ok, report.checks.iter().all(|c| c.ok));
+    }
+}
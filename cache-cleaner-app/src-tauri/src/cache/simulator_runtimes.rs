@@ -0,0 +1,294 @@
+//! Simulator runtimes managed by `xcrun simctl`.
+//!
+//! Runtimes live in system-owned disk images, so they are listed and deleted
+//! through `simctl` rather than the filesystem. Runtimes simctl reports as
+//! unavailable or unknown can no longer boot a device and are auto-selected.
+
+use super::guard;
+use crate::utils::filesystem;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// States simctl uses for runtimes that can no longer be used
+const UNUSABLE_STATES: &[&str] = &["unavailable", "unknown", "unusable"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeEntry {
+    pub identifier: String,
+    pub version: String,
+    pub state: String,
+    pub size: u64,
+    pub auto_select: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatorRuntimesCleanResult {
+    pub total_freed_bytes: u64,
+    pub items_removed: usize,
+    pub success: bool,
+    pub message: String,
+}
+
+/// Check if `xcrun simctl` is available (Xcode or its command line tools)
+pub fn is_simctl_available() -> bool {
+    Command::new("xcrun")
+        .args(["--find", "simctl"])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+pub async fn scan_simulator_runtimes() -> Result<Vec<RuntimeEntry>> {
+    tokio::task::spawn_blocking(scan_simulator_runtimes_sync).await?
+}
+
+fn scan_simulator_runtimes_sync() -> Result<Vec<RuntimeEntry>> {
+    if !is_simctl_available() {
+        return Ok(Vec::new());
+    }
+
+    // `runtime list` (Xcode 15+) reports disk image sizes; older Xcode only
+    // has `list runtimes`, where the size comes from the runtime bundle
+    let mut runtimes = match simctl_json(&["runtime", "list", "-j"]) {
+        Ok(json) => parse_runtime_list(&json)?,
+        Err(_) => {
+            let json = simctl_json(&["list", "runtimes", "-j"])?;
+            let mut runtimes = Vec::new();
+            for (entry, bundle_path) in parse_list_runtimes(&json)? {
+                let size = bundle_path
+                    .map(|p| filesystem::calculate_dir_size_sync(Path::new(&p)).unwrap_or(0))
+                    .unwrap_or(0);
+                runtimes.push(RuntimeEntry { size, ..entry });
+            }
+            runtimes
+        }
+    };
+
+    runtimes.sort_by_key(|runtime| Reverse(runtime.size));
+    Ok(runtimes)
+}
+
+/// Delete the given runtimes with `simctl runtime delete`
+pub async fn delete_simulator_runtimes(identifiers: Vec<String>) -> Result<SimulatorRuntimesCleanResult> {
+    guard::ensure_deletion_enabled()?;
+    tokio::task::spawn_blocking(move || delete_simulator_runtimes_sync(&identifiers)).await?
+}
+
+fn delete_simulator_runtimes_sync(identifiers: &[String]) -> Result<SimulatorRuntimesCleanResult> {
+    if !is_simctl_available() {
+        return Ok(SimulatorRuntimesCleanResult {
+            total_freed_bytes: 0,
+            items_removed: 0,
+            success: false,
+            message: "xcrun simctl is not available".to_string(),
+        });
+    }
+
+    let sizes: HashMap<String, u64> = scan_simulator_runtimes_sync()?
+        .into_iter()
+        .map(|r| (r.identifier, r.size))
+        .collect();
+
+    let mut total_freed = 0u64;
+    let mut items_removed = 0usize;
+    let mut errors = Vec::new();
+
+    for identifier in identifiers {
+        // Only delete runtimes simctl itself reported
+        let Some(size) = sizes.get(identifier) else {
+            errors.push(format!("{}: runtime not found", identifier));
+            continue;
+        };
+        if filesystem::skip_deletion() {
+            total_freed += size;
+            items_removed += 1;
+            continue;
+        }
+
+        let output = Command::new("xcrun")
+            .args(["simctl", "runtime", "delete", identifier])
+            .output()?;
+        if output.status.success() {
+            total_freed += size;
+            items_removed += 1;
+        } else {
+            errors.push(format!(
+                "{}: {}",
+                identifier,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+
+    let message = if errors.is_empty() {
+        format!("Deleted {} simulator runtime(s)", items_removed)
+    } else {
+        format!(
+            "Deleted {} simulator runtime(s), {} errors: {}",
+            items_removed,
+            errors.len(),
+            errors.join("; ")
+        )
+    };
+
+    Ok(SimulatorRuntimesCleanResult {
+        total_freed_bytes: total_freed,
+        items_removed,
+        success: errors.is_empty(),
+        message,
+    })
+}
+
+fn simctl_json(args: &[&str]) -> Result<serde_json::Value> {
+    let output = Command::new("xcrun").arg("simctl").args(args).output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "simctl {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
+
+fn is_unusable(state: &str) -> bool {
+    let state = state.to_lowercase();
+    UNUSABLE_STATES.iter().any(|s| state.starts_with(s))
+}
+
+/// Parse `simctl runtime list -j`: an object keyed by runtime identifier
+fn parse_runtime_list(json: &serde_json::Value) -> Result<Vec<RuntimeEntry>> {
+    let runtimes = json
+        .as_object()
+        .ok_or_else(|| anyhow!("Unexpected simctl runtime list output"))?;
+
+    Ok(runtimes
+        .iter()
+        .map(|(key, runtime)| {
+            let field = |name: &str| runtime.get(name).and_then(|v| v.as_str()).map(|s| s.to_string());
+            let state = field("state").unwrap_or_else(|| "Unknown".to_string());
+            RuntimeEntry {
+                identifier: field("identifier").unwrap_or_else(|| key.clone()),
+                version: field("version").unwrap_or_default(),
+                auto_select: is_unusable(&state),
+                state,
+                size: runtime.get("sizeBytes").and_then(|v| v.as_u64()).unwrap_or(0),
+            }
+        })
+        .collect())
+}
+
+/// Parse `simctl list runtimes -j`, returning each runtime with its bundle path
+fn parse_list_runtimes(json: &serde_json::Value) -> Result<Vec<(RuntimeEntry, Option<String>)>> {
+    let runtimes = json
+        .get("runtimes")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow!("Unexpected simctl list runtimes output"))?;
+
+    Ok(runtimes
+        .iter()
+        .filter_map(|runtime| {
+            let field = |name: &str| runtime.get(name).and_then(|v| v.as_str()).map(|s| s.to_string());
+            let state = match runtime.get("isAvailable").and_then(|v| v.as_bool()) {
+                Some(true) => "Ready",
+                Some(false) => "Unavailable",
+                None => "Unknown",
+            };
+            let entry = RuntimeEntry {
+                identifier: field("identifier")?,
+                version: field("version").unwrap_or_default(),
+                state: state.to_string(),
+                size: 0,
+                auto_select: is_unusable(state),
+            };
+            Some((entry, field("bundlePath")))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUNTIME_LIST: &str = r#"{
+  "6A7D2E32-9B4B-4A3E-8E5B-E1E5D8E55E11" : {
+    "build" : "21A328",
+    "deletable" : true,
+    "identifier" : "6A7D2E32-9B4B-4A3E-8E5B-E1E5D8E55E11",
+    "kind" : "Disk Image",
+    "runtimeIdentifier" : "com.apple.CoreSimulator.SimRuntime.iOS-17-0",
+    "sizeBytes" : 7214624956,
+    "state" : "Ready",
+    "version" : "17.0"
+  },
+  "0F1E2D3C-4B5A-6978-8796-A5B4C3D2E1F0" : {
+    "build" : "20E247",
+    "deletable" : true,
+    "identifier" : "0F1E2D3C-4B5A-6978-8796-A5B4C3D2E1F0",
+    "kind" : "Disk Image",
+    "runtimeIdentifier" : "com.apple.CoreSimulator.SimRuntime.iOS-16-4",
+    "sizeBytes" : 6543210000,
+    "state" : "Unavailable",
+    "version" : "16.4"
+  },
+  "C3D2E1F0-0F1E-2D3C-4B5A-697887960000" : {
+    "identifier" : "C3D2E1F0-0F1E-2D3C-4B5A-697887960000",
+    "state" : "Unknown",
+    "version" : "10.0"
+  }
+}"#;
+
+    #[test]
+    fn test_parse_runtime_list() {
+        let json: serde_json::Value = serde_json::from_str(RUNTIME_LIST).unwrap();
+        let runtimes = parse_runtime_list(&json).unwrap();
+        assert_eq!(runtimes.len(), 3);
+
+        let find = |id: &str| runtimes.iter().find(|r| r.identifier == id).unwrap();
+        let ready = find("6A7D2E32-9B4B-4A3E-8E5B-E1E5D8E55E11");
+        assert_eq!(ready.state, "Ready");
+        assert_eq!(ready.version, "17.0");
+        assert_eq!(ready.size, 7_214_624_956);
+        assert!(!ready.auto_select);
+
+        let unavailable = find("0F1E2D3C-4B5A-6978-8796-A5B4C3D2E1F0");
+        assert_eq!(unavailable.state, "Unavailable");
+        assert!(unavailable.auto_select);
+
+        let unknown = find("C3D2E1F0-0F1E-2D3C-4B5A-697887960000");
+        assert_eq!(unknown.size, 0);
+        assert!(unknown.auto_select);
+    }
+
+    #[test]
+    fn test_parse_list_runtimes() {
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{"runtimes": [
+                {"identifier": "com.apple.CoreSimulator.SimRuntime.iOS-17-0", "version": "17.0",
+                 "isAvailable": true, "bundlePath": "/Library/Developer/CoreSimulator/Volumes/iOS_21A328/iOS 17.0.simruntime"},
+                {"identifier": "com.apple.CoreSimulator.SimRuntime.iOS-15-5", "version": "15.5",
+                 "isAvailable": false, "availabilityError": "runtime profile not found"}
+            ]}"#,
+        )
+        .unwrap();
+        let runtimes = parse_list_runtimes(&json).unwrap();
+        assert_eq!(runtimes.len(), 2);
+        assert_eq!(runtimes[0].0.state, "Ready");
+        assert!(runtimes[0].1.is_some());
+        assert_eq!(runtimes[1].0.identifier, "com.apple.CoreSimulator.SimRuntime.iOS-15-5");
+        assert!(runtimes[1].0.auto_select);
+        assert!(runtimes[1].1.is_none());
+    }
+
+    #[test]
+    fn test_unusable_states() {
+        assert!(is_unusable("Unavailable"));
+        assert!(is_unusable("Unusable - Other Error"));
+        assert!(is_unusable("unknown"));
+        assert!(!is_unusable("Ready"));
+    }
+}
@@ -0,0 +1,434 @@
+//! Trash storage for cleaned cache folders.
+//!
+//! Each trash operation lives in `~/.cache-cleaner/trash/<operation_id>/`:
+//! - `manifest.json` records where the folder originally lived
+//! - `contents/` is the trashed folder itself, moved as-is
+//!
+//! Files can be restored individually back to their original subpaths.
+
+use super::guard;
+use crate::utils::filesystem;
+use crate::utils::home::app_data_dir;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+use walkdir::WalkDir;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const CONTENTS_DIR: &str = "contents";
+
+/// Metadata stored alongside every trashed folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedOperation {
+    pub operation_id: String,
+    pub original_path: String,
+    pub trashed_at: u64, // seconds since epoch
+}
+
+/// A single file inside a trashed folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedFile {
+    pub relative_path: String,
+    pub size_bytes: u64,
+}
+
+/// Result of restoring files out of the trash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreResult {
+    pub operation_id: String,
+    pub files_restored: usize,
+    pub restored_bytes: u64,
+    pub success: bool,
+    pub message: String,
+}
+
+fn trash_root() -> Result<PathBuf> {
+    Ok(app_data_dir()?.join("trash"))
+}
+
+/// Move a folder into the trash and return the new operation
+pub fn move_to_trash(path: &Path) -> Result<TrashedOperation> {
+    move_to_trash_in(&trash_root()?, path)
+}
+
+/// List every file inside a trashed folder, with sizes
+pub fn list_trashed_operation_contents(operation_id: &str) -> Result<Vec<TrashedFile>> {
+    list_trashed_operation_contents_in(&trash_root()?, operation_id)
+}
+
+/// Restore only the selected files of a trashed folder to their original subpaths
+pub fn restore_trashed_files(operation_id: &str, relative_paths: Vec<String>) -> Result<RestoreResult> {
+    restore_trashed_files_in(&trash_root()?, operation_id, relative_paths)
+}
+
+/// Move every path into the trash, or none of them.
+///
+/// If any move fails, the paths already trashed are moved back before the
+/// error is returned. Permanent deletion cannot be undone, so `use_trash`
+/// must be true.
+pub fn remove_paths_transactional(paths: &[PathBuf], use_trash: bool) -> Result<Vec<TrashedOperation>> {
+    if !use_trash {
+        return Err(anyhow!("All-or-nothing removal is only possible through the trash"));
+    }
+    remove_paths_transactional_in(&trash_root()?, paths)
+}
+
+fn remove_paths_transactional_in(root: &Path, paths: &[PathBuf]) -> Result<Vec<TrashedOperation>> {
+    guard::ensure_deletion_enabled()?;
+    for path in paths {
+        guard::assert_safe_to_delete(path)?;
+    }
+    if filesystem::skip_deletion() {
+        return Ok(Vec::new());
+    }
+
+    let mut trashed = Vec::new();
+    for path in paths {
+        match move_to_trash_in(root, path) {
+            Ok(operation) => trashed.push(operation),
+            Err(e) => {
+                let unrestored: Vec<String> = trashed
+                    .iter()
+                    .rev()
+                    .filter_map(|op| restore_operation_in(root, op).err().map(|e| e.to_string()))
+                    .collect();
+                if unrestored.is_empty() {
+                    return Err(anyhow!("{}; nothing was removed", e));
+                }
+                // Anything that could not be moved back is still in the trash
+                return Err(anyhow!(
+                    "{}; {} item(s) remain in the trash: {}",
+                    e,
+                    unrestored.len(),
+                    unrestored.join("; ")
+                ));
+            }
+        }
+    }
+    Ok(trashed)
+}
+
+/// Move a whole trashed folder back to where it came from
+fn restore_operation_in(root: &Path, operation: &TrashedOperation) -> Result<()> {
+    let dir = operation_dir(root, &operation.operation_id)?;
+    let original = Path::new(&operation.original_path);
+    if original.exists() {
+        return Err(anyhow!("Already exists: {}", original.display()));
+    }
+    std::fs::rename(dir.join(CONTENTS_DIR), original)
+        .map_err(|e| anyhow!("Failed to restore {}: {}", original.display(), e))?;
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+pub(super) fn move_to_trash_in(root: &Path, path: &Path) -> Result<TrashedOperation> {
+    guard::ensure_deletion_enabled()?;
+    if !path.exists() {
+        return Err(anyhow!("Path not found: {}", path.display()));
+    }
+
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "item".to_string());
+    let operation_id = format!("{}-{}", now.as_nanos(), sanitize_name(&name));
+
+    let operation_dir = root.join(&operation_id);
+    std::fs::create_dir_all(&operation_dir)?;
+
+    let operation = TrashedOperation {
+        operation_id,
+        original_path: path.to_string_lossy().to_string(),
+        trashed_at: now.as_secs(),
+    };
+    std::fs::write(
+        operation_dir.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&operation)?,
+    )?;
+
+    if let Err(e) = std::fs::rename(path, operation_dir.join(CONTENTS_DIR)) {
+        let _ = std::fs::remove_dir_all(&operation_dir);
+        return Err(anyhow!("Failed to move {} to trash: {}", path.display(), e));
+    }
+
+    Ok(operation)
+}
+
+fn list_trashed_operation_contents_in(root: &Path, operation_id: &str) -> Result<Vec<TrashedFile>> {
+    let contents = operation_dir(root, operation_id)?.join(CONTENTS_DIR);
+    if !contents.exists() {
+        return Err(anyhow!("Trash operation has no contents: {}", operation_id));
+    }
+
+    let mut files = Vec::new();
+    for entry in WalkDir::new(&contents).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let relative = match entry.path().strip_prefix(&contents) {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+        files.push(TrashedFile {
+            relative_path: to_slash_path(relative),
+            size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+        });
+    }
+
+    files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    Ok(files)
+}
+
+fn restore_trashed_files_in(
+    root: &Path,
+    operation_id: &str,
+    relative_paths: Vec<String>,
+) -> Result<RestoreResult> {
+    // Validate everything up front so a bad entry restores nothing
+    for relative in &relative_paths {
+        validate_relative_path(relative)?;
+    }
+
+    let operation = read_manifest(root, operation_id)?;
+    let contents = operation_dir(root, operation_id)?.join(CONTENTS_DIR);
+    let original = PathBuf::from(&operation.original_path);
+
+    let mut files_restored = 0usize;
+    let mut restored_bytes = 0u64;
+    let mut errors = Vec::new();
+
+    for relative in &relative_paths {
+        let source = contents.join(relative);
+        let target = original.join(relative);
+
+        if !source.is_file() {
+            errors.push(format!("Not found in trash: {}", relative));
+            continue;
+        }
+        if target.exists() {
+            errors.push(format!("Already exists: {}", target.display()));
+            continue;
+        }
+
+        let size = std::fs::metadata(&source).map(|m| m.len()).unwrap_or(0);
+        if let Some(parent) = target.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                errors.push(format!("Failed to create {}: {}", parent.display(), e));
+                continue;
+            }
+        }
+
+        match std::fs::rename(&source, &target) {
+            Ok(_) => {
+                files_restored += 1;
+                restored_bytes += size;
+            }
+            Err(e) => errors.push(format!("Failed to restore {}: {}", relative, e)),
+        }
+    }
+
+    let success = errors.is_empty();
+    let message = if success {
+        format!("Restored {} file(s)", files_restored)
+    } else {
+        format!(
+            "Restored {} file(s) with {} error(s): {}",
+            files_restored,
+            errors.len(),
+            errors.join("; ")
+        )
+    };
+
+    Ok(RestoreResult {
+        operation_id: operation.operation_id,
+        files_restored,
+        restored_bytes,
+        success,
+        message,
+    })
+}
+
+fn read_manifest(root: &Path, operation_id: &str) -> Result<TrashedOperation> {
+    let manifest = operation_dir(root, operation_id)?.join(MANIFEST_FILE);
+    let content = std::fs::read_to_string(&manifest)
+        .map_err(|_| anyhow!("Trash operation not found: {}", operation_id))?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn operation_dir(root: &Path, operation_id: &str) -> Result<PathBuf> {
+    if operation_id.is_empty()
+        || operation_id.contains('/')
+        || operation_id.contains('\\')
+        || operation_id.contains("..")
+    {
+        return Err(anyhow!("Invalid trash operation id: {}", operation_id));
+    }
+    Ok(root.join(operation_id))
+}
+
+/// Only plain relative paths are accepted - no `..`, no absolute paths
+fn validate_relative_path(relative: &str) -> Result<()> {
+    let path = Path::new(relative);
+    if relative.is_empty() || path.is_absolute() {
+        return Err(anyhow!("Invalid relative path: {}", relative));
+    }
+    for component in path.components() {
+        match component {
+            Component::Normal(_) | Component::CurDir => {}
+            _ => return Err(anyhow!("Path traversal is not allowed: {}", relative)),
+        }
+    }
+    Ok(())
+}
+
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn to_slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn create_test_file(dir: &Path, name: &str, content: &[u8]) -> PathBuf {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        let mut file = File::create(&path).unwrap();
+        file.write_all(content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_list_trashed_operation_contents() {
+        let dir = create_test_dir();
+        let cache = dir.path().join("cache");
+        create_test_file(&cache, "a.bin", b"hello");
+        create_test_file(&cache, "nested/b.bin", b"world!");
+
+        let root = dir.path().join("trash");
+        let op = move_to_trash_in(&root, &cache).unwrap();
+        assert!(!cache.exists());
+
+        let files = list_trashed_operation_contents_in(&root, &op.operation_id).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].relative_path, "a.bin");
+        assert_eq!(files[0].size_bytes, 5);
+        assert_eq!(files[1].relative_path, "nested/b.bin");
+        assert_eq!(files[1].size_bytes, 6);
+    }
+
+    #[test]
+    fn test_restore_single_nested_file() {
+        let dir = create_test_dir();
+        let cache = dir.path().join("cache");
+        create_test_file(&cache, "a.bin", b"hello");
+        create_test_file(&cache, "deep/nested/b.bin", b"world!");
+
+        let root = dir.path().join("trash");
+        let op = move_to_trash_in(&root, &cache).unwrap();
+
+        let result = restore_trashed_files_in(
+            &root,
+            &op.operation_id,
+            vec!["deep/nested/b.bin".to_string()],
+        )
+        .unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.files_restored, 1);
+        assert_eq!(result.restored_bytes, 6);
+        assert_eq!(fs::read(cache.join("deep/nested/b.bin")).unwrap(), b"world!");
+        assert!(!cache.join("a.bin").exists());
+
+        let remaining = list_trashed_operation_contents_in(&root, &op.operation_id).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].relative_path, "a.bin");
+    }
+
+    #[test]
+    fn test_restore_rejects_path_traversal() {
+        let dir = create_test_dir();
+        let cache = dir.path().join("cache");
+        create_test_file(&cache, "a.bin", b"hello");
+
+        let root = dir.path().join("trash");
+        let op = move_to_trash_in(&root, &cache).unwrap();
+
+        let result = restore_trashed_files_in(
+            &root,
+            &op.operation_id,
+            vec!["a.bin".to_string(), "../outside.bin".to_string()],
+        );
+        assert!(result.is_err());
+        // Nothing is restored when any entry is invalid
+        assert!(!cache.join("a.bin").exists());
+    }
+
+    #[test]
+    fn test_transactional_batch_succeeds() {
+        guard::allow_root_for_tests();
+        let dir = create_test_dir();
+        let paths: Vec<PathBuf> = ["a", "b", "c"]
+            .iter()
+            .map(|name| {
+                create_test_file(&dir.path().join(name), "data.bin", name.as_bytes());
+                dir.path().join(name)
+            })
+            .collect();
+
+        let root = dir.path().join("trash");
+        let ops = remove_paths_transactional_in(&root, &paths).unwrap();
+        assert_eq!(ops.len(), 3);
+        assert!(paths.iter().all(|p| !p.exists()));
+    }
+
+    #[test]
+    fn test_transactional_batch_restores_on_failure() {
+        guard::allow_root_for_tests();
+        let dir = create_test_dir();
+        let first = dir.path().join("first");
+        let third = dir.path().join("third");
+        create_test_file(&first, "data.bin", b"first");
+        create_test_file(&third, "data.bin", b"third");
+        let paths = vec![first.clone(), dir.path().join("missing"), third.clone()];
+
+        let root = dir.path().join("trash");
+        assert!(remove_paths_transactional_in(&root, &paths).is_err());
+
+        assert_eq!(fs::read(first.join("data.bin")).unwrap(), b"first");
+        assert_eq!(fs::read(third.join("data.bin")).unwrap(), b"third");
+        // The restored operation leaves nothing behind in the trash
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_transactional_requires_trash() {
+        assert!(remove_paths_transactional(&[PathBuf::from("/tmp/whatever")], false).is_err());
+    }
+
+    #[test]
+    fn test_invalid_operation_id() {
+        let dir = create_test_dir();
+        assert!(list_trashed_operation_contents_in(dir.path(), "../etc").is_err());
+        assert!(list_trashed_operation_contents_in(dir.path(), "missing").is_err());
+    }
+}
@@ -0,0 +1,297 @@
+//! Per-archive view of `~/Library/Developer/Xcode/Archives`.
+//!
+//! Xcode stores each archive as `<yyyy-mm-dd>/<Name> <date>.xcarchive`, with
+//! an `Info.plist` naming the app, its version and the creation date. Listing
+//! them individually lets the newest archives of every app be kept while the
+//! older ones are removed.
+
+use super::ios_backups::{parse_iso8601, plist_value};
+use super::paths::MacPaths;
+use super::{guard, CacheError, PathResult};
+use crate::utils::filesystem;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Archives sit one level below the date folders
+const ARCHIVE_SEARCH_DEPTH: usize = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub app_name: String,
+    pub bundle_id: Option<String>,
+    /// `CFBundleShortVersionString` and `CFBundleVersion`, e.g. `1.2 (42)`
+    pub version: Option<String>,
+    /// ISO 8601 `CreationDate` from `Info.plist`
+    pub creation_date: Option<String>,
+    pub size_bytes: u64,
+}
+
+impl ArchiveEntry {
+    /// Archives of the same app share a bundle id; older ones may only have a name
+    fn app_key(&self) -> &str {
+        self.bundle_id.as_deref().unwrap_or(&self.app_name)
+    }
+
+    fn created_secs(&self) -> Option<u64> {
+        self.creation_date.as_deref().and_then(parse_iso8601)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XcodeArchivesCleanResult {
+    pub total_freed_bytes: u64,
+    pub items_kept: usize,
+    pub items_removed: usize,
+    pub success: bool,
+    pub message: String,
+    pub dry_run: bool,
+    pub path_results: Vec<PathResult>,
+}
+
+pub async fn scan_xcode_archives() -> Result<Vec<ArchiveEntry>> {
+    let root = MacPaths::xcode_archives()?;
+    tokio::task::spawn_blocking(move || scan_xcode_archives_in(&root)).await?
+}
+
+/// Keep the newest `keep_recent_per_app` archives of every app and remove the rest
+pub async fn clean_xcode_archives(keep_recent_per_app: usize, dry_run: bool) -> Result<XcodeArchivesCleanResult> {
+    if !dry_run {
+        guard::ensure_deletion_enabled()?;
+    }
+    let root = MacPaths::xcode_archives()?;
+    tokio::task::spawn_blocking(move || clean_xcode_archives_in(&root, keep_recent_per_app, dry_run)).await?
+}
+
+fn scan_xcode_archives_in(root: &Path) -> Result<Vec<ArchiveEntry>> {
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    for entry in WalkDir::new(root).max_depth(ARCHIVE_SEARCH_DEPTH).into_iter().flatten() {
+        let path = entry.path();
+        if !entry.file_type().is_dir() || path.extension().is_none_or(|ext| ext != "xcarchive") {
+            continue;
+        }
+        let plist = std::fs::read_to_string(path.join("Info.plist")).unwrap_or_default();
+        let fallback_name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let short_version = plist_value(&plist, "CFBundleShortVersionString", "string");
+        let build = plist_value(&plist, "CFBundleVersion", "string");
+        let version = match (short_version, build) {
+            (Some(short), Some(build)) => Some(format!("{} ({})", short, build)),
+            (short, build) => short.or(build),
+        };
+
+        entries.push(ArchiveEntry {
+            path: path.display().to_string(),
+            app_name: plist_value(&plist, "Name", "string").unwrap_or(fallback_name),
+            bundle_id: plist_value(&plist, "CFBundleIdentifier", "string"),
+            version,
+            creation_date: plist_value(&plist, "CreationDate", "date"),
+            size_bytes: filesystem::calculate_dir_size_sync(path).unwrap_or(0),
+        });
+    }
+
+    // Newest first, undated archives last
+    entries.sort_by_key(|archive| Reverse(archive.created_secs()));
+    Ok(entries)
+}
+
+/// Archives beyond the newest `keep` of their app. Undated archives are
+/// never selected, since their age is unknown.
+fn archives_to_remove(entries: &[ArchiveEntry], keep: usize) -> Vec<&ArchiveEntry> {
+    let mut by_app: HashMap<&str, Vec<&ArchiveEntry>> = HashMap::new();
+    for entry in entries.iter().filter(|e| e.created_secs().is_some()) {
+        by_app.entry(entry.app_key()).or_default().push(entry);
+    }
+
+    let mut remove = Vec::new();
+    for mut archives in by_app.into_values() {
+        archives.sort_by_key(|archive| Reverse(archive.created_secs()));
+        remove.extend(archives.into_iter().skip(keep));
+    }
+    remove
+}
+
+fn clean_xcode_archives_in(root: &Path, keep_recent_per_app: usize, dry_run: bool) -> Result<XcodeArchivesCleanResult> {
+    let entries = scan_xcode_archives_in(root)?;
+    let remove = archives_to_remove(&entries, keep_recent_per_app);
+    let items_kept = entries.len() - remove.len();
+
+    let mut total_freed = 0u64;
+    let mut items_removed = 0usize;
+    let mut path_results = Vec::new();
+
+    for entry in remove {
+        let path = Path::new(&entry.path);
+        if let Err(e) = guard::assert_safe_to_delete(path) {
+            path_results.push(PathResult::failed(&entry.path, e.to_string()));
+            continue;
+        }
+        if dry_run || filesystem::skip_deletion() {
+            total_freed += entry.size_bytes;
+            items_removed += 1;
+            path_results.push(PathResult::removed(&entry.path, entry.size_bytes));
+            continue;
+        }
+        match filesystem::delete_dir_all(path) {
+            Ok(()) => {
+                total_freed += entry.size_bytes;
+                items_removed += 1;
+                path_results.push(PathResult::removed(&entry.path, entry.size_bytes));
+            }
+            Err(e) => path_results.push(PathResult::from_error(&entry.path, &CacheError::from_io(path, e))),
+        }
+    }
+
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    let errors: Vec<String> = path_results.iter().filter_map(|r| r.error.clone()).collect();
+    let message = if errors.is_empty() {
+        format!("{} {} archive(s), kept {}", verb, items_removed, items_kept)
+    } else {
+        format!(
+            "{} {} archive(s), kept {}, {} errors: {}",
+            verb,
+            items_removed,
+            items_kept,
+            errors.len(),
+            errors.join("; ")
+        )
+    };
+
+    Ok(XcodeArchivesCleanResult {
+        total_freed_bytes: total_freed,
+        items_kept,
+        items_removed,
+        success: errors.is_empty(),
+        message,
+        dry_run,
+        path_results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn create_archive(root: &Path, name: &str, bundle_id: &str, version: &str, date: &str) -> String {
+        let day = &date[..10];
+        let path = root.join(day).join(format!("{} {}.xcarchive", name, day));
+        fs::create_dir_all(path.join("Products/Applications")).unwrap();
+        fs::write(
+            path.join("Info.plist"),
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>ApplicationProperties</key>
+	<dict>
+		<key>CFBundleIdentifier</key>
+		<string>{}</string>
+		<key>CFBundleShortVersionString</key>
+		<string>{}</string>
+		<key>CFBundleVersion</key>
+		<string>7</string>
+	</dict>
+	<key>ArchiveVersion</key>
+	<integer>2</integer>
+	<key>CreationDate</key>
+	<date>{}</date>
+	<key>Name</key>
+	<string>{}</string>
+	<key>SchemeName</key>
+	<string>{}</string>
+</dict>
+</plist>
+"#,
+                bundle_id, version, date, name, name
+            ),
+        )
+        .unwrap();
+        fs::write(path.join("Products/Applications/binary"), [0u8; 100]).unwrap();
+        path.display().to_string()
+    }
+
+    #[test]
+    fn test_scan_parses_info_plist() {
+        let root = create_test_dir();
+        create_archive(root.path(), "Notes", "com.example.notes", "1.2", "2024-03-01T09:00:00Z");
+
+        let entries = scan_xcode_archives_in(root.path()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].app_name, "Notes");
+        assert_eq!(entries[0].bundle_id.as_deref(), Some("com.example.notes"));
+        assert_eq!(entries[0].version.as_deref(), Some("1.2 (7)"));
+        assert_eq!(entries[0].creation_date.as_deref(), Some("2024-03-01T09:00:00Z"));
+        assert!(entries[0].size_bytes >= 100);
+    }
+
+    #[test]
+    fn test_keep_newest_per_app() {
+        let root = create_test_dir();
+        let notes_old = create_archive(root.path(), "Notes", "com.example.notes", "1.0", "2024-01-01T09:00:00Z");
+        let notes_mid = create_archive(root.path(), "Notes", "com.example.notes", "1.1", "2024-02-01T09:00:00Z");
+        let notes_new = create_archive(root.path(), "Notes", "com.example.notes", "1.2", "2024-03-01T09:00:00Z");
+        let todo_old = create_archive(root.path(), "Todo", "com.example.todo", "2.0", "2024-01-15T09:00:00Z");
+        let todo_new = create_archive(root.path(), "Todo", "com.example.todo", "2.1", "2024-02-20T09:00:00Z");
+
+        let result = clean_xcode_archives_in(root.path(), 2, false).unwrap();
+        assert!(result.success, "{}", result.message);
+        assert_eq!(result.items_removed, 1);
+        assert_eq!(result.items_kept, 4);
+        assert!(!Path::new(&notes_old).exists());
+        for kept in [&notes_mid, &notes_new, &todo_old, &todo_new] {
+            assert!(Path::new(kept).exists(), "{} should be kept", kept);
+        }
+
+        let result = clean_xcode_archives_in(root.path(), 1, false).unwrap();
+        assert_eq!(result.items_removed, 2);
+        assert_eq!(result.items_kept, 2);
+        assert!(Path::new(&notes_new).exists());
+        assert!(Path::new(&todo_new).exists());
+        assert!(!Path::new(&todo_old).exists());
+    }
+
+    #[test]
+    fn test_dry_run_keeps_everything() {
+        let root = create_test_dir();
+        let old = create_archive(root.path(), "Notes", "com.example.notes", "1.0", "2024-01-01T09:00:00Z");
+        create_archive(root.path(), "Notes", "com.example.notes", "1.1", "2024-02-01T09:00:00Z");
+
+        let result = clean_xcode_archives_in(root.path(), 1, true).unwrap();
+        assert!(result.dry_run);
+        assert_eq!(result.items_removed, 1);
+        assert_eq!(result.items_kept, 1);
+        assert!(result.total_freed_bytes >= 100);
+        assert!(Path::new(&old).exists());
+    }
+
+    #[test]
+    fn test_undated_archives_are_kept() {
+        let root = create_test_dir();
+        let undated = root.path().join("2024-01-01/Broken.xcarchive");
+        fs::create_dir_all(&undated).unwrap();
+        create_archive(root.path(), "Notes", "com.example.notes", "1.0", "2024-01-01T09:00:00Z");
+
+        let result = clean_xcode_archives_in(root.path(), 0, false).unwrap();
+        assert_eq!(result.items_removed, 1);
+        assert_eq!(result.items_kept, 1);
+        assert!(undated.exists());
+    }
+}
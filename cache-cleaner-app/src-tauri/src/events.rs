@@ -0,0 +1,176 @@
+//! Application-wide event bus.
+//!
+//! Business logic publishes typed `AppEvent`s without knowing about Tauri.
+//! The bus forwards them to the frontend once an `AppHandle` is attached, and
+//! to in-process subscribers through a broadcast channel.
+
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber before the oldest are dropped
+const CHANNEL_CAPACITY: usize = 64;
+
+static BUS: OnceLock<EventBus> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AppEvent {
+    ScanProgress {
+        scanned: usize,
+        total: usize,
+        current: String,
+    },
+    ScanCompleted {
+        caches_found: usize,
+        errors: usize,
+    },
+    CleanProgress {
+        cache_type: String,
+        items_done: usize,
+        items_total: usize,
+        bytes_freed: u64,
+    },
+    CleanCompleted {
+        cache_type: String,
+        freed_bytes: u64,
+        success: bool,
+    },
+}
+
+impl AppEvent {
+    /// Event name the frontend listens on
+    pub fn name(&self) -> &'static str {
+        match self {
+            AppEvent::ScanProgress { .. } => "scan-progress",
+            AppEvent::ScanCompleted { .. } => "scan-completed",
+            AppEvent::CleanProgress { .. } => "clean-progress",
+            AppEvent::CleanCompleted { .. } => "clean-completed",
+        }
+    }
+}
+
+pub struct EventBus {
+    app: OnceLock<AppHandle>,
+    sender: broadcast::Sender<AppEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            app: OnceLock::new(),
+            sender,
+        }
+    }
+
+    /// Start forwarding events to the frontend
+    pub fn attach(&self, app: AppHandle) {
+        let _ = self.app.set(app);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AppEvent> {
+        self.sender.subscribe()
+    }
+
+    pub fn publish(&self, event: AppEvent) {
+        if let Some(app) = self.app.get() {
+            if let Err(e) = app.emit(event.name(), &event) {
+                eprintln!("[Rust] Failed to emit {}: {}", event.name(), e);
+            }
+        }
+        // Having no in-process subscribers is normal
+        let _ = self.sender.send(event);
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rate limit for high-frequency events such as per-item progress
+pub struct Throttle {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl Throttle {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, last: None }
+    }
+
+    /// True at most once per interval; the first call always passes
+    pub fn ready(&mut self) -> bool {
+        let now = Instant::now();
+        match self.last {
+            Some(last) if now.duration_since(last) < self.interval => false,
+            _ => {
+                self.last = Some(now);
+                true
+            }
+        }
+    }
+}
+
+/// The process-wide bus
+pub fn bus() -> &'static EventBus {
+    BUS.get_or_init(EventBus::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscriber_receives_scan_progress() {
+        let bus = EventBus::new();
+        let mut rx = bus.subscribe();
+
+        let event = AppEvent::ScanProgress {
+            scanned: 1,
+            total: 3,
+            current: "Npm".to_string(),
+        };
+        bus.publish(event.clone());
+
+        assert_eq!(rx.recv().await.unwrap(), event);
+    }
+
+    #[test]
+    fn test_publish_without_subscribers_or_app() {
+        let bus = EventBus::new();
+        bus.publish(AppEvent::ScanCompleted {
+            caches_found: 0,
+            errors: 0,
+        });
+    }
+
+    #[test]
+    fn test_throttle_limits_rate() {
+        let mut throttle = Throttle::new(Duration::from_secs(60));
+        assert!(throttle.ready());
+        assert!(!throttle.ready());
+
+        let mut unthrottled = Throttle::new(Duration::ZERO);
+        assert!(unthrottled.ready());
+        assert!(unthrottled.ready());
+    }
+
+    #[test]
+    fn test_event_names_and_serialization() {
+        let event = AppEvent::CleanCompleted {
+            cache_type: "Npm".to_string(),
+            freed_bytes: 42,
+            success: true,
+        };
+        assert_eq!(event.name(), "clean-completed");
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "clean_completed");
+        assert_eq!(json["freed_bytes"], 42);
+    }
+}
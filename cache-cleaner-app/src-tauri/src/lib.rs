@@ -0,0 +1,11 @@
+//! Cache scanning and cleaning engine shared by the Tauri app and `rpc-server`.
+
+/// Version of the shapes returned by commands, so the frontend can detect a
+/// mismatched backend. Adding an optional field (with `#[serde(default)]`)
+/// keeps the version; removing or repurposing one, or changing a command's
+/// arguments, bumps it.
+pub const API_VERSION: u32 = 2;
+
+pub mod cache;
+pub mod events;
+pub mod utils;
@@ -1,21 +1,40 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod cache;
-mod utils;
-
+use cache_cleaner_lib::{cache, events, utils};
 use cache::{
-    CacheInfo, CacheType, CleanResult, IndexedDbCleanResult, IndexedDbItem,
-    NpmCacheEntry, NpmCachesCleanResult,
-    config::AppConfig,
+    CacheCategory, CacheInfo, CacheType, CleanResult, DeleteMode, IndexedDbCleanResult, IndexedDbItem,
+    NpmCacheEntry, NpmCachesCleanResult, ScanReport,
+    config::{AppConfig, ImportSummary},
     custom_scanner::CustomScannerConfig,
     registry::ScannerRegistry,
     scanner_trait::{ScanResult, CleanResultGeneric},
-    smart_suggestions::{FolderSuggestion, SmartSuggestionsCleanResult},
+    smart_suggestions::{FolderSuggestion, SmartSuggestionsCleanResult, SuggestionScan, SuggestionScanOptions},
     docker::{DockerScanResult, DockerCleanResult, DockerSuggestion},
+    trash::{TrashedFile, TrashedOperation, RestoreResult},
+    self_test::SelfTestReport,
+    duplicates::DuplicateGroup,
+    preflight::PreflightResult,
+    deep_clean::{DeepCleanOptions, DeepCleanResult},
+    cleanup_plan::CleanupPlan,
+    ios_backups::{IosBackupEntry, IosBackupsCleanResult},
+    simulator_runtimes::{RuntimeEntry, SimulatorRuntimesCleanResult},
+    git_maintenance::{GitGcResult, GitRepoEntry},
+    app_data::AppFootprint,
+    cold_artifacts::{ColdArtifactsCleanResult, LargeCacheEntry},
+    audit::AuditEntry,
+    diagnosis::CacheDiagnosis,
+    xcode_archives::{ArchiveEntry, XcodeArchivesCleanResult},
+    baselines::{ScanBaseline, ScanDelta},
+    media_caches::MediaCachesCleanResult,
+    chrome_profiles::{ChromeProfileCache, ChromeProfileCleanResult},
+    jetbrains::JetBrainsCleanResult,
+    old_logs::OldLogsCleanResult,
+    cleanup_eta::DurationEstimate,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::OnceCell;
+use utils::permissions::PermissionGuidance;
 
 static REGISTRY: OnceCell<Arc<ScannerRegistry>> = OnceCell::const_new();
 
@@ -38,9 +57,28 @@ pub struct PermissionStatus {
     pub home_accessible: bool,
 }
 
+/// Directory a cache type lives in, for the audit log
+fn audit_paths(cache_type: &CacheType) -> Vec<String> {
+    cache::scanner::get_cache_path(cache_type)
+        .map(|path| vec![path.display().to_string()])
+        .unwrap_or_default()
+}
+
+/// Record a Docker operation in the audit log and pass its result through
+fn audit_docker(operation: &str, targets: Vec<String>, result: DockerCleanResult) -> DockerCleanResult {
+    cache::audit::record(AuditEntry::new(operation, targets, result.freed_bytes, false));
+    result
+}
+
 #[tauri::command]
-async fn scan_caches() -> Result<Vec<CacheInfo>, String> {
-    cache::scanner::scan_all().await.map_err(|e| e.to_string())
+async fn scan_caches(force: Option<bool>) -> Result<ScanReport, String> {
+    Ok(cache::scan_results::cached_scan_all(force.unwrap_or(false)).await)
+}
+
+/// Scan with sampled sizes for large caches; not stored in the scan cache
+#[tauri::command]
+async fn scan_caches_fast() -> Result<ScanReport, String> {
+    Ok(cache::scanner::scan_all_fast().await)
 }
 
 #[tauri::command]
@@ -49,8 +87,30 @@ async fn get_cache_size(cache_type: String) -> Result<u64, String> {
     cache::scanner::get_size(&ct).await.map_err(|e| e.to_string())
 }
 
+/// Like `clean_cache`, emitting throttled `clean-progress` events while deleting
 #[tauri::command]
-async fn clean_cache(cache_type: String, dry_run: bool) -> Result<CleanResult, String> {
+async fn clean_cache_with_progress(cache_type: String, mode: Option<DeleteMode>) -> Result<CleanResult, String> {
+    let dry_run = mode.unwrap_or_default().is_preview();
+    let _lock = cache::operation_lock::exclusive().await;
+    let ct = CacheType::from_str(&cache_type).map_err(|e| e.to_string())?;
+    let result = cache::cleaner::clean_with_progress(&ct, dry_run)
+        .await
+        .map_err(|e| e.to_string())?;
+    cache::audit::record(AuditEntry::from_clean(&result, audit_paths(&ct)));
+    if !dry_run {
+        cache::scan_results::invalidate_scan_cache().await;
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+async fn clean_cache(
+    cache_type: String,
+    mode: Option<DeleteMode>,
+    protect_recent_minutes: Option<u64>,
+) -> Result<CleanResult, String> {
+    let dry_run = mode.unwrap_or_default().is_preview();
+    let _lock = cache::operation_lock::exclusive().await;
     println!(
         "[Rust] clean_cache called: cache_type={}, dry_run={}",
         cache_type, dry_run
@@ -61,7 +121,7 @@ async fn clean_cache(cache_type: String, dry_run: bool) -> Result<CleanResult, S
         e.to_string()
     })?;
 
-    let result = cache::cleaner::clean(&ct, dry_run)
+    let result = cache::cleaner::clean_protecting_recent(&ct, dry_run, protect_recent_minutes)
         .await
         .map_err(|e| {
             eprintln!(
@@ -75,10 +135,56 @@ async fn clean_cache(cache_type: String, dry_run: bool) -> Result<CleanResult, S
         "[Rust] clean_cache finished: type={:?}, freed_bytes={}, items_removed={}, dry_run={}",
         result.cache_type, result.freed_bytes, result.items_removed, result.dry_run
     );
+    cache::audit::record(AuditEntry::from_clean(&result, audit_paths(&ct)));
 
+    if !dry_run {
+        cache::scan_results::invalidate_scan_cache().await;
+    }
     Ok(result)
 }
 
+/// Estimated seconds to delete everything below `paths`, for a progress ETA
+#[tauri::command]
+async fn estimate_cleanup_duration(paths: Vec<String>) -> Result<DurationEstimate, String> {
+    tokio::task::spawn_blocking(move || cache::cleanup_eta::estimate_cleanup_duration(paths))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Scan all caches belonging to one category (browser, package manager, ...)
+#[tauri::command]
+async fn scan_by_category(category: String) -> Result<ScanReport, String> {
+    let category: CacheCategory = category.parse()?;
+    Ok(cache::scanner::scan_by_category(category).await)
+}
+
+/// Clean all caches belonging to one category
+#[tauri::command]
+async fn clean_by_category(category: String, mode: Option<DeleteMode>) -> Result<Vec<CleanResult>, String> {
+    let dry_run = mode.unwrap_or_default().is_preview();
+    let _lock = cache::operation_lock::exclusive().await;
+    let category: CacheCategory = category.parse()?;
+    let results = cache::cleaner::clean_by_category(category, dry_run).await;
+    for result in &results {
+        cache::audit::record(AuditEntry::from_clean(result, audit_paths(&result.cache_type)));
+    }
+    if !dry_run {
+        cache::scan_results::invalidate_scan_cache().await;
+    }
+    Ok(results)
+}
+
+/// Health checks that tell whether cleaning would fix a broken cache
+#[tauri::command]
+async fn diagnose_cache(cache_type: String) -> Result<CacheDiagnosis, String> {
+    let ct = CacheType::from_str(&cache_type).map_err(|e| e.to_string())?;
+    tokio::task::spawn_blocking(move || cache::diagnosis::diagnose_cache(ct))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn check_chrome_running() -> Result<bool, String> {
     utils::permissions::is_chrome_running().map_err(|e| e.to_string())
@@ -92,24 +198,117 @@ async fn check_permissions() -> Result<PermissionStatus, String> {
     })
 }
 
+/// Missing capabilities and the steps to grant them
+#[tauri::command]
+async fn request_permissions_with_guidance() -> Result<PermissionGuidance, String> {
+    Ok(utils::permissions::request_permissions_with_guidance())
+}
+
+/// One-click fix offered when a result reports `tcc_denied`
+#[tauri::command]
+async fn open_full_disk_access_settings() -> Result<(), String> {
+    utils::permissions::open_full_disk_access_settings().map_err(|e| e.to_string())
+}
+
+/// Cache sizes from the persistent index, without scanning
+#[tauri::command]
+async fn query_cache_index() -> Result<Vec<CacheInfo>, String> {
+    cache::index::query_index().map_err(|e| e.to_string())
+}
 
+/// Rescan and rewrite the persistent index, returning the number of caches indexed
+#[tauri::command]
+async fn refresh_cache_index() -> Result<usize, String> {
+    cache::index::refresh_index().await.map_err(|e| e.to_string())
+}
+
+/// Keep the index fresh from filesystem events on the indexed caches
+#[tauri::command]
+async fn start_watching() -> Result<(), String> {
+    cache::index_watcher::start_watching().await.map_err(|e| e.to_string())
+}
+
+/// Stop the index watcher, returning whether one was running
+#[tauri::command]
+async fn stop_watching() -> Result<bool, String> {
+    Ok(cache::index_watcher::stop_watching())
+}
+
+/// Save the current scan under `name` for later comparison
+#[tauri::command]
+async fn save_scan_baseline(name: String) -> Result<ScanBaseline, String> {
+    cache::baselines::save_scan_baseline(name).await.map_err(|e| e.to_string())
+}
+
+/// Growth per cache type since the baseline saved as `name`
+#[tauri::command]
+async fn compare_to_baseline(name: String) -> Result<ScanDelta, String> {
+    cache::baselines::compare_to_baseline(name).await.map_err(|e| e.to_string())
+}
+
+/// Whether scheduled cleans are currently postponed because the Mac is on battery
+#[tauri::command]
+async fn check_scheduled_work_postponed() -> Result<bool, String> {
+    let config = AppConfig::load().map_err(|e| e.to_string())?;
+    Ok(utils::power::should_postpone_scheduled_work(config.run_on_battery))
+}
+
+/// Total, free and purgeable space on the boot volume
+#[tauri::command]
+async fn get_disk_usage() -> Result<utils::disk::DiskUsage, String> {
+    utils::disk::get_disk_usage().map_err(|e| e.to_string())
+}
+
+/// Version of the command result shapes, checked by the frontend at startup
+#[tauri::command]
+async fn get_api_version() -> Result<u32, String> {
+    Ok(cache_cleaner_lib::API_VERSION)
+}
+
+/// Internal performance counters, for diagnosing slow scans
+#[tauri::command]
+async fn get_metrics() -> Result<utils::metrics::MetricsSnapshot, String> {
+    Ok(utils::metrics::get_metrics())
+}
+
+/// Zero the performance counters
+#[tauri::command]
+async fn reset_metrics() -> Result<(), String> {
+    utils::metrics::reset_metrics();
+    Ok(())
+}
+
+/// Run the health-check/self-test used for support diagnostics
+#[tauri::command]
+async fn run_self_test() -> Result<SelfTestReport, String> {
+    cache::self_test::run_self_test().await.map_err(|e| e.to_string())
+}
 
 #[tauri::command]
 async fn scan_indexed_db_items(
     threshold_mb: Option<u64>,
+    include_active: Option<bool>,
 ) -> Result<Vec<IndexedDbItem>, String> {
     // Default alert threshold is 10MB if not specified.
     let mb = threshold_mb.unwrap_or(10);
     let threshold_bytes = mb * 1024 * 1024;
-    cache::indexeddb::scan_indexed_db(threshold_bytes).map_err(|e| e.to_string())
+    cache::indexeddb::scan_indexed_db(threshold_bytes, include_active.unwrap_or(true))
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
 async fn clean_indexed_db_items(
     paths: Vec<String>,
-    dry_run: bool,
+    mode: Option<DeleteMode>,
 ) -> Result<IndexedDbCleanResult, String> {
-    cache::indexeddb::clean_indexed_db_items(paths, dry_run).map_err(|e| e.to_string())
+    let dry_run = mode.unwrap_or_default().is_preview();
+    let _lock = cache::operation_lock::exclusive().await;
+    let result = cache::indexeddb::clean_indexed_db_items(paths.clone(), dry_run).map_err(|e| e.to_string())?;
+    cache::audit::record(AuditEntry::new("clean_indexed_db_items", paths, result.total_freed_bytes, dry_run));
+    if !dry_run {
+        cache::scan_results::invalidate_scan_cache().await;
+    }
+    Ok(result)
 }
 
 #[tauri::command]
@@ -121,9 +320,13 @@ async fn scan_npm_caches() -> Result<Vec<NpmCacheEntry>, String> {
 
 #[tauri::command]
 async fn remove_npm_caches(paths: Vec<String>) -> Result<NpmCachesCleanResult, String> {
-    cache::npm_caches::remove_npm_caches(paths)
+    let _lock = cache::operation_lock::exclusive().await;
+    let result = cache::npm_caches::remove_npm_caches(paths.clone())
         .await
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+    cache::audit::record(AuditEntry::new("remove_npm_caches", paths, result.total_freed_bytes, false));
+    cache::scan_results::invalidate_scan_cache().await;
+    Ok(result)
 }
 
 // === Custom Scanner Commands ===
@@ -140,6 +343,14 @@ async fn register_custom_scanner(config: CustomScannerConfig) -> Result<(), Stri
     Ok(())
 }
 
+/// Validate a scanner and show what it would find, without registering it
+#[tauri::command]
+async fn preview_custom_scanner(config: CustomScannerConfig) -> Result<ScanResult, String> {
+    cache::custom_scanner::preview_custom_scanner(config)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn list_custom_scanners() -> Result<Vec<CustomScannerConfig>, String> {
     let registry = get_registry().await;
@@ -166,9 +377,57 @@ async fn scan_custom_caches() -> Result<Vec<ScanResult>, String> {
 }
 
 #[tauri::command]
-async fn clean_custom_cache(id: String, dry_run: bool) -> Result<CleanResultGeneric, String> {
+async fn clean_custom_cache(id: String, mode: Option<DeleteMode>) -> Result<CleanResultGeneric, String> {
+    let dry_run = mode.unwrap_or_default().is_preview();
+    let _lock = cache::operation_lock::exclusive().await;
     let registry = get_registry().await;
-    registry.clean_custom(&id, dry_run).await.map_err(|e| e.to_string())
+    let result = registry.clean_custom(&id, dry_run).await.map_err(|e| e.to_string())?;
+    cache::audit::record(AuditEntry::new(format!("clean_custom:{}", id), Vec::new(), result.freed_bytes, dry_run));
+    if !dry_run {
+        cache::scan_results::invalidate_scan_cache().await;
+    }
+    Ok(result)
+}
+
+// === Config Import/Export Commands ===
+
+/// Reset tunables to their defaults, keeping scanners, exclusions and pins
+#[tauri::command]
+async fn reset_settings_to_defaults() -> Result<AppConfig, String> {
+    cache::config::reset_settings_to_defaults().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn export_config(path: String) -> Result<(), String> {
+    let app_config = AppConfig::load().map_err(|e| e.to_string())?;
+    app_config
+        .export_to(std::path::Path::new(&path))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn import_config(path: String, merge: bool) -> Result<ImportSummary, String> {
+    // An unreadable config must not be silently replaced by defaults on save
+    let mut app_config = AppConfig::load().map_err(|e| e.to_string())?;
+    let summary = app_config
+        .import_from(std::path::Path::new(&path), merge)
+        .map_err(|e| e.to_string())?;
+    app_config.save().map_err(|e| e.to_string())?;
+
+    // Keep the live registry in sync with the imported scanners
+    let registry = get_registry().await;
+    if !merge {
+        for existing in registry.list().await {
+            if !app_config.custom_scanners.iter().any(|s| s.id == existing.id) {
+                registry.unregister(&existing.id).await;
+            }
+        }
+    }
+    for scanner_config in app_config.custom_scanners {
+        registry.register(scanner_config).await.map_err(|e| e.to_string())?;
+    }
+
+    Ok(summary)
 }
 
 // === Smart Suggestions Commands ===
@@ -181,14 +440,76 @@ async fn scan_smart_suggestions(min_size_mb: Option<u64>, max_age_days: Option<u
     ).await.map_err(|e| e.to_string())
 }
 
+/// Like `scan_smart_suggestions`, but bounded in concurrency and time
+#[tauri::command]
+async fn scan_smart_suggestions_bounded(
+    min_size_mb: Option<u64>,
+    max_age_days: Option<u64>,
+    options: Option<SuggestionScanOptions>,
+) -> Result<SuggestionScan, String> {
+    cache::smart_suggestions::scan_suggestions_with(
+        min_size_mb.unwrap_or(100),
+        max_age_days.unwrap_or(30),
+        options.unwrap_or_default(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn get_folder_suggestion_info(path: String) -> Result<FolderSuggestion, String> {
     cache::smart_suggestions::get_folder_info(&path).await.map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn analyze_arbitrary_folder(path: String, min_size_bytes: u64) -> Result<FolderSuggestion, String> {
+    cache::smart_suggestions::analyze_arbitrary_folder(&path, min_size_bytes).await.map_err(|e| e.to_string())
+}
+
+/// What a path is and whether it is safe to clean, for tooltips
+#[tauri::command]
+async fn explain_path(path: String) -> Result<cache::explain::PathExplanation, String> {
+    cache::explain::explain_path(&path).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 async fn remove_smart_suggestions(paths: Vec<String>) -> Result<SmartSuggestionsCleanResult, String> {
-    cache::smart_suggestions::remove_suggested_folders(paths).await.map_err(|e| e.to_string())
+    let _lock = cache::operation_lock::exclusive().await;
+    let result = cache::smart_suggestions::remove_suggested_folders(paths.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    cache::audit::record(AuditEntry::new("remove_smart_suggestions", paths, result.total_freed_bytes, false));
+    cache::scan_results::invalidate_scan_cache().await;
+    Ok(result)
+}
+
+// === Cleanup Plan Commands ===
+
+/// Suggestions to clean so that free space reaches `target_free_gb`
+#[tauri::command]
+async fn suggest_to_reach_free_space(target_free_gb: f64) -> Result<CleanupPlan, String> {
+    let target_free_bytes = (target_free_gb * 1_000_000_000.0) as u64;
+    cache::cleanup_plan::suggest_to_reach_free_space(target_free_bytes)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// === Preflight Commands ===
+
+/// Check that each path could actually be deleted, without deleting anything
+#[tauri::command]
+async fn preflight_clean(paths: Vec<String>) -> Result<Vec<PreflightResult>, String> {
+    Ok(cache::preflight::preflight_paths(&paths))
+}
+
+// === Duplicate Files Commands ===
+
+/// Report identical files across the given roots (read-only)
+#[tauri::command]
+async fn find_duplicate_files(roots: Vec<String>, min_size_bytes: u64) -> Result<Vec<DuplicateGroup>, String> {
+    cache::duplicates::find_duplicate_files(roots, min_size_bytes)
+        .await
+        .map_err(|e| e.to_string())
 }
 
 // === Docker Cleanup Commands ===
@@ -207,6 +528,12 @@ async fn scan_docker() -> Result<DockerScanResult, String> {
         .map_err(|e| e.to_string())
 }
 
+/// Space an image prune would free, with shared layers counted once
+#[tauri::command]
+async fn estimate_docker_prune_reclaim() -> Result<u64, String> {
+    Ok(cache::docker::estimated_prune_reclaim().await)
+}
+
 /// Get smart suggestions for Docker cleanup
 #[tauri::command]
 async fn get_docker_suggestions() -> Result<Vec<DockerSuggestion>, String> {
@@ -215,134 +542,597 @@ async fn get_docker_suggestions() -> Result<Vec<DockerSuggestion>, String> {
         .map_err(|e| e.to_string())
 }
 
+/// Current Docker suggestions as a reviewable shell script
+#[tauri::command]
+async fn export_docker_plan() -> Result<String, String> {
+    cache::docker::export_docker_plan()
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Remove specific Docker containers
 #[tauri::command]
 async fn clean_docker_containers(ids: Vec<String>, force: bool) -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let targets = ids.clone();
     cache::docker::remove_containers(ids, force)
         .await
+        .map(|result| audit_docker("docker_remove_containers", targets, result))
         .map_err(|e| e.to_string())
 }
 
 /// Remove specific Docker images
 #[tauri::command]
 async fn clean_docker_images(ids: Vec<String>, force: bool) -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let targets = ids.clone();
     cache::docker::remove_images(ids, force)
         .await
+        .map(|result| audit_docker("docker_remove_images", targets, result))
+        .map_err(|e| e.to_string())
+}
+
+/// Remove Docker images along with the stopped containers still using them
+#[tauri::command]
+async fn clean_docker_images_with_deps(ids: Vec<String>, force: bool) -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let targets = ids.clone();
+    cache::docker::remove_images_with_deps(ids, force)
+        .await
+        .map(|result| audit_docker("docker_remove_images_with_deps", targets, result))
         .map_err(|e| e.to_string())
 }
 
 /// Remove specific Docker volumes
 #[tauri::command]
 async fn clean_docker_volumes(names: Vec<String>) -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let targets = names.clone();
     cache::docker::remove_volumes(names)
         .await
+        .map(|result| audit_docker("docker_remove_volumes", targets, result))
         .map_err(|e| e.to_string())
 }
 
 /// Remove specific Docker networks
 #[tauri::command]
 async fn clean_docker_networks(ids: Vec<String>) -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let targets = ids.clone();
     cache::docker::remove_networks(ids)
         .await
+        .map(|result| audit_docker("docker_remove_networks", targets, result))
         .map_err(|e| e.to_string())
 }
 
 /// Prune Docker system (all unused resources)
 #[tauri::command]
 async fn docker_system_prune(all: bool, include_volumes: bool) -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
     cache::docker::docker_system_prune(all, include_volumes)
         .await
+        .map(|result| audit_docker("docker_system_prune", Vec::new(), result))
+        .map_err(|e| e.to_string())
+}
+
+/// Remove only dangling images, dead or never-started containers and orphan volumes
+#[tauri::command]
+async fn docker_safe_cleanup() -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    cache::docker::docker_safe_cleanup()
+        .await
+        .map(|result| audit_docker("docker_safe_cleanup", Vec::new(), result))
         .map_err(|e| e.to_string())
 }
 
 /// Prune Docker builder cache
 #[tauri::command]
 async fn docker_builder_prune() -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
     cache::docker::docker_builder_prune()
         .await
+        .map(|result| audit_docker("docker_builder_prune", Vec::new(), result))
         .map_err(|e| e.to_string())
 }
 
 /// Prune stopped containers
 #[tauri::command]
 async fn docker_prune_containers() -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
     cache::docker::prune_containers()
         .await
+        .map(|result| audit_docker("docker_prune_containers", Vec::new(), result))
         .map_err(|e| e.to_string())
 }
 
 /// Prune unused images (dangling or all)
 #[tauri::command]
 async fn docker_prune_images(all: bool) -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
     cache::docker::prune_images(all)
         .await
+        .map(|result| audit_docker("docker_prune_images", Vec::new(), result))
         .map_err(|e| e.to_string())
 }
 
 /// Prune unused volumes
 #[tauri::command]
 async fn docker_prune_volumes() -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
     cache::docker::prune_volumes()
         .await
+        .map(|result| audit_docker("docker_prune_volumes", Vec::new(), result))
         .map_err(|e| e.to_string())
 }
 
 /// Prune unused networks
 #[tauri::command]
 async fn docker_prune_networks() -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
     cache::docker::prune_networks()
         .await
+        .map(|result| audit_docker("docker_prune_networks", Vec::new(), result))
         .map_err(|e| e.to_string())
 }
 
 /// Clean Docker resources based on suggestions
 #[tauri::command]
 async fn clean_docker_suggestions(suggestions: Vec<DockerSuggestion>) -> Result<DockerCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let targets = suggestions.iter().map(|s| s.id.clone()).collect();
     cache::docker::clean_docker_suggestions(suggestions)
+        .await
+        .map(|result| audit_docker("docker_clean_suggestions", targets, result))
+        .map_err(|e| e.to_string())
+}
+
+// === iOS Backup Commands ===
+
+#[tauri::command]
+async fn scan_ios_backups(stale_after_days: Option<u64>) -> Result<Vec<IosBackupEntry>, String> {
+    cache::ios_backups::scan_ios_backups(
+        stale_after_days.unwrap_or(cache::ios_backups::DEFAULT_STALE_AFTER_DAYS),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// Remove only the selected backups, by folder id
+#[tauri::command]
+async fn remove_ios_backups(ids: Vec<String>) -> Result<IosBackupsCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let result = cache::ios_backups::remove_ios_backups(ids)
+        .await
+        .map_err(|e| e.to_string())?;
+    let paths = result.path_results.iter().map(|r| r.path.clone()).collect();
+    cache::audit::record(AuditEntry::new("remove_ios_backups", paths, result.total_freed_bytes, false));
+    cache::scan_results::invalidate_scan_cache().await;
+    Ok(result)
+}
+
+// === Xcode Archive Commands ===
+
+#[tauri::command]
+async fn scan_xcode_archives() -> Result<Vec<ArchiveEntry>, String> {
+    cache::xcode_archives::scan_xcode_archives()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Keep the newest `keep_recent_per_app` archives of each app, remove the rest
+#[tauri::command]
+async fn clean_xcode_archives(keep_recent_per_app: usize, mode: Option<DeleteMode>) -> Result<XcodeArchivesCleanResult, String> {
+    let dry_run = mode.unwrap_or_default().is_preview();
+    let _lock = cache::operation_lock::exclusive().await;
+    let result = cache::xcode_archives::clean_xcode_archives(keep_recent_per_app, dry_run)
+        .await
+        .map_err(|e| e.to_string())?;
+    let paths = result.path_results.iter().map(|r| r.path.clone()).collect();
+    cache::audit::record(AuditEntry::new("clean_xcode_archives", paths, result.total_freed_bytes, dry_run));
+    if !dry_run {
+        cache::scan_results::invalidate_scan_cache().await;
+    }
+    Ok(result)
+}
+
+// === Simulator Runtime Commands ===
+
+#[tauri::command]
+async fn scan_simulator_runtimes() -> Result<Vec<RuntimeEntry>, String> {
+    cache::simulator_runtimes::scan_simulator_runtimes()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_simulator_runtimes(identifiers: Vec<String>) -> Result<SimulatorRuntimesCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let result = cache::simulator_runtimes::delete_simulator_runtimes(identifiers.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    cache::audit::record(AuditEntry::new("delete_simulator_runtimes", identifiers, result.total_freed_bytes, false));
+    cache::scan_results::invalidate_scan_cache().await;
+    Ok(result)
+}
+
+// === Git Maintenance Commands ===
+
+#[tauri::command]
+async fn scan_git_maintenance(roots: Vec<String>) -> Result<Vec<GitRepoEntry>, String> {
+    cache::git_maintenance::scan_git_maintenance(roots)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn run_git_gc(paths: Vec<String>) -> Result<GitGcResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let result = cache::git_maintenance::run_git_gc(paths.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    cache::audit::record(AuditEntry::new("run_git_gc", paths, result.total_freed_bytes, false));
+    Ok(result)
+}
+
+// === Cold Artifact Commands ===
+
+#[tauri::command]
+async fn scan_cold_artifacts(cache_type: String, cold_days: u64) -> Result<Vec<LargeCacheEntry>, String> {
+    let ct = CacheType::from_str(&cache_type).map_err(|e| e.to_string())?;
+    cache::cold_artifacts::scan_cold_artifacts(ct, cold_days)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn remove_cold_artifacts(cache_type: String, paths: Vec<String>) -> Result<ColdArtifactsCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let ct = CacheType::from_str(&cache_type).map_err(|e| e.to_string())?;
+    let result = cache::cold_artifacts::remove_cold_artifacts(ct, paths.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    cache::audit::record(AuditEntry::new("remove_cold_artifacts", paths, result.total_freed_bytes, false));
+    cache::scan_results::invalidate_scan_cache().await;
+    Ok(result)
+}
+
+// === Media Cache Commands ===
+
+/// Messages and Photos caches, with how safe each is to remove
+#[tauri::command]
+async fn scan_media_app_caches() -> Result<Vec<LargeCacheEntry>, String> {
+    cache::media_caches::scan_media_app_caches()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Empty the selected media caches; Photos library contents are refused
+#[tauri::command]
+async fn clean_media_app_caches(paths: Vec<String>) -> Result<MediaCachesCleanResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let result = cache::media_caches::clean_media_app_caches(paths)
+        .await
+        .map_err(|e| e.to_string())?;
+    let paths = result.path_results.iter().map(|r| r.path.clone()).collect();
+    cache::audit::record(AuditEntry::new("clean_media_app_caches", paths, result.total_freed_bytes, false));
+    cache::scan_results::invalidate_scan_cache().await;
+    Ok(result)
+}
+
+// === JetBrains Commands ===
+
+/// Cache and log folders of every JetBrains IDE version; older versions are preselected
+#[tauri::command]
+async fn scan_jetbrains_caches() -> Result<Vec<LargeCacheEntry>, String> {
+    cache::jetbrains::scan_jetbrains_caches()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Remove the selected JetBrains version folders
+#[tauri::command]
+async fn clean_jetbrains_caches(paths: Vec<String>, mode: Option<DeleteMode>) -> Result<JetBrainsCleanResult, String> {
+    let dry_run = mode.unwrap_or_default().is_preview();
+    let _lock = cache::operation_lock::exclusive().await;
+    let result = cache::jetbrains::clean_jetbrains_caches(paths, dry_run)
+        .await
+        .map_err(|e| e.to_string())?;
+    let paths = result.path_results.iter().map(|r| r.path.clone()).collect();
+    cache::audit::record(AuditEntry::new("clean_jetbrains_caches", paths, result.total_freed_bytes, dry_run));
+    if !dry_run {
+        cache::scan_results::invalidate_scan_cache().await;
+    }
+    Ok(result)
+}
+
+// === Old Log Commands ===
+
+/// Log files in the log folders not written for `older_than_days`
+#[tauri::command]
+async fn scan_old_logs(older_than_days: u64) -> Result<Vec<LargeCacheEntry>, String> {
+    cache::old_logs::scan_old_logs(older_than_days)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Delete log files older than `older_than_days`, leaving every other file alone
+#[tauri::command]
+async fn clean_old_logs(older_than_days: u64, mode: Option<DeleteMode>) -> Result<OldLogsCleanResult, String> {
+    let dry_run = mode.unwrap_or_default().is_preview();
+    let _lock = cache::operation_lock::exclusive().await;
+    let result = cache::old_logs::clean_old_logs(older_than_days, dry_run)
+        .await
+        .map_err(|e| e.to_string())?;
+    let paths = result.path_results.iter().map(|r| r.path.clone()).collect();
+    cache::audit::record(AuditEntry::new("clean_old_logs", paths, result.total_freed_bytes, dry_run));
+    if !dry_run {
+        cache::scan_results::invalidate_scan_cache().await;
+    }
+    Ok(result)
+}
+
+// === Chrome Profile Commands ===
+
+/// Cache folder of every Chrome profile, with the profile's display name
+#[tauri::command]
+async fn scan_chrome_profiles() -> Result<Vec<ChromeProfileCache>, String> {
+    cache::chrome_profiles::scan_chrome_profiles()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Empty one profile's cache; refused while Chrome is running
+#[tauri::command]
+async fn clean_chrome_profile_cache(profile: String, mode: Option<DeleteMode>) -> Result<ChromeProfileCleanResult, String> {
+    let dry_run = mode.unwrap_or_default().is_preview();
+    let _lock = cache::operation_lock::exclusive().await;
+    let result = cache::chrome_profiles::clean_chrome_profile_cache(profile, dry_run)
+        .await
+        .map_err(|e| e.to_string())?;
+    let paths = cache::paths::MacPaths::chrome_cache()
+        .map(|base| vec![base.join(&result.profile).display().to_string()])
+        .unwrap_or_default();
+    cache::audit::record(AuditEntry::new("clean_chrome_profile_cache", paths, result.total_freed_bytes, result.dry_run));
+    if !result.dry_run {
+        cache::scan_results::invalidate_scan_cache().await;
+    }
+    Ok(result)
+}
+
+// === App Data Commands ===
+
+#[tauri::command]
+async fn get_app_footprint() -> Result<AppFootprint, String> {
+    cache::app_data::get_app_footprint().map_err(|e| e.to_string())
+}
+
+/// Clear config, index and trash
+#[tauri::command]
+async fn reset_app_data() -> Result<(), String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let footprint = cache::app_data::get_app_footprint().map_err(|e| e.to_string())?;
+    cache::app_data::reset_app_data().map_err(|e| e.to_string())?;
+    cache::audit::record(AuditEntry::new("reset_app_data", vec![footprint.data_dir], footprint.total_bytes, false));
+    Ok(())
+}
+
+// === Deep Clean Commands ===
+
+/// Clean every safe cache in order. Without `options`, exclusions, pinned
+/// paths and the trash setting come from the saved config.
+#[tauri::command]
+async fn deep_clean(options: Option<DeepCleanOptions>, mode: Option<DeleteMode>) -> Result<DeepCleanResult, String> {
+    let dry_run = mode.unwrap_or_default().is_preview();
+    let _lock = cache::operation_lock::exclusive().await;
+    let options = match options {
+        Some(options) => options,
+        None => DeepCleanOptions::from_config(&AppConfig::load().map_err(|e| e.to_string())?),
+    };
+    let use_trash = options.use_trash;
+    let result = cache::deep_clean::deep_clean(options, dry_run).map_err(|e| e.to_string())?;
+    let paths = result.results.iter().flat_map(|r| audit_paths(&r.cache_type)).collect();
+    cache::audit::record(AuditEntry::new("deep_clean", paths, result.total_freed_bytes, dry_run).trashed(use_trash));
+    if !dry_run {
+        cache::scan_results::invalidate_scan_cache().await;
+    }
+    Ok(result)
+}
+
+// === Audit Log Commands ===
+
+/// The most recent audit log entries, newest first
+#[tauri::command]
+async fn get_audit_log(limit: Option<usize>) -> Result<Vec<AuditEntry>, String> {
+    cache::audit::get_audit_log(limit.unwrap_or(100)).map_err(|e| e.to_string())
+}
+
+// === Trash Commands ===
+
+/// List the files inside a trashed cache folder
+#[tauri::command]
+async fn list_trashed_operation_contents(operation_id: String) -> Result<Vec<TrashedFile>, String> {
+    cache::trash::list_trashed_operation_contents(&operation_id).map_err(|e| e.to_string())
+}
+
+/// Trash all of `paths` or, if any of them fails, none of them
+#[tauri::command]
+async fn remove_paths_transactional(paths: Vec<String>, use_trash: Option<bool>) -> Result<Vec<TrashedOperation>, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    let targets: Vec<std::path::PathBuf> = paths.iter().map(std::path::PathBuf::from).collect();
+    let operations = cache::trash::remove_paths_transactional(&targets, use_trash.unwrap_or(true))
+        .map_err(|e| e.to_string())?;
+    cache::audit::record(AuditEntry::new("remove_paths_transactional", paths, 0, false).trashed(true));
+    cache::scan_results::invalidate_scan_cache().await;
+    Ok(operations)
+}
+
+/// Restore selected files from a trashed cache folder
+#[tauri::command]
+async fn restore_trashed_files(operation_id: String, relative_paths: Vec<String>) -> Result<RestoreResult, String> {
+    let _lock = cache::operation_lock::exclusive().await;
+    cache::trash::restore_trashed_files(&operation_id, relative_paths).map_err(|e| e.to_string())
+}
+
 fn main() {
+    cache::guard::init_allow_root(std::env::args());
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            events::bus().attach(app.handle().clone());
+            if let Err(e) = cache::guard::validate_whitelists() {
+                eprintln!("[Rust] Whitelist validation failed: {}", e);
+                // Fail fast while developing; release builds keep running
+                if cfg!(debug_assertions) {
+                    return Err(e.into());
+                }
+            }
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             scan_caches,
+            scan_caches_fast,
             get_cache_size,
             clean_cache,
+            clean_cache_with_progress,
+            scan_by_category,
+            clean_by_category,
+            diagnose_cache,
             check_chrome_running,
             check_permissions,
+            request_permissions_with_guidance,
+            open_full_disk_access_settings,
+            get_disk_usage,
+            get_api_version,
+            get_metrics,
+            reset_metrics,
+            check_scheduled_work_postponed,
+            query_cache_index,
+            refresh_cache_index,
+            start_watching,
+            stop_watching,
+            save_scan_baseline,
+            compare_to_baseline,
+            run_self_test,
             scan_indexed_db_items,
             clean_indexed_db_items,
             scan_npm_caches,
             remove_npm_caches,
             // Custom scanner commands
+            preview_custom_scanner,
             register_custom_scanner,
             list_custom_scanners,
             remove_custom_scanner,
             scan_custom_caches,
             clean_custom_cache,
+            // Config import/export commands
+            reset_settings_to_defaults,
+            export_config,
+            import_config,
             // Smart suggestions commands
             scan_smart_suggestions,
+            scan_smart_suggestions_bounded,
             get_folder_suggestion_info,
+            analyze_arbitrary_folder,
+            explain_path,
             remove_smart_suggestions,
+            // Cleanup plan commands
+            suggest_to_reach_free_space,
+            // Preflight commands
+            preflight_clean,
+            // Duplicate files commands
+            find_duplicate_files,
             // Docker cleanup commands
             check_docker_status,
             scan_docker,
+            estimate_docker_prune_reclaim,
             get_docker_suggestions,
+            export_docker_plan,
             clean_docker_containers,
             clean_docker_images,
+            clean_docker_images_with_deps,
             clean_docker_volumes,
             clean_docker_networks,
             docker_system_prune,
+            docker_safe_cleanup,
             docker_builder_prune,
             docker_prune_containers,
             docker_prune_images,
             docker_prune_volumes,
             docker_prune_networks,
             clean_docker_suggestions,
+            // iOS backup commands
+            scan_ios_backups,
+            remove_ios_backups,
+            // Xcode archive commands
+            scan_xcode_archives,
+            clean_xcode_archives,
+            // Simulator runtime commands
+            scan_simulator_runtimes,
+            delete_simulator_runtimes,
+            // Git maintenance commands
+            scan_git_maintenance,
+            run_git_gc,
+            // Cold artifact commands
+            scan_cold_artifacts,
+            remove_cold_artifacts,
+            // Media cache commands
+            scan_media_app_caches,
+            clean_media_app_caches,
+            scan_jetbrains_caches,
+            clean_jetbrains_caches,
+            scan_old_logs,
+            clean_old_logs,
+            estimate_cleanup_duration,
+            // Chrome profile commands
+            scan_chrome_profiles,
+            clean_chrome_profile_cache,
+            // App data commands
+            get_app_footprint,
+            reset_app_data,
+            // Deep clean commands
+            deep_clean,
+            // Audit log commands
+            get_audit_log,
+            // Trash commands
+            list_trashed_operation_contents,
+            restore_trashed_files,
+            remove_paths_transactional,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Queued audit entries must reach disk before the process ends
+                cache::audit::flush();
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "readonly"))]
+    #[tokio::test]
+    async fn test_concurrent_cleans_are_serialized() {
+        // This test binary holds only this test, so the environment is ours
+        let home = tempfile::tempdir().unwrap();
+        let blob = home.path().join(".npm/_cacache/content-v2/blob");
+        std::fs::create_dir_all(blob.parent().unwrap()).unwrap();
+        std::fs::write(&blob, [0u8; 4096]).unwrap();
+        std::env::set_var("HOME", home.path());
+        std::env::set_var(utils::home::DATA_DIR_ENV, home.path().join(".cache-cleaner"));
+        cache::guard::init_allow_root([cache::guard::ALLOW_ROOT_FLAG.to_string()]);
+
+        let (first, second) = tokio::join!(
+            clean_cache("npm".to_string(), Some(DeleteMode::Commit), None),
+            clean_cache("npm".to_string(), Some(DeleteMode::Commit), None),
+        );
+
+        // The second clean waits for the first and measures what is left
+        assert!(first.unwrap().freed_bytes > 0);
+        assert_eq!(second.unwrap().freed_bytes, 0);
+        assert!(!blob.exists());
+    }
 }
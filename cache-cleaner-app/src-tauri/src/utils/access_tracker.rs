@@ -40,3 +40,8 @@ pub fn days_since_access(info: &FolderAccessInfo) -> Option<u64> {
     
     info.last_accessed.map(|accessed| (now - accessed) / 86400)
 }
+
+/// Most recent of the access and modification times, seconds since epoch
+pub fn last_used(info: &FolderAccessInfo) -> Option<u64> {
+    info.last_accessed.max(info.last_modified)
+}
@@ -0,0 +1,57 @@
+//! Command lines run without a shell.
+//!
+//! Configured commands (manifest cleaners, custom scanner clean commands)
+//! are split on whitespace and passed to `Command` as separate arguments.
+//! Anything a shell would interpret is rejected outright, so a command can
+//! never smuggle in redirection, substitution or a second command. Paths
+//! are always passed with `Command::arg`, never formatted into a string.
+
+use anyhow::{anyhow, Result};
+
+/// Characters with special meaning to a shell
+const FORBIDDEN_COMMAND_CHARS: &[char] = &[
+    ';', '&', '|', '`', '$', '<', '>', '(', ')', '{', '}', '\\', '\'', '"', '*', '?', '!', '~', '\n', '\r',
+];
+
+/// Split `command` into program and arguments, rejecting shell metacharacters
+pub fn split_command_line(command: &str) -> Result<Vec<String>> {
+    if let Some(c) = command.chars().find(|c| FORBIDDEN_COMMAND_CHARS.contains(c)) {
+        return Err(anyhow!("Command contains forbidden character '{}'", c.escape_default()));
+    }
+    let argv: Vec<String> = command.split_whitespace().map(|s| s.to_string()).collect();
+    if argv.is_empty() {
+        return Err(anyhow!("Command is empty"));
+    }
+    Ok(argv)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(split_command_line("go clean -cache").unwrap(), vec!["go", "clean", "-cache"]);
+        assert_eq!(
+            split_command_line("brew cleanup --prune=all").unwrap(),
+            vec!["brew", "cleanup", "--prune=all"]
+        );
+        assert!(split_command_line("   ").is_err());
+    }
+
+    #[test]
+    fn test_shell_metacharacters_rejected() {
+        for command in [
+            "go clean -cache; rm -rf /",
+            "docker builder prune && curl x | sh",
+            "echo $(whoami)",
+            "echo `whoami`",
+            "rm -rf ~",
+            "cat < /etc/passwd",
+            "rm \"quoted path\"",
+            "brew cleanup\nrm -rf /",
+        ] {
+            assert!(split_command_line(command).is_err(), "{:?}", command);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// Space on the boot volume as reported by `diskutil`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskUsage {
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    /// Space macOS marks as purgeable (local snapshots, iCloud-evictable files).
+    /// The OS reclaims it on its own under storage pressure; this app does not
+    /// and cannot clean it, it is only reported so free-space numbers add up.
+    pub purgeable_bytes: u64,
+}
+
+/// Disk usage of the boot volume
+pub fn get_disk_usage() -> Result<DiskUsage> {
+    parse_disk_usage(&diskutil_info_plist()?)
+}
+
+/// Purgeable bytes on the boot volume, 0 when macOS does not report any
+pub fn get_purgeable_space() -> Result<u64> {
+    Ok(parse_purgeable_space(&diskutil_info_plist()?))
+}
+
+/// Source of free-space readings, swapped out in tests
+pub trait DiskStats: Send + Sync {
+    fn free_bytes(&self) -> Result<u64>;
+}
+
+/// Free space of the boot volume from `diskutil`
+pub struct SystemDiskStats;
+
+impl DiskStats for SystemDiskStats {
+    fn free_bytes(&self) -> Result<u64> {
+        Ok(get_disk_usage()?.free_bytes)
+    }
+}
+
+fn diskutil_info_plist() -> Result<String> {
+    let output = Command::new("diskutil").args(["info", "-plist", "/"]).output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "diskutil failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn parse_disk_usage(plist: &str) -> Result<DiskUsage> {
+    let total_bytes = plist_integer(plist, "TotalSize")
+        .ok_or_else(|| anyhow!("diskutil output has no TotalSize"))?;
+    // APFS volumes share their container's free space
+    let free_bytes = plist_integer(plist, "APFSContainerFree")
+        .or_else(|| plist_integer(plist, "FreeSpace"))
+        .ok_or_else(|| anyhow!("diskutil output has no free space value"))?;
+
+    Ok(DiskUsage {
+        total_bytes,
+        free_bytes,
+        purgeable_bytes: parse_purgeable_space(plist),
+    })
+}
+
+fn parse_purgeable_space(plist: &str) -> u64 {
+    plist_integer(plist, "PurgeableSpace").unwrap_or(0)
+}
+
+/// Value of `<key>name</key><integer>…</integer>` in an XML plist
+fn plist_integer(plist: &str, key: &str) -> Option<u64> {
+    let key_tag = format!("<key>{}</key>", key);
+    let after_key = &plist[plist.find(&key_tag)? + key_tag.len()..];
+    let value = after_key.trim_start().strip_prefix("<integer>")?;
+    let end = value.find("</integer>")?;
+    value[..end].trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>APFSContainerFree</key>
+	<integer>120000000000</integer>
+	<key>APFSContainerSize</key>
+	<integer>494384795648</integer>
+	<key>FilesystemType</key>
+	<string>apfs</string>
+	<key>FreeSpace</key>
+	<integer>110000000000</integer>
+	<key>PurgeableSpace</key>
+	<integer>8589934592</integer>
+	<key>TotalSize</key>
+	<integer>494384795648</integer>
+	<key>VolumeName</key>
+	<string>Macintosh HD</string>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn test_parse_purgeable_space() {
+        assert_eq!(parse_purgeable_space(SAMPLE_PLIST), 8_589_934_592);
+    }
+
+    #[test]
+    fn test_parse_purgeable_space_missing() {
+        let plist = "<dict><key>TotalSize</key><integer>100</integer></dict>";
+        assert_eq!(parse_purgeable_space(plist), 0);
+    }
+
+    #[test]
+    fn test_parse_disk_usage() {
+        let usage = parse_disk_usage(SAMPLE_PLIST).unwrap();
+        assert_eq!(usage.total_bytes, 494_384_795_648);
+        assert_eq!(usage.free_bytes, 120_000_000_000);
+        assert_eq!(usage.purgeable_bytes, 8_589_934_592);
+    }
+
+    #[test]
+    fn test_plist_integer_ignores_non_integer_values() {
+        assert_eq!(plist_integer(SAMPLE_PLIST, "VolumeName"), None);
+        assert_eq!(plist_integer(SAMPLE_PLIST, "Missing"), None);
+    }
+}
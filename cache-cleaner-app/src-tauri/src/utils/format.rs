@@ -0,0 +1,236 @@
+use crate::cache::config::AppConfig;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+const BINARY_UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+const DECIMAL_UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+static CONFIGURED: OnceLock<SizeUnitPreference> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeUnits {
+    /// Powers of 1024: KiB, MiB, GiB
+    #[default]
+    Binary,
+    /// Powers of 1000: KB, MB, GB, as Finder shows them
+    Decimal,
+}
+
+/// How sizes in messages and reasons are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeUnitPreference {
+    #[serde(default)]
+    pub units: SizeUnits,
+    /// Digits after the decimal point
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+}
+
+fn default_decimals() -> u8 {
+    1
+}
+
+impl Default for SizeUnitPreference {
+    fn default() -> Self {
+        Self {
+            units: SizeUnits::default(),
+            decimals: default_decimals(),
+        }
+    }
+}
+
+impl SizeUnitPreference {
+    pub fn format(&self, bytes: u64) -> String {
+        format_units(bytes, self.units == SizeUnits::Binary, self.decimals as usize, '.')
+    }
+}
+
+/// The preference from the config, read once per process
+pub fn configured() -> SizeUnitPreference {
+    *CONFIGURED.get_or_init(|| AppConfig::load().map(|c| c.size_units).unwrap_or_default())
+}
+
+/// Format a byte count the way the user asked for in the config
+pub fn format_size(bytes: u64) -> String {
+    configured().format(bytes)
+}
+
+/// Format a byte count for display, in binary (KiB, MiB) or decimal (KB, MB) units
+pub fn human_size(bytes: u64, binary: bool) -> String {
+    human_size_with_locale(bytes, binary, '.')
+}
+
+/// Same as `human_size`, with a locale-specific decimal separator ("1,5 GB")
+pub fn human_size_with_locale(bytes: u64, binary: bool, decimal_separator: char) -> String {
+    format_units(bytes, binary, 1, decimal_separator)
+}
+
+fn format_units(bytes: u64, binary: bool, decimals: usize, decimal_separator: char) -> String {
+    let (base, units) = if binary {
+        (1024.0, &BINARY_UNITS)
+    } else {
+        (1000.0, &DECIMAL_UNITS)
+    };
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= base && unit < units.len() - 1 {
+        value /= base;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        return format!("{} {}", bytes, units[0]);
+    }
+
+    let formatted = format!("{:.*}", decimals, value);
+    if decimal_separator == '.' {
+        format!("{} {}", formatted, units[unit])
+    } else {
+        format!("{} {}", formatted.replace('.', &decimal_separator.to_string()), units[unit])
+    }
+}
+
+/// Parse a size as printed by CLI tools (e.g., "1.5GB", "500MB", "100kB") to bytes
+pub fn parse_size(size_str: &str) -> u64 {
+    let size_str = size_str.trim();
+    
+    // Handle "0B" or empty
+    if size_str.is_empty() || size_str == "0B" || size_str == "0" {
+        return 0;
+    }
+
+    // Extract number and unit
+    let mut num_str = String::new();
+    let mut unit_str = String::new();
+    let mut in_unit = false;
+
+    for c in size_str.chars() {
+        if c.is_ascii_digit() || c == '.' {
+            if !in_unit {
+                num_str.push(c);
+            }
+        } else if c.is_alphabetic() {
+            in_unit = true;
+            unit_str.push(c);
+        }
+    }
+
+    let num: f64 = num_str.parse().unwrap_or(0.0);
+    let unit = unit_str.to_uppercase();
+
+    let multiplier: u64 = match unit.as_str() {
+        "B" => 1,
+        "KB" | "K" => 1024,
+        "MB" | "M" => 1024 * 1024,
+        "GB" | "G" => 1024 * 1024 * 1024,
+        "TB" | "T" => 1024 * 1024 * 1024 * 1024,
+        _ => 1,
+    };
+
+    (num * multiplier as f64) as u64
+}
+
+/// Parse reclaimed space from prune/clean output, e.g. "Total reclaimed space: 1.5GB"
+pub fn parse_reclaimed_space(output: &str) -> u64 {
+    // Look for patterns like "Total reclaimed space: 1.5GB"
+    for line in output.lines() {
+        let lower = line.to_lowercase();
+        if lower.contains("reclaimed") || lower.contains("freed") {
+            // Find the size part
+            let parts: Vec<&str> = line.split(':').collect();
+            if parts.len() >= 2 {
+                return parse_size(parts[1].trim());
+            }
+            // Try to find size pattern in the line
+            for word in line.split_whitespace() {
+                if word.chars().any(|c| c.is_ascii_digit()) 
+                    && word.chars().any(|c| c.is_alphabetic()) 
+                {
+                    let size = parse_size(word);
+                    if size > 0 {
+                        return size;
+                    }
+                }
+            }
+        }
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_boundaries() {
+        assert_eq!(human_size(0, true), "0 B");
+        assert_eq!(human_size(1023, true), "1023 B");
+        assert_eq!(human_size(1024, true), "1.0 KiB");
+        assert_eq!(human_size(1024 * 1024, true), "1.0 MiB");
+        assert_eq!(human_size(1024 * 1024 * 1024, true), "1.0 GiB");
+        assert_eq!(human_size(1024 * 1024 * 1024 * 1024, true), "1.0 TiB");
+        assert_eq!(human_size((1.5 * 1024.0 * 1024.0 * 1024.0) as u64, true), "1.5 GiB");
+    }
+
+    #[test]
+    fn test_decimal_boundaries() {
+        assert_eq!(human_size(999, false), "999 B");
+        assert_eq!(human_size(1000, false), "1.0 KB");
+        assert_eq!(human_size(1024, false), "1.0 KB");
+        assert_eq!(human_size(1_000_000_000, false), "1.0 GB");
+        assert_eq!(human_size(1_500_000_000, false), "1.5 GB");
+    }
+
+    #[test]
+    fn test_largest_unit_is_capped() {
+        assert_eq!(human_size(1024u64.pow(5), true), "1024.0 TiB");
+    }
+
+    #[test]
+    fn test_locale_separator() {
+        assert_eq!(human_size_with_locale(1_500_000_000, false, ','), "1,5 GB");
+        assert_eq!(human_size_with_locale(1023, true, ','), "1023 B");
+        assert_eq!(human_size_with_locale(1536, true, '.'), "1.5 KiB");
+    }
+
+    #[test]
+    fn test_size_unit_preference() {
+        let gib = 1024 * 1024 * 1024;
+        let binary = SizeUnitPreference { units: SizeUnits::Binary, decimals: 2 };
+        let decimal = SizeUnitPreference { units: SizeUnits::Decimal, decimals: 2 };
+        assert_eq!(binary.format(gib), "1.00 GiB");
+        assert_eq!(decimal.format(gib), "1.07 GB");
+        assert_eq!(decimal.format(512), "512 B");
+        assert_eq!(SizeUnitPreference::default().format(gib), human_size(gib, true));
+
+        let parsed: SizeUnitPreference = serde_json::from_str(r#"{"units": "decimal"}"#).unwrap();
+        assert_eq!(parsed, SizeUnitPreference { units: SizeUnits::Decimal, decimals: 1 });
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("0B"), 0);
+        assert_eq!(parse_size("100B"), 100);
+        assert_eq!(parse_size("1KB"), 1024);
+        assert_eq!(parse_size("1.5KB"), 1536);
+        assert_eq!(parse_size("1MB"), 1024 * 1024);
+        assert_eq!(parse_size("1.5MB"), (1.5 * 1024.0 * 1024.0) as u64);
+        assert_eq!(parse_size("1GB"), 1024 * 1024 * 1024);
+        assert_eq!(parse_size("2.5GB"), (2.5 * 1024.0 * 1024.0 * 1024.0) as u64);
+    }
+
+    #[test]
+    fn test_parse_reclaimed_space() {
+        assert_eq!(
+            parse_reclaimed_space("Total reclaimed space: 1.5GB"),
+            (1.5 * 1024.0 * 1024.0 * 1024.0) as u64
+        );
+        assert_eq!(
+            parse_reclaimed_space("Reclaimed space: 500MB"),
+            500 * 1024 * 1024
+        );
+        assert_eq!(parse_reclaimed_space("No space reclaimed"), 0);
+    }
+}
@@ -0,0 +1,85 @@
+//! Streaming file hashes.
+//!
+//! Files are read through a fixed 8 KB buffer, so hashing a multi-gigabyte
+//! tarball uses no more memory than hashing a small file.
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::Path;
+
+const HASH_BUFFER_SIZE: usize = 8 * 1024;
+
+/// SHA-256 of a file's full content, lowercase hex
+pub fn hash_file(path: &Path) -> Result<String> {
+    hash_reader(std::fs::File::open(path)?, u64::MAX)
+}
+
+/// SHA-256 of the first block of a file only.
+///
+/// A cheap prefilter: files whose first blocks differ cannot be identical,
+/// so only the ones that match need a full `hash_file`.
+pub fn hash_first_block(path: &Path) -> Result<String> {
+    hash_reader(std::fs::File::open(path)?, HASH_BUFFER_SIZE as u64)
+}
+
+fn hash_reader(reader: impl Read, limit: u64) -> Result<String> {
+    let mut reader = reader.take(limit);
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; HASH_BUFFER_SIZE];
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    #[test]
+    fn test_hash_file_known_digest() {
+        let dir = create_test_dir();
+        let file = dir.path().join("abc.txt");
+        fs::write(&file, b"abc").unwrap();
+        assert_eq!(
+            hash_file(&file).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // Shorter than one block, so the prefilter sees the whole file
+        assert_eq!(hash_first_block(&file).unwrap(), hash_file(&file).unwrap());
+    }
+
+    #[test]
+    fn test_hash_large_file_streams() {
+        let dir = create_test_dir();
+        let path = dir.path().join("large.tar");
+        let chunk: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+
+        // 32 MiB written and hashed chunk by chunk, never held in memory at once
+        let mut file = File::create(&path).unwrap();
+        let mut expected = Sha256::new();
+        for _ in 0..512 {
+            file.write_all(&chunk).unwrap();
+            expected.update(&chunk);
+        }
+        drop(file);
+
+        assert_eq!(hash_file(&path).unwrap(), format!("{:x}", expected.finalize()));
+        assert_eq!(
+            hash_first_block(&path).unwrap(),
+            format!("{:x}", Sha256::digest(&chunk[..HASH_BUFFER_SIZE]))
+        );
+    }
+}
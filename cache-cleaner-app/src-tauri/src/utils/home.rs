@@ -0,0 +1,111 @@
+use crate::cache::CacheError;
+use std::path::{Path, PathBuf};
+
+/// Resolve the user's home directory, or fail with a single clear error.
+///
+/// Never falls back to `/`: a wrong home directory is dangerous for deletion.
+pub fn require_home() -> Result<PathBuf, CacheError> {
+    #[cfg(test)]
+    if let Some(home) = HOME_FOR_THREAD.with(|home| home.borrow().clone()) {
+        return Ok(home);
+    }
+    resolve_home(dirs::home_dir())
+}
+
+#[cfg(test)]
+thread_local! {
+    static HOME_FOR_THREAD: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Home directory override for the current test thread only, so a test can
+/// run real cleaners against a fixture home
+#[cfg(test)]
+pub(crate) fn set_home_for_thread(home: Option<PathBuf>) {
+    HOME_FOR_THREAD.with(|current| *current.borrow_mut() = home);
+}
+
+fn resolve_home(home: Option<PathBuf>) -> Result<PathBuf, CacheError> {
+    match home {
+        Some(h) if h.is_absolute() && h != Path::new("/") => Ok(h),
+        _ => Err(CacheError::HomeNotFound),
+    }
+}
+
+/// Environment variable that moves the app's data directory, e.g. to keep
+/// one per user profile or to point a test run at a temporary folder
+pub const DATA_DIR_ENV: &str = "CACHE_CLEANER_DATA_DIR";
+
+#[cfg(test)]
+thread_local! {
+    static DATA_DIR_FOR_THREAD: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Where config, the index, trash, the audit log and stats live:
+/// `CACHE_CLEANER_DATA_DIR` when set to an absolute path, else `~/.cache-cleaner`
+pub fn app_data_dir() -> Result<PathBuf, CacheError> {
+    #[cfg(test)]
+    if let Some(dir) = DATA_DIR_FOR_THREAD.with(|dir| dir.borrow().clone()) {
+        return Ok(dir);
+    }
+    resolve_data_dir(std::env::var_os(DATA_DIR_ENV).map(PathBuf::from), require_home)
+}
+
+/// Data directory override for the current test thread only, since the
+/// environment is shared by tests running in parallel
+#[cfg(test)]
+pub(crate) fn set_data_dir_for_thread(dir: Option<PathBuf>) {
+    DATA_DIR_FOR_THREAD.with(|current| *current.borrow_mut() = dir);
+}
+
+fn resolve_data_dir(
+    configured: Option<PathBuf>,
+    home: impl FnOnce() -> Result<PathBuf, CacheError>,
+) -> Result<PathBuf, CacheError> {
+    // A relative value would depend on the working directory, and `/` would
+    // let a data reset empty the disk
+    match configured {
+        Some(dir) if dir.is_absolute() && dir != Path::new("/") => Ok(dir),
+        _ => Ok(home()?.join(".cache-cleaner")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_require_home_in_test_environment() {
+        let home = require_home().unwrap();
+        assert_ne!(home, PathBuf::from("/"));
+    }
+
+    #[test]
+    fn test_unavailable_home_is_an_error() {
+        assert!(matches!(resolve_home(None), Err(CacheError::HomeNotFound)));
+    }
+
+    #[test]
+    fn test_root_or_relative_home_is_rejected() {
+        assert!(resolve_home(Some(PathBuf::from("/"))).is_err());
+        assert!(resolve_home(Some(PathBuf::from(""))).is_err());
+        assert!(resolve_home(Some(PathBuf::from("relative/home"))).is_err());
+        assert!(resolve_home(Some(PathBuf::from("/Users/test"))).is_ok());
+    }
+
+    #[test]
+    fn test_data_dir_resolution() {
+        let home = || Ok(PathBuf::from("/Users/test"));
+        assert_eq!(resolve_data_dir(None, home).unwrap(), PathBuf::from("/Users/test/.cache-cleaner"));
+        assert_eq!(
+            resolve_data_dir(Some(PathBuf::from("/Volumes/Shared/alice")), home).unwrap(),
+            PathBuf::from("/Volumes/Shared/alice")
+        );
+        for ignored in ["", "relative/dir", "/"] {
+            assert_eq!(
+                resolve_data_dir(Some(PathBuf::from(ignored)), home).unwrap(),
+                PathBuf::from("/Users/test/.cache-cleaner")
+            );
+        }
+        assert!(resolve_data_dir(None, || Err(CacheError::HomeNotFound)).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+//! Optional I/O throttling for directory walks.
+//!
+//! A full scan reads directories as fast as the disk allows, which can make
+//! the whole Mac sluggish. With `ScanThrottle::max_dirs_per_sec` set, walks
+//! sleep whenever they get ahead of that rate, and on macOS the walking
+//! thread's disk I/O is marked throttled so other apps are served first.
+
+use crate::cache::config::AppConfig;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+static CONFIGURED: OnceLock<ScanThrottle> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanThrottle {
+    /// Directories read per second at most; `None` walks at full speed
+    #[serde(default)]
+    pub max_dirs_per_sec: Option<u32>,
+}
+
+impl ScanThrottle {
+    pub fn is_enabled(&self) -> bool {
+        self.max_dirs_per_sec.is_some_and(|max| max > 0)
+    }
+
+    /// Start pacing one walk
+    pub fn start(&self) -> DirPacer {
+        DirPacer {
+            max_dirs_per_sec: self.max_dirs_per_sec.filter(|max| *max > 0),
+            started: Instant::now(),
+            dirs: 0,
+            _priority: self.is_enabled().then(LowIoPriority::lower),
+        }
+    }
+}
+
+/// The throttle from the config, read once per process
+pub fn configured() -> ScanThrottle {
+    *CONFIGURED.get_or_init(|| AppConfig::load().map(|c| c.scan_throttle).unwrap_or_default())
+}
+
+/// Rate limiter for a single walk, created by `ScanThrottle::start`
+pub struct DirPacer {
+    max_dirs_per_sec: Option<u32>,
+    started: Instant,
+    dirs: u64,
+    _priority: Option<LowIoPriority>,
+}
+
+impl DirPacer {
+    /// Call before reading each directory; sleeps if the walk is ahead of the rate
+    pub fn tick(&mut self) {
+        let Some(max) = self.max_dirs_per_sec else {
+            return;
+        };
+        self.dirs += 1;
+        let due = Duration::from_secs_f64(self.dirs as f64 / max as f64);
+        if let Some(ahead) = due.checked_sub(self.started.elapsed()) {
+            std::thread::sleep(ahead);
+        }
+    }
+}
+
+/// Marks the current thread's disk I/O as throttled until dropped
+struct LowIoPriority {
+    #[cfg(target_os = "macos")]
+    previous: Option<libc::c_int>,
+}
+
+#[cfg(target_os = "macos")]
+mod iopolicy {
+    use libc::c_int;
+
+    // <sys/resource.h>
+    pub const IOPOL_TYPE_DISK: c_int = 0;
+    pub const IOPOL_SCOPE_THREAD: c_int = 1;
+    pub const IOPOL_THROTTLE: c_int = 3;
+
+    extern "C" {
+        pub fn getiopolicy_np(iotype: c_int, scope: c_int) -> c_int;
+        pub fn setiopolicy_np(iotype: c_int, scope: c_int, policy: c_int) -> c_int;
+    }
+}
+
+impl LowIoPriority {
+    #[cfg(target_os = "macos")]
+    fn lower() -> Self {
+        use iopolicy::*;
+        // SAFETY: both calls only read or change the calling thread's I/O policy
+        let previous = unsafe { getiopolicy_np(IOPOL_TYPE_DISK, IOPOL_SCOPE_THREAD) };
+        let lowered = unsafe { setiopolicy_np(IOPOL_TYPE_DISK, IOPOL_SCOPE_THREAD, IOPOL_THROTTLE) } == 0;
+        Self {
+            previous: (lowered && previous >= 0).then_some(previous),
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    fn lower() -> Self {
+        Self {}
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Drop for LowIoPriority {
+    fn drop(&mut self) {
+        // Walks run on shared blocking-pool threads; give the next task its old priority
+        if let Some(previous) = self.previous {
+            use iopolicy::*;
+            // SAFETY: restores the policy read in `lower` on the same thread
+            unsafe { setiopolicy_np(IOPOL_TYPE_DISK, IOPOL_SCOPE_THREAD, previous) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_throttle_never_sleeps() {
+        for throttle in [ScanThrottle::default(), ScanThrottle { max_dirs_per_sec: Some(0) }] {
+            assert!(!throttle.is_enabled());
+            let started = Instant::now();
+            let mut pacer = throttle.start();
+            for _ in 0..10_000 {
+                pacer.tick();
+            }
+            assert!(started.elapsed() < Duration::from_millis(100));
+        }
+    }
+
+    #[test]
+    fn test_pacer_holds_the_rate() {
+        let throttle = ScanThrottle { max_dirs_per_sec: Some(100) };
+        let started = Instant::now();
+        let mut pacer = throttle.start();
+        for _ in 0..20 {
+            pacer.tick();
+        }
+        assert!(started.elapsed() >= Duration::from_millis(200));
+    }
+}
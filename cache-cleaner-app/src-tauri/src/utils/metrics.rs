@@ -0,0 +1,97 @@
+//! In-process counters for diagnosing performance.
+//!
+//! Plain atomics bumped at a few hot spots (the size walker, the scan result
+//! cache, docker invocations) and read back with `get_metrics`. Nothing here
+//! leaves the process; this is for debugging slow scans, not analytics.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A monotonically growing count, until `reset_metrics`
+pub struct Counter(AtomicU64);
+
+impl Counter {
+    const fn new() -> Self {
+        Self(AtomicU64::new(0))
+    }
+
+    pub fn add(&self, n: u64) {
+        self.0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn incr(&self) {
+        self.add(1);
+    }
+
+    fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn reset(&self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Directories read by the size walker
+pub static DIRECTORIES_WALKED: Counter = Counter::new();
+/// Bytes of regular files the size walker added up
+pub static BYTES_SCANNED: Counter = Counter::new();
+/// Full scans served from the scan result cache
+pub static SCAN_CACHE_HITS: Counter = Counter::new();
+/// Full scans that had to walk the disk
+pub static SCAN_CACHE_MISSES: Counter = Counter::new();
+/// `docker` processes started
+pub static DOCKER_COMMANDS_RUN: Counter = Counter::new();
+
+/// Counter values at one moment
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub directories_walked: u64,
+    pub bytes_scanned: u64,
+    pub scan_cache_hits: u64,
+    pub scan_cache_misses: u64,
+    pub docker_commands_run: u64,
+}
+
+pub fn get_metrics() -> MetricsSnapshot {
+    MetricsSnapshot {
+        directories_walked: DIRECTORIES_WALKED.get(),
+        bytes_scanned: BYTES_SCANNED.get(),
+        scan_cache_hits: SCAN_CACHE_HITS.get(),
+        scan_cache_misses: SCAN_CACHE_MISSES.get(),
+        docker_commands_run: DOCKER_COMMANDS_RUN.get(),
+    }
+}
+
+pub fn reset_metrics() {
+    for counter in [&DIRECTORIES_WALKED, &BYTES_SCANNED, &SCAN_CACHE_HITS, &SCAN_CACHE_MISSES, &DOCKER_COMMANDS_RUN] {
+        counter.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::filesystem::{calculate_dir_size_with, DEFAULT_MAX_WALK_DEPTH};
+    use crate::utils::io_throttle::ScanThrottle;
+    use std::fs;
+
+    #[test]
+    fn test_walk_updates_counters() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.bin"), [0u8; 100]).unwrap();
+        for sub in ["one", "two"] {
+            fs::create_dir(dir.path().join(sub)).unwrap();
+            fs::write(dir.path().join(sub).join("b.bin"), [0u8; 50]).unwrap();
+        }
+
+        // Other tests walk concurrently, so only lower bounds hold
+        let before = get_metrics();
+        let size = calculate_dir_size_with(dir.path(), DEFAULT_MAX_WALK_DEPTH, ScanThrottle::default(), true).unwrap();
+        let after = get_metrics();
+
+        assert_eq!(size, 200);
+        assert!(after.directories_walked - before.directories_walked >= 3);
+        assert!(after.bytes_scanned - before.bytes_scanned >= size);
+    }
+}
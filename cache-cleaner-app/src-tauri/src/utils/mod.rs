@@ -2,3 +2,14 @@ pub mod filesystem;
 pub mod permissions;
 pub mod access_tracker;
 pub mod concurrency;
+pub mod home;
+pub mod format;
+pub mod disk;
+pub mod power;
+pub mod hash;
+pub mod project_roots;
+pub mod io_throttle;
+pub mod command;
+pub mod size_estimate;
+pub mod preserve_globs;
+pub mod metrics;
@@ -1,9 +1,37 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
 use std::process::Command;
 
+/// Privacy & Security → Full Disk Access in System Settings
+pub const FULL_DISK_ACCESS_SETTINGS_URL: &str =
+    "x-apple.systempreferences:com.apple.preference.security?Privacy_AllFiles";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingCapability {
+    /// Protected caches such as Safari's cannot be read or cleaned
+    FullDiskAccess,
+    /// Nothing in the home directory can be scanned
+    HomeAccess,
+}
+
+/// What the app is missing and how the user can grant it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PermissionGuidance {
+    pub missing: Vec<MissingCapability>,
+    pub steps: Vec<String>,
+    /// Opened by `open_full_disk_access_settings`, when that would help
+    pub settings_url: Option<String>,
+}
+
 pub fn is_chrome_running() -> Result<bool> {
+    is_app_running("Google Chrome")
+}
+
+/// Whether a process with exactly this name is running
+pub fn is_app_running(process_name: &str) -> Result<bool> {
     let output = Command::new("pgrep")
-        .args(["-x", "Google Chrome"])
+        .args(["-x", process_name])
         .output()?;
     Ok(output.status.success())
 }
@@ -22,6 +50,43 @@ pub fn can_access_home() -> bool {
         .unwrap_or(false)
 }
 
+/// Open the Full Disk Access pane, e.g. after a clean failed with `TccDenied`
+pub fn open_full_disk_access_settings() -> Result<()> {
+    let status = Command::new("open").arg(FULL_DISK_ACCESS_SETTINGS_URL).status()?;
+    if !status.success() {
+        bail!("Could not open System Settings ({})", status);
+    }
+    Ok(())
+}
+
+pub fn request_permissions_with_guidance() -> PermissionGuidance {
+    guidance_for(has_full_disk_access(), can_access_home())
+}
+
+fn guidance_for(full_disk_access: bool, home_accessible: bool) -> PermissionGuidance {
+    let mut missing = Vec::new();
+    let mut steps = Vec::new();
+
+    if !full_disk_access {
+        missing.push(MissingCapability::FullDiskAccess);
+        steps.extend([
+            "Open System Settings → Privacy & Security → Full Disk Access".to_string(),
+            "Turn on Cache Cleaner, or add it with the + button if it is not listed".to_string(),
+            "Quit and reopen Cache Cleaner so the change takes effect".to_string(),
+        ]);
+    }
+    if !home_accessible {
+        missing.push(MissingCapability::HomeAccess);
+        steps.push("Make sure your home folder is readable by your user account".to_string());
+    }
+
+    PermissionGuidance {
+        settings_url: (!full_disk_access).then(|| FULL_DISK_ACCESS_SETTINGS_URL.to_string()),
+        missing,
+        steps,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,6 +104,20 @@ mod tests {
         let _result = has_full_disk_access();
     }
 
+    #[test]
+    fn test_guidance_when_full_disk_access_missing() {
+        let guidance = guidance_for(false, true);
+        assert_eq!(guidance.missing, vec![MissingCapability::FullDiskAccess]);
+        assert_eq!(guidance.settings_url.as_deref(), Some(FULL_DISK_ACCESS_SETTINGS_URL));
+        assert!(guidance.steps[0].contains("Full Disk Access"));
+        assert!(guidance.steps.iter().any(|step| step.contains("reopen")));
+
+        let granted = guidance_for(true, true);
+        assert!(granted.missing.is_empty());
+        assert!(granted.steps.is_empty());
+        assert!(granted.settings_url.is_none());
+    }
+
     #[test]
     fn test_can_access_home() {
         // Home directory should be accessible in test environment
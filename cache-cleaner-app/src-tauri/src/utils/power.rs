@@ -0,0 +1,57 @@
+use anyhow::{anyhow, Result};
+use std::process::Command;
+
+/// Whether the Mac is currently running on battery
+pub fn is_on_battery() -> Result<bool> {
+    let output = Command::new("pmset").args(["-g", "batt"]).output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "pmset failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    parse_power_source(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Whether scheduled work should wait for AC power.
+///
+/// An unreadable power state does not block anything.
+pub fn should_postpone_scheduled_work(run_on_battery: bool) -> bool {
+    !run_on_battery && is_on_battery().unwrap_or(false)
+}
+
+/// Read the "Now drawing from '…'" line of `pmset -g batt`
+fn parse_power_source(output: &str) -> Result<bool> {
+    let line = output
+        .lines()
+        .find(|l| l.contains("drawing from"))
+        .ok_or_else(|| anyhow!("pmset output has no power source"))?;
+    Ok(line.contains("'Battery Power'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ac_power() {
+        let output = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=4653155)\t100%; charged; 0:00 remaining present: true\n";
+        assert!(!parse_power_source(output).unwrap());
+    }
+
+    #[test]
+    fn test_parse_battery_power() {
+        let output = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t87%; discharging; 5:12 remaining present: true\n";
+        assert!(parse_power_source(output).unwrap());
+    }
+
+    #[test]
+    fn test_parse_desktop_without_battery() {
+        assert!(!parse_power_source("Now drawing from 'AC Power'\n").unwrap());
+    }
+
+    #[test]
+    fn test_parse_unexpected_output() {
+        assert!(parse_power_source("").is_err());
+    }
+}
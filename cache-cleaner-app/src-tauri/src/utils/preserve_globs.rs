@@ -0,0 +1,56 @@
+//! Files that survive any cache clean.
+//!
+//! `AppConfig::preserve_globs` lists patterns such as `**/*.keep` or
+//! `**/config.json`. They are matched against each file's path relative to
+//! the directory being emptied, and matching files are left in place.
+
+use crate::cache::config::AppConfig;
+use anyhow::Result;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::sync::OnceLock;
+
+static CONFIGURED: OnceLock<GlobSet> = OnceLock::new();
+
+/// Compile `patterns`, failing on the first invalid one
+pub fn build(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern)?);
+    }
+    Ok(builder.build()?)
+}
+
+/// The globs from the config, read once per process. Invalid patterns are
+/// logged and skipped rather than disabling the others.
+pub fn configured() -> &'static GlobSet {
+    CONFIGURED.get_or_init(|| {
+        let patterns = AppConfig::load().map(|c| c.preserve_globs).unwrap_or_default();
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &patterns {
+            match Glob::new(pattern) {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(e) => eprintln!("[Rust] ignoring preserve glob {:?}: {}", pattern, e),
+            }
+        }
+        builder.build().unwrap_or_else(|_| GlobSet::empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_globs_match_relative_paths() {
+        let globs = build(&["**/*.keep".to_string(), "**/config.json".to_string()]).unwrap();
+        assert!(globs.is_match(Path::new(".keep")));
+        assert!(globs.is_match(Path::new("a/b/.keep")));
+        assert!(globs.is_match(Path::new("config.json")));
+        assert!(globs.is_match(Path::new("tool/config.json")));
+        assert!(!globs.is_match(Path::new("tool/config.json.bak")));
+        assert!(build(&["a/[".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+//! Where the user's source projects are likely to live.
+//!
+//! Project-oriented scanners such as git maintenance fall back to these
+//! roots when none are passed: the usual folders below the home directory
+//! that exist on this Mac, plus anything listed in `project_roots` in the
+//! config.
+
+use crate::cache::config::AppConfig;
+use crate::utils::home::require_home;
+use std::path::{Path, PathBuf};
+
+/// Common project folders, relative to the home directory
+pub const DEFAULT_PROJECT_DIRS: &[&str] = &["Projects", "Code", "dev", "Developer", "src", "work", "go/src"];
+
+/// Existing default project folders plus the configured `project_roots`
+pub fn discover_default_roots() -> Vec<PathBuf> {
+    let Ok(home) = require_home() else {
+        return Vec::new();
+    };
+    let configured = AppConfig::load().map(|c| c.project_roots).unwrap_or_default();
+    discover_roots_in(&home, &configured)
+}
+
+fn discover_roots_in(home: &Path, configured: &[String]) -> Vec<PathBuf> {
+    let defaults = DEFAULT_PROJECT_DIRS.iter().map(|dir| home.join(dir));
+    let configured = configured.iter().map(|path| match path.strip_prefix("~/") {
+        Some(rest) => home.join(rest),
+        None => PathBuf::from(path),
+    });
+
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for root in defaults.chain(configured) {
+        if root.is_dir() && !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    #[test]
+    fn test_only_existing_dirs_returned() {
+        let home = create_test_dir();
+        fs::create_dir(home.path().join("Developer")).unwrap();
+        fs::create_dir_all(home.path().join("go/src")).unwrap();
+        // A file named like a project folder is not a root
+        fs::write(home.path().join("work"), b"").unwrap();
+
+        let roots = discover_roots_in(home.path(), &[]);
+        assert_eq!(roots, vec![home.path().join("Developer"), home.path().join("go/src")]);
+    }
+
+    #[test]
+    fn test_configured_root_included() {
+        let home = create_test_dir();
+        let elsewhere = create_test_dir();
+        fs::create_dir(home.path().join("Code")).unwrap();
+        fs::create_dir(home.path().join("clients")).unwrap();
+
+        let configured = vec![
+            "~/clients".to_string(),
+            elsewhere.path().display().to_string(),
+            "~/Code".to_string(),
+            "/nonexistent/projects".to_string(),
+        ];
+        let roots = discover_roots_in(home.path(), &configured);
+        assert_eq!(
+            roots,
+            vec![
+                home.path().join("Code"),
+                home.path().join("clients"),
+                elsewhere.path().to_path_buf(),
+            ]
+        );
+    }
+}
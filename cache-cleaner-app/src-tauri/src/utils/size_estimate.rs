@@ -0,0 +1,214 @@
+//! Sampled size estimates for very large directories.
+//!
+//! Walking every file of a cache with millions of entries (a big Cargo
+//! registry, a Gradle cache) dominates a scan. `estimate_dir_size_sampled`
+//! instead expands the top of the tree until it has enough subtrees, sizes a
+//! random sample of them exactly and extrapolates, reporting how far off the
+//! estimate may be at the requested confidence. Files met while expanding
+//! are counted exactly, so a few huge top-level files never skew the sample.
+
+use crate::utils::filesystem;
+use anyhow::Result;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Subtrees needed before sampling pays off; with fewer, `path` is sized exactly
+pub const SAMPLING_MIN_SUBTREES: usize = 64;
+/// Levels expanded at most while looking for enough subtrees
+const MAX_EXPAND_DEPTH: usize = 4;
+/// Subtrees always sized before the margin is trusted
+const MIN_SAMPLES: usize = 30;
+/// Sampling stops once the margin is this fraction of the estimate
+const TARGET_MARGIN: f64 = 0.05;
+
+/// Size and item count of a directory, exact or extrapolated from a sample
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeEstimate {
+    pub bytes: u64,
+    pub items: usize,
+    /// Relative margin of error on `bytes`, 0.0 when sized exactly
+    pub margin: f64,
+}
+
+impl SizeEstimate {
+    pub fn is_exact(&self) -> bool {
+        self.margin == 0.0
+    }
+}
+
+/// Estimated size of `path` and its relative margin of error at
+/// `confidence` (e.g. 0.95 for ±margin 95% of the time). Directories with
+/// fewer than `SAMPLING_MIN_SUBTREES` subtrees are sized exactly, margin 0.
+pub fn estimate_dir_size_sampled(path: &Path, confidence: f64) -> Result<(u64, f64)> {
+    let estimate = estimate_dir(path, confidence)?;
+    Ok((estimate.bytes, estimate.margin))
+}
+
+/// `estimate_dir_size_sampled`, also extrapolating the item count
+pub fn estimate_dir(path: &Path, confidence: f64) -> Result<SizeEstimate> {
+    let frontier = expand(path);
+    if frontier.subtrees.len() < SAMPLING_MIN_SUBTREES {
+        return Ok(SizeEstimate {
+            bytes: filesystem::calculate_dir_size_sync(path)?,
+            items: filesystem::count_items(path)?,
+            margin: 0.0,
+        });
+    }
+
+    let z = z_score(confidence);
+    let population = frontier.subtrees.len();
+    let mut order = frontier.subtrees;
+    shuffle(&mut order);
+
+    let mut sizes = Vec::new();
+    let mut sampled_items = 0usize;
+    for subtree in &order {
+        sizes.push(filesystem::calculate_dir_size_sync(subtree).unwrap_or(0) as f64);
+        sampled_items += filesystem::count_items(subtree).unwrap_or(0);
+        if sizes.len() >= MIN_SAMPLES && relative_margin(&sizes, population, z, frontier.bytes) <= TARGET_MARGIN {
+            break;
+        }
+    }
+
+    let scale = population as f64 / sizes.len() as f64;
+    Ok(SizeEstimate {
+        bytes: frontier.bytes + (sizes.iter().sum::<f64>() * scale).round() as u64,
+        items: frontier.items + (sampled_items as f64 * scale).round() as usize,
+        margin: relative_margin(&sizes, population, z, frontier.bytes),
+    })
+}
+
+/// Files counted exactly on the way down, and the subtrees left to size
+struct Frontier {
+    bytes: u64,
+    items: usize,
+    subtrees: Vec<PathBuf>,
+}
+
+/// Expand levels below `path` until there are enough subtrees to sample
+fn expand(path: &Path) -> Frontier {
+    let mut frontier = Frontier {
+        bytes: 0,
+        items: 0,
+        subtrees: vec![path.to_path_buf()],
+    };
+    let include_hidden = filesystem::include_hidden();
+    for _ in 0..MAX_EXPAND_DEPTH {
+        if frontier.subtrees.len() >= SAMPLING_MIN_SUBTREES {
+            break;
+        }
+        let mut next = Vec::new();
+        for dir in &frontier.subtrees {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if !include_hidden && filesystem::is_hidden(&entry.file_name()) {
+                    continue;
+                }
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                frontier.items += 1;
+                if file_type.is_dir() {
+                    next.push(entry.path());
+                } else if file_type.is_file() {
+                    frontier.bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                }
+            }
+        }
+        frontier.subtrees = next;
+    }
+    frontier
+}
+
+/// Margin of the extrapolated total over `population` subtrees, relative to
+/// the estimate, with the finite population correction for what was sampled
+fn relative_margin(sizes: &[f64], population: usize, z: f64, exact_bytes: u64) -> f64 {
+    let n = sizes.len() as f64;
+    if sizes.len() < 2 || sizes.len() >= population {
+        return 0.0;
+    }
+    let mean = sizes.iter().sum::<f64>() / n;
+    let variance = sizes.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let fpc = (1.0 - n / population as f64).sqrt();
+    let margin_bytes = z * population as f64 * (variance / n).sqrt() * fpc;
+    let estimate = exact_bytes as f64 + mean * population as f64;
+    if estimate > 0.0 {
+        margin_bytes / estimate
+    } else {
+        0.0
+    }
+}
+
+/// Two-sided normal critical value for `confidence`, using Abramowitz and
+/// Stegun 26.2.23 (error below 4.5e-4)
+fn z_score(confidence: f64) -> f64 {
+    let tail = (1.0 - confidence.clamp(0.5, 0.9999)) / 2.0;
+    let t = (-2.0 * tail.ln()).sqrt();
+    t - (2.515517 + 0.802853 * t + 0.010328 * t * t) / (1.0 + 1.432788 * t + 0.189269 * t * t + 0.001308 * t * t * t)
+}
+
+/// Fisher-Yates with a xorshift generator seeded per call
+fn shuffle<T>(items: &mut [T]) {
+    let mut state = RandomState::new().build_hasher().finish() | 1;
+    for i in (1..items.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        items.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_dir() -> TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    /// `subtrees` directories holding one file of 900-1099 bytes each
+    fn create_fixture(root: &Path, subtrees: usize) {
+        for i in 0..subtrees {
+            let dir = root.join(format!("crate-{}", i));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("lib.rs"), vec![0u8; 900 + (i * 37) % 200]).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_z_score() {
+        assert!((z_score(0.95) - 1.96).abs() < 0.01);
+        assert!((z_score(0.99) - 2.576).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_sampled_estimate_within_margin() {
+        let root = create_test_dir();
+        create_fixture(root.path(), 400);
+        let exact = filesystem::calculate_dir_size_sync(root.path()).unwrap();
+
+        // Near-certain confidence so the check itself is not flaky
+        let estimate = estimate_dir(root.path(), 0.9999).unwrap();
+        assert!(!estimate.is_exact());
+        assert!(estimate.margin < 0.1, "margin {}", estimate.margin);
+        let error = (estimate.bytes as f64 - exact as f64).abs() / estimate.bytes as f64;
+        assert!(error <= estimate.margin, "error {} over margin {}", error, estimate.margin);
+        assert_eq!(estimate.items, 800);
+    }
+
+    #[test]
+    fn test_small_directory_sized_exactly() {
+        let root = create_test_dir();
+        create_fixture(root.path(), 10);
+        let exact = filesystem::calculate_dir_size_sync(root.path()).unwrap();
+
+        let (bytes, margin) = estimate_dir_size_sampled(root.path(), 0.95).unwrap();
+        assert_eq!(bytes, exact);
+        assert_eq!(margin, 0.0);
+    }
+}
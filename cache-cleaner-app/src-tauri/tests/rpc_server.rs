@@ -0,0 +1,48 @@
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+/// Run `rpc-server` against an empty home directory with `input` on stdin
+fn run_rpc(input: &str) -> Vec<Value> {
+    let home = tempfile::tempdir().unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rpc-server"))
+        .env("HOME", home.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let responses = BufReader::new(child.stdout.take().unwrap())
+        .lines()
+        .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+        .collect();
+    assert!(child.wait().unwrap().success());
+    responses
+}
+
+#[test]
+fn test_scan_caches_response() {
+    let responses = run_rpc("{\"id\": 1, \"method\": \"scan_caches\", \"params\": {}}\n");
+    assert_eq!(responses.len(), 1);
+
+    let response = &responses[0];
+    assert_eq!(response["id"], 1);
+    assert!(response.get("error").is_none(), "{}", response);
+    assert!(response["result"]["caches"].is_array());
+    assert!(response["result"]["errors"].is_array());
+}
+
+#[test]
+fn test_errors_keep_request_id() {
+    let responses = run_rpc(
+        "{\"id\": \"a\", \"method\": \"no_such_method\"}\nnot json\n{\"id\": 2, \"method\": \"get_cache_size\", \"params\": {\"cache_type\": \"bogus\"}}\n",
+    );
+    assert_eq!(responses.len(), 3);
+    assert_eq!(responses[0]["id"], "a");
+    assert_eq!(responses[0]["error"], "Unknown method: no_such_method");
+    assert!(responses[1]["id"].is_null());
+    assert!(responses[1]["error"].as_str().unwrap().starts_with("Invalid request"));
+    assert_eq!(responses[2]["id"], 2);
+    assert!(responses[2].get("result").is_none());
+}